@@ -0,0 +1,88 @@
+//! Where every roll went, so that none can go missing without a trace.
+//!
+//! Every roll made or received is generated, and then ends up tallied into the counts, dropped
+//! on purpose, or rejected as invalid. Anything that changes how rolls flow has to keep these
+//! balanced.
+
+#[derive(Clone, Copy, Default)]
+pub struct Accounting {
+    /// Rolls made by the random number generator or received from outside, valid or not.
+    pub generated: u64,
+    /// Rolls added to the counts.
+    pub tallied: u64,
+    /// Valid rolls thrown away rather than counted.
+    pub dropped: u64,
+    /// Values received that weren't a face of the die.
+    pub invalid: u64,
+}
+
+impl Accounting {
+    /// Whether every roll generated has been tallied, dropped, or rejected.
+    pub fn is_balanced(&self) -> bool {
+        self.generated == self.tallied + self.dropped + self.invalid
+    }
+
+    /// Panic in debug builds if any roll is unaccounted for.
+    pub fn check(&self) {
+        debug_assert!(self.is_balanced(), "rolls unaccounted for: {self}");
+    }
+}
+
+impl std::fmt::Display for Accounting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} generated, {} tallied, {} dropped, {} invalid",
+            self.generated, self.tallied, self.dropped, self.invalid
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balances_when_every_roll_is_accounted_for() {
+        let accounting = Accounting {
+            generated: 10,
+            tallied: 7,
+            dropped: 2,
+            invalid: 1,
+        };
+        assert!(accounting.is_balanced());
+        accounting.check();
+        assert!(Accounting::default().is_balanced());
+    }
+
+    #[test]
+    fn unbalanced_either_way() {
+        let missing = Accounting {
+            generated: 10,
+            tallied: 9,
+            ..Accounting::default()
+        };
+        assert!(!missing.is_balanced());
+        let extra = Accounting {
+            generated: 1,
+            tallied: 1,
+            dropped: 1,
+            ..Accounting::default()
+        };
+        assert!(!extra.is_balanced());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(
+        expected = "rolls unaccounted for: 10 generated, 9 tallied, 0 dropped, 0 invalid"
+    )]
+    fn check_panics_when_unbalanced() {
+        Accounting {
+            generated: 10,
+            tallied: 9,
+            ..Accounting::default()
+        }
+        .check();
+    }
+}
@@ -0,0 +1,1107 @@
+//! The windowed app the `d20` binary runs: the event loop, command-line flags, the config
+//! file, and everything else around the chart itself.
+
+use super::*;
+use crate::config::Config;
+use clap::Parser as _;
+use notify::Watcher as _;
+use pixels::{Error, Pixels, PixelsBuilder, SurfaceTexture};
+use winit::dpi::{LogicalSize, PhysicalSize};
+use winit::event::{ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget};
+use winit::window::{Window, WindowBuilder, WindowId};
+
+/// Events sent to the event loop from other threads.
+enum UserEvent {
+    /// The config file was saved, boxed so this event is no bigger than the rest.
+    ConfigChanged(Box<Result<Config, config::Error>>),
+    /// Ctrl+C or a similar request from the terminal to shut down.
+    Interrupted,
+    Remote(remote::Command),
+}
+
+/// A window opened alongside the main one, drawing the shared counts in a view of its own.
+struct Detached {
+    // Declared before the window so its surface is dropped first.
+    pixels: Pixels,
+    window: Window,
+    /// Size of the frame, which only follows the window's when resizing succeeds.
+    size: PhysicalSize<u32>,
+    view: WindowView,
+}
+
+impl Detached {
+    /// Open a window showing `view`, made like the main window apart from its title bar, which
+    /// is always the system's.
+    fn open(
+        target: &EventLoopWindowTarget<UserEvent>,
+        args: &cli::Args,
+        view: WindowView,
+    ) -> Result<Self, OpenError> {
+        let window = WindowBuilder::new()
+            .with_title(APP_NAME)
+            .with_min_inner_size(LogicalSize::new(100., 100.))
+            .with_transparent(args.opacity.is_some())
+            .build(target)
+            .map_err(OpenError::Window)?;
+        let size = window.inner_size();
+        let surface_texture = SurfaceTexture::new(size.width, size.height, &window);
+        let clear_color = if args.opacity.is_some() {
+            pixels::wgpu::Color::TRANSPARENT
+        } else {
+            pixels::wgpu::Color::BLACK
+        };
+        let pixels = PixelsBuilder::new(size.width, size.height, surface_texture)
+            .clear_color(clear_color)
+            .build()
+            .map_err(OpenError::Pixels)?;
+        Ok(Self {
+            pixels,
+            window,
+            size,
+            view,
+        })
+    }
+
+    /// Match the surface and frame to the window's new `size`, unless it's been minimized.
+    fn resize(&mut self, size: PhysicalSize<u32>) {
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+        if let Err(err) = self.pixels.resize_surface(size.width, size.height) {
+            log_error("pixels.resize_surface", err);
+            return;
+        }
+        match self.pixels.resize_buffer(size.width, size.height) {
+            Ok(()) => self.size = size,
+            Err(err) => log_error("pixels.resize_buffer", err),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum OpenError {
+    Window(winit::error::OsError),
+    Pixels(Error),
+}
+
+impl std::fmt::Display for OpenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Window(_) => write!(f, "could not open window"),
+            Self::Pixels(_) => write!(f, "could not draw to window"),
+        }
+    }
+}
+
+impl std::error::Error for OpenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Window(err) => Some(err),
+            Self::Pixels(err) => Some(err),
+        }
+    }
+}
+
+/// Open the window and roll until it's closed, as set up by the command-line flags.
+pub fn run() -> Result<(), Error> {
+    env_logger::init();
+    let args = cli::Args::parse();
+
+    if args.bench {
+        bench::run(args.rolls, 20, args.seed.unwrap_or_else(rand::random));
+        return Ok(());
+    }
+
+    if args.tournament {
+        let seed = args.seed.unwrap_or_else(rand::random);
+        let entries = tournament::run(args.rolls, 20, seed);
+        tournament::print(&entries, args.rolls, seed);
+        if let Some(path) = &args.tournament_json {
+            if let Err(err) = tournament::save(path, &entries, args.rolls, seed) {
+                log_error("tournament::save", err);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if args.ascii {
+        print_ascii_chart(args.rolls, args.seed);
+        return Ok(());
+    }
+
+    if let Some(command) = &args.command {
+        run_command(command);
+        return Ok(());
+    }
+
+    let config_path = args
+        .config
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(config::DEFAULT_PATH));
+    let mut config = match Config::load(&config_path) {
+        Ok(config) => config,
+        Err(config::Error::Io(err))
+            if args.config.is_none() && err.kind() == std::io::ErrorKind::NotFound =>
+        {
+            Config::default()
+        }
+        Err(err) => {
+            log_error("Config::load", err);
+            std::process::exit(1);
+        }
+    };
+    // Checked before opening the window, so a directory that can't be written to fails fast.
+    let mut timelapse = match (&args.snapshot_dir, args.snapshot_every) {
+        (Some(dir), Some(interval)) => match record::Timelapse::spawn(dir, interval) {
+            Ok(timelapse) => Some(timelapse),
+            Err(err) => {
+                log_error("Timelapse::spawn", err);
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
+
+    let mut event_loop_builder = EventLoopBuilder::<UserEvent>::with_user_event();
+    platform::select_wm_backend(&mut event_loop_builder, args.wm_backend);
+    let event_loop = event_loop_builder.build();
+    platform::log_wm_backend(&event_loop);
+    let window = {
+        let mut builder = WindowBuilder::new();
+
+        builder = builder
+            .with_title(APP_NAME)
+            .with_min_inner_size(LogicalSize::new(100., 100.))
+            .with_decorations(!args.client_decorations)
+            .with_transparent(args.opacity.is_some());
+
+        #[cfg(debug_assertions)]
+        {
+            let monitor = event_loop
+                .available_monitors()
+                .last()
+                .expect("no monitor found");
+            let monitor_size = monitor.size();
+            builder = builder
+                .with_position(monitor.position())
+                .with_inner_size(LogicalSize::new(
+                    monitor_size.width as f64 * 0.85,
+                    monitor_size.height as f64 * 0.85,
+                ));
+        }
+
+        builder.build(&event_loop).unwrap()
+    };
+    if args.click_through {
+        if let Err(err) = window.set_cursor_hittest(false) {
+            warn!("clicks can't pass through the window here: {err}");
+        }
+    }
+
+    let inner_size = window.inner_size();
+    let mut pixels = {
+        let surface_texture = SurfaceTexture::new(inner_size.width, inner_size.height, &window);
+        let clear_color = if args.opacity.is_some() {
+            pixels::wgpu::Color::TRANSPARENT
+        } else {
+            pixels::wgpu::Color::BLACK
+        };
+        PixelsBuilder::new(inner_size.width, inner_size.height, surface_texture)
+            .clear_color(clear_color)
+            .build()?
+    };
+    let mut world = World::new(inner_size.width, inner_size.height);
+    if let Some(opacity) = args.opacity {
+        if has_alpha(pixels.render_texture_format()) {
+            world.set_opacity(Some(opacity));
+        } else {
+            warn!("the window's surface has no alpha channel; drawing it opaque");
+        }
+    }
+    world.set_scale_factor(window.scale_factor());
+    world.set_integer_render(args.integer_render);
+    world.set_title_bar(args.client_decorations);
+    #[cfg(debug_assertions)]
+    world.set_failures(inject::Failures::new(&args.inject_failure));
+    world.set_desktop_dark(window.theme() != Some(winit::window::Theme::Light));
+    let groups = match (args.group, &args.groups) {
+        (Some(size), _) => group::by_size(size, 20).map(Some),
+        (None, Some(spec)) => group::parse(spec, 20).map(Some),
+        (None, None) => Ok(None),
+    };
+    match groups {
+        Ok(groups) => world.set_groups(groups),
+        Err(err) => {
+            log_error("group::parse", err);
+            std::process::exit(1);
+        }
+    }
+    if args.snap_width {
+        let columns = world.column_count() as u32;
+        window.set_inner_size(PhysicalSize::new(
+            snapped_width(inner_size.width, columns),
+            inner_size.height,
+        ));
+    }
+    world.set_trail(args.trail.unwrap_or(0));
+    world.set_min_bar_height(args.min_bar_height.unwrap_or(0));
+    world.set_y_max(args.y_max);
+    if let Some(seed) = args.seed {
+        world.set_seed(seed);
+    }
+    world.set_stickiness(args.stickiness.unwrap_or(0.));
+    world.set_continuous(args.continuous);
+    if args.stdin {
+        world.set_source(source::Kind::Stdin);
+    }
+    world.set_paired(args.paired);
+    if let Some(faces) = &args.exclude {
+        if (1..=20).all(|face| faces.contains(&face)) {
+            error!("--exclude has to leave at least one face to roll");
+            std::process::exit(1);
+        }
+        world.set_excluded(faces);
+    }
+    world.set_convergence(args.stop_when_converged);
+    world.set_detection(args.detection);
+    world.set_fit(args.fit);
+    world.set_bias_alert(args.bias_alert);
+    if let Some(events) = &args.auto_capture {
+        world.set_auto_capture(events.clone());
+    }
+    #[cfg(feature = "audio")]
+    world.set_beep_on_bias(args.beep);
+    if let Some(rolls) = args.rounds {
+        world.set_rounds(rolls);
+    }
+    if let Some(budget) = args.budget {
+        world.set_budget(budget);
+    }
+    if let Some(ramp) = &args.ramp {
+        world.set_ramp(ramp.clone());
+    }
+    world.set_pixel_exact(args.pixel_exact);
+    world.set_update_rate(args.update_hz, args.interpolate);
+    world.set_easing(args.easing);
+    if let Some(sizes) = &args.overlay_sizes {
+        world.set_overlay(Overlay::new(sizes));
+    }
+    if args.set {
+        world.set_overlay(Overlay::set());
+    }
+    if let Some(runs) = args.ensemble {
+        world.set_ensemble(runs);
+    }
+    apply_config(&mut world, &args, &config, None);
+    if args.demo {
+        world.start_demo();
+    }
+    match snapshot::load(Path::new(snapshot::PATH)) {
+        Ok(snapshots) => world.set_snapshots(snapshots),
+        Err(err) => log_error("snapshot::load", err),
+    }
+    match history::load(Path::new(history::PATH)) {
+        Ok(history) => world.set_history(history),
+        Err(err) => log_error("history::load", err),
+    }
+    if let Some(path) = &args.import {
+        let counts = match import::load(path, 20) {
+            Ok(counts) => counts,
+            Err(err) => {
+                log_error("import::load", err);
+                std::process::exit(1);
+            }
+        };
+        if args.import_as_ghost {
+            world.add_imported_ghost(path.display().to_string(), counts);
+        } else {
+            let labels = world.labels.clone();
+            if let Err(err) = world.set_counts(&export::Counts { labels, counts }) {
+                log_error("World::set_counts", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(address) = args.listen {
+        let proxy = event_loop.create_proxy();
+        let send = move |command| proxy.send_event(UserEvent::Remote(command)).is_ok();
+        if let Err(err) = remote::listen(address, 20, send) {
+            log_error("remote::listen", err);
+        }
+    }
+    #[cfg(all(unix, feature = "control-socket"))]
+    if let Some(path) = &args.control_socket {
+        let proxy = event_loop.create_proxy();
+        let send = move |command| proxy.send_event(UserEvent::Remote(command)).is_ok();
+        if let Err(err) = remote::listen_unix(path, 20, send) {
+            log_error("remote::listen_unix", err);
+        }
+    }
+
+    if let Err(err) = handle_interrupts(event_loop.create_proxy()) {
+        log_error("handle_interrupts", err);
+    }
+
+    let mut recorder = match &args.record {
+        Some(dir) => match record::Recorder::spawn(dir) {
+            Ok(recorder) => Some(recorder),
+            Err(err) => {
+                log_error("Recorder::spawn", err);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let mut stills = args.auto_capture.is_some().then(record::Stills::spawn);
+
+    let config_watcher = if args.watch_config {
+        match watch_config(&config_path, event_loop.create_proxy()) {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                log_error("watch_config", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // The last width snapped from, so a window manager that won't allow the snapped width isn't
+    // asked over and over.
+    let mut snapped_from = None;
+
+    let mut detached: Vec<Detached> = Vec::new();
+
+    event_loop.run(move |event, target, control_flow| match event {
+        Event::WindowEvent { event, window_id } if window_id != window.id() => {
+            handle_detached_event(
+                &mut detached,
+                window_id,
+                event,
+                &mut world,
+                &args,
+                control_flow,
+            )
+        }
+        Event::WindowEvent { event, window_id } => match event {
+            WindowEvent::CloseRequested => {
+                if window_id == window.id() {
+                    close(&mut world, &args, control_flow, "window closed")
+                }
+            }
+            WindowEvent::Resized(_) => {
+                let inner_size = window.inner_size();
+                let resized = if world.failures.take(inject::Point::ResizeSurface) {
+                    Err(pixels::TextureError::TextureWidth(inner_size.width))
+                } else {
+                    pixels.resize_surface(inner_size.width, inner_size.height)
+                };
+                // Keep drawing at the old size; the next resize tries again.
+                if let Err(err) = resized {
+                    log_error("pixels.resize_surface", err);
+                    return;
+                }
+                fit_frame(&mut pixels, &mut world, inner_size);
+                if args.snap_width {
+                    let panel_width = world.stats_panel_width();
+                    let snapped =
+                        (snapped_width(world.width - panel_width, world.column_count() as u32)
+                            + panel_width)
+                            * world.pixel_ratio();
+                    if snapped != inner_size.width && snapped_from != Some(inner_size.width) {
+                        snapped_from = Some(inner_size.width);
+                        window.set_inner_size(PhysicalSize::new(snapped, inner_size.height));
+                    }
+                }
+                window.request_redraw()
+            }
+            WindowEvent::Moved(_) => (),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                world.set_scale_factor(scale_factor)
+            }
+            // Modifiers released while another window has focus are never reported, so forget
+            // them rather than leave them stuck down.
+            WindowEvent::Focused(false) => world.set_modifiers(ModifiersState::empty()),
+            WindowEvent::Focused(true) => (),
+            WindowEvent::ModifiersChanged(modifiers) => world.set_modifiers(modifiers),
+            WindowEvent::CursorMoved { position, .. } => {
+                let (x, y) = pixels
+                    .window_pos_to_pixel(position.into())
+                    .unwrap_or_else(|position| pixels.clamp_pixel_pos(position));
+                world.set_cursor_position(Some((x as u32, y as u32)));
+                world.hover_menu();
+            }
+            WindowEvent::CursorLeft { .. } => world.set_cursor_position(None),
+            // A click anywhere else closes the menu without doing anything more.
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } if world.menu.is_some() => {
+                if let Some(item) = world.menu_item_under_cursor() {
+                    choose_menu_item(item, &mut world, &args, control_flow);
+                }
+                world.menu = None;
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Right,
+                ..
+            } => world.open_menu(),
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } => match world.title_bar_hit() {
+                Some(TitleBarHit::Close) => close(&mut world, &args, control_flow, "window closed"),
+                Some(TitleBarHit::Drag) => {
+                    if let Err(err) = window.drag_window() {
+                        log_error("window.drag_window", err);
+                    }
+                }
+                None if world.modifiers == ModifiersState::CTRL => world.hide_hovered_column(),
+                None => world.inspect_heat_slice(),
+            },
+            WindowEvent::MouseInput { .. } => (),
+            WindowEvent::KeyboardInput {
+                device_id: _,
+                input,
+                is_synthetic: _,
+            } => match (input.state, input.virtual_keycode) {
+                // Everything else typed into a prompt arrives as `ReceivedCharacter`.
+                (ElementState::Pressed, Some(VirtualKeyCode::Escape)) if world.is_typing() => {
+                    world.cancel_typing()
+                }
+                _ if world.is_typing() => (),
+                (ElementState::Pressed, Some(key)) if world.menu.is_some() => match key {
+                    VirtualKeyCode::Up => world.move_menu_selection(-1),
+                    VirtualKeyCode::Down => world.move_menu_selection(1),
+                    VirtualKeyCode::Return | VirtualKeyCode::Space => {
+                        if let Some(menu) = world.menu.take() {
+                            choose_menu_item(menu.item(), &mut world, &args, control_flow);
+                        }
+                    }
+                    VirtualKeyCode::Escape => world.menu = None,
+                    _ => (),
+                },
+                _ if world.menu.is_some() => (),
+                // The key pressed to end the tour isn't also taken as a shortcut.
+                (ElementState::Pressed, Some(_)) if world.demo.is_some() => world.stop_demo(),
+                // Start on release so the key's own character isn't typed into the name.
+                (ElementState::Released, Some(VirtualKeyCode::N)) if world.modifiers.is_empty() => {
+                    world.start_snapshot_name()
+                }
+                (ElementState::Released, Some(VirtualKeyCode::B)) if world.modifiers.is_empty() => {
+                    world.start_bet_entry()
+                }
+                (ElementState::Released, Some(VirtualKeyCode::Semicolon))
+                    if world.modifiers.is_empty() =>
+                {
+                    world.start_note_entry()
+                }
+                (ElementState::Pressed, Some(key)) => {
+                    if let Some(action) = world.keymap.action(key, world.modifiers) {
+                        perform(action, &mut world, &args, control_flow);
+                    }
+                }
+                _ => (),
+            },
+            WindowEvent::ReceivedCharacter(c) => {
+                world.type_bet_face(c);
+                world.type_note(c);
+                if world.type_snapshot_name(c) {
+                    if let Err(err) = snapshot::save(Path::new(snapshot::PATH), world.snapshots()) {
+                        log_error("snapshot::save", err);
+                    }
+                }
+            }
+            // The accent color may have changed along with the theme, so it's looked up again.
+            WindowEvent::ThemeChanged(theme) => {
+                world.set_desktop_dark(theme == winit::window::Theme::Dark);
+            }
+            WindowEvent::Destroyed
+            | WindowEvent::DroppedFile(_)
+            | WindowEvent::HoveredFile(_)
+            | WindowEvent::HoveredFileCancelled
+            | WindowEvent::Ime(_)
+            | WindowEvent::CursorEntered { .. }
+            | WindowEvent::MouseWheel { .. }
+            | WindowEvent::TouchpadMagnify { .. }
+            | WindowEvent::SmartMagnify { .. }
+            | WindowEvent::TouchpadRotate { .. }
+            | WindowEvent::TouchpadPressure { .. }
+            | WindowEvent::AxisMotion { .. }
+            | WindowEvent::Touch(_)
+            | WindowEvent::Occluded(_) => (),
+        },
+        Event::MainEventsCleared => {
+            if world.is_update_due(Instant::now()) {
+                if let Some(segment) = world.roll_over(SystemTime::now()) {
+                    if let Err(err) = history::append(Path::new(history::PATH), &segment) {
+                        log_error("history::append", err);
+                    }
+                }
+                world.update();
+                for detached in &mut detached {
+                    world.tick_view(&mut detached.view, Instant::now());
+                }
+                if world.take_summary_pending() {
+                    let path = args.summary.as_deref();
+                    save_summary(&mut world, path.unwrap_or(Path::new(summary::DEFAULT_PATH)));
+                }
+            }
+            if world.take_window_requested() {
+                match Detached::open(target, &args, world.current_view()) {
+                    Ok(window) => detached.push(window),
+                    Err(err) => log_error("Detached::open", err),
+                }
+            }
+            if world.take_layout_changed() {
+                fit_frame(&mut pixels, &mut world, window.inner_size());
+            }
+            if let Some(title) = world.title.poll(Instant::now()) {
+                window.set_title(title);
+            }
+            window.request_redraw();
+            for detached in &detached {
+                detached.window.request_redraw();
+            }
+        }
+        Event::RedrawRequested(window_id) if window_id != window.id() => {
+            let Some(detached) = detached
+                .iter_mut()
+                .find(|detached| detached.window.id() == window_id)
+            else {
+                return;
+            };
+            let size = detached.size;
+            world.draw_detached(
+                detached.pixels.frame_mut(),
+                &mut detached.view,
+                size.width,
+                size.height,
+            );
+            if let Err(err) = detached.pixels.render() {
+                log_error("pixels.render", err);
+            }
+        }
+        Event::RedrawRequested(_) => {
+            world.draw(pixels.frame_mut());
+            // Capture here, between finishing the draw and the next update, so every recorded
+            // frame is a whole one.
+            if let Some(recorder) = &mut recorder {
+                recorder.capture(pixels.frame(), world.width, world.height);
+            }
+            if let Some(timelapse) = &mut timelapse {
+                timelapse.capture(pixels.frame(), world.width, world.height, Instant::now());
+            }
+            if let Some(stills) = &mut stills {
+                for path in world.take_captures() {
+                    stills.capture(path, pixels.frame(), world.width, world.height);
+                }
+            }
+            let rendered = if world.failures.take(inject::Point::Render) {
+                Err(pixels::Error::Surface(pixels::wgpu::SurfaceError::Lost))
+            } else {
+                pixels.render()
+            };
+            if let Err(err) = rendered {
+                log_error("pixels.render", err);
+                close(&mut world, &args, control_flow, "render failed");
+            }
+        }
+        Event::UserEvent(UserEvent::ConfigChanged(result)) => {
+            // Keep the watcher alive for as long as the loop runs.
+            let _ = &config_watcher;
+            match *result {
+                Ok(reloaded) => {
+                    apply_config(&mut world, &args, &reloaded, Some(&config));
+                    config = reloaded;
+                    match snapshot::load(Path::new(snapshot::PATH)) {
+                        Ok(snapshots) => world.set_snapshots(snapshots),
+                        Err(err) => log_error("snapshot::load", err),
+                    }
+                    let status = if world.pending_weights.is_some() {
+                        "Config reloaded; new weights take effect on the next reset"
+                    } else {
+                        "Config reloaded"
+                    };
+                    world.set_status(status.to_string(), Some(STATUS_TIMEOUT));
+                }
+                Err(err) => {
+                    // The previous config stays in effect until the file parses again.
+                    world.set_status(format!("{err}: {}", err.reason()), None);
+                    log_error("Config::load", err);
+                }
+            }
+        }
+        Event::UserEvent(UserEvent::Remote(command)) => match command {
+            remote::Command::Rolls(rolls) => {
+                for roll_key in rolls {
+                    world.record_external_roll(roll_key);
+                }
+            }
+            remote::Command::Reset => world.start_reset(),
+            remote::Command::Pause => world.set_paused(true),
+            remote::Command::Resume => world.set_paused(false),
+            remote::Command::Rate(rate) => world.set_rate(rate, world.frame_budget),
+            remote::Command::Seed(seed) => world.set_seed(seed),
+            remote::Command::Stats(reply) => {
+                // The client may have given up waiting, which is fine.
+                let _ = reply.send(world.stats_json());
+            }
+        },
+        Event::UserEvent(UserEvent::Interrupted) => {
+            info!("interrupted; shutting down");
+            close(&mut world, &args, control_flow, "interrupted");
+        }
+        Event::LoopDestroyed => {
+            if let Some(recorder) = recorder.take() {
+                recorder.finish();
+            }
+            if let Some(timelapse) = timelapse.take() {
+                timelapse.finish(pixels.frame(), world.width, world.height);
+            }
+        }
+        Event::NewEvents(_)
+        | Event::DeviceEvent { .. }
+        | Event::Suspended
+        | Event::Resumed
+        | Event::RedrawEventsCleared => (),
+    });
+}
+
+/// Handle `event` for the detached window `window_id`. Closing it, or switching its view, only
+/// affects that window; other shortcuts act on the shared counts as in the main window.
+fn handle_detached_event(
+    detached: &mut Vec<Detached>,
+    window_id: WindowId,
+    event: WindowEvent<'_>,
+    world: &mut World,
+    args: &cli::Args,
+    control_flow: &mut ControlFlow,
+) {
+    let Some(index) = detached
+        .iter()
+        .position(|detached| detached.window.id() == window_id)
+    else {
+        return;
+    };
+    match event {
+        // Dropping the window frees its surface along with it.
+        WindowEvent::CloseRequested => drop(detached.remove(index)),
+        WindowEvent::Resized(size) => detached[index].resize(size),
+        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+            detached[index].resize(*new_inner_size)
+        }
+        WindowEvent::Focused(false) => world.set_modifiers(ModifiersState::empty()),
+        WindowEvent::ModifiersChanged(modifiers) => world.set_modifiers(modifiers),
+        WindowEvent::KeyboardInput { input, .. } if !world.is_typing() && world.menu.is_none() => {
+            let (ElementState::Pressed, Some(key)) = (input.state, input.virtual_keycode) else {
+                return;
+            };
+            match world.keymap.action(key, world.modifiers) {
+                Some(Action::CycleView) => world.cycle_detached_view(&mut detached[index].view),
+                Some(Action::Close) => drop(detached.remove(index)),
+                Some(action) => perform(action, world, args, control_flow),
+                None => (),
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Carry out the action bound to a shortcut.
+fn perform(action: Action, world: &mut World, args: &cli::Args, control_flow: &mut ControlFlow) {
+    match action {
+        Action::Reset => world.start_reset(),
+        Action::SoftReset => world.soft_reset(),
+        Action::Pause => world.toggle_paused(),
+        Action::ToggleExpected => world.toggle_expected(),
+        Action::TogglePValue => world.toggle_p_value(),
+        Action::ToggleSmoothed => world.toggle_smoothed(),
+        Action::ToggleMirrored => world.toggle_mirrored(),
+        Action::ToggleBigTotal => world.toggle_big_total(),
+        Action::TogglePin => world.toggle_pin(),
+        Action::ToggleSorted => world.toggle_sorted(),
+        Action::ToggleDeviations => world.toggle_deviations(),
+        Action::ToggleTransitions => world.toggle_transitions(),
+        Action::ToggleColorblindPreview => world.toggle_colorblind_preview(),
+        Action::ToggleLogicalLayout => world.toggle_logical_layout(),
+        Action::CycleHighlights => world.cycle_highlight_pair(),
+        Action::ShowHidden => world.show_hidden(),
+        Action::PrintChart => world.print_chart(),
+        Action::SaveCounts => world.save_counts(Path::new(export::DEFAULT_PATH)),
+        Action::ExportConfig => world.export_config(),
+        Action::WriteReport => {
+            let path = args.report.as_deref();
+            world.write_report(path.unwrap_or(Path::new(report::DEFAULT_PATH)));
+        }
+        Action::WriteSvg => {
+            let path = args.svg.as_deref();
+            world.write_svg(path.unwrap_or(Path::new(svg::DEFAULT_PATH)));
+        }
+        Action::ToggleSpotlight => world.toggle_spotlight(),
+        Action::Raise if world.is_spotlighting() => world.adjust_spotlight(SPOTLIGHT_STEP),
+        Action::Lower if world.is_spotlighting() => world.adjust_spotlight(-SPOTLIGHT_STEP),
+        Action::Raise => world.adjust_dc(1),
+        Action::Lower => world.adjust_dc(-1),
+        Action::ToggleDc => world.toggle_dc(),
+        Action::CycleNotation => world.cycle_notation(),
+        Action::CycleView => world.cycle_view(),
+        Action::NewWindow => world.request_window(),
+        Action::DeleteLastNote => world.delete_last_note(),
+        Action::ToggleLegend => world.toggle_legend(),
+        Action::ToggleStats => world.toggle_stats(),
+        Action::CycleSnapshot => world.cycle_snapshot(),
+        Action::CycleSource => world.cycle_source(),
+        Action::OlderSegment => world.view_older_segment(),
+        Action::NewerSegment => world.view_newer_segment(),
+        Action::SpeedUp => world.speed_up(),
+        Action::SlowDown => world.slow_down(),
+        Action::Close => close(world, args, control_flow, "quit"),
+    }
+}
+
+/// Carry out `item`, chosen from the right-click menu.
+fn choose_menu_item(
+    item: menu::Item,
+    world: &mut World,
+    args: &cli::Args,
+    control_flow: &mut ControlFlow,
+) {
+    match item {
+        menu::Item::Pause => perform(Action::Pause, world, args, control_flow),
+        menu::Item::Reset => perform(Action::Reset, world, args, control_flow),
+        menu::Item::WriteReport => perform(Action::WriteReport, world, args, control_flow),
+        menu::Item::SaveCounts => perform(Action::SaveCounts, world, args, control_flow),
+        menu::Item::SaveSummary => {
+            let path = args.summary.as_deref();
+            let path = path.unwrap_or(Path::new(summary::DEFAULT_PATH));
+            save_summary(world, path);
+            world.set_status(
+                format!("Saved summary to {}", path.display()),
+                Some(STATUS_TIMEOUT),
+            );
+        }
+        menu::Item::SwitchView => perform(Action::CycleView, world, args, control_flow),
+        menu::Item::Quit => perform(Action::Close, world, args, control_flow),
+    }
+}
+
+/// Whether frames drawn in `format` keep their alpha, so the window can be translucent.
+fn has_alpha(format: pixels::wgpu::TextureFormat) -> bool {
+    use pixels::wgpu::TextureFormat;
+    matches!(
+        format,
+        TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8UnormSrgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8UnormSrgb
+            | TextureFormat::Rgba16Float
+            | TextureFormat::Rgb10a2Unorm
+    )
+}
+
+/// Resize the frame and lay the chart out again for a window of `size` physical pixels.
+fn fit_frame(pixels: &mut Pixels, world: &mut World, size: PhysicalSize<u32>) {
+    let (width, height) = world.frame_size(size);
+    pixels
+        .resize_buffer(width, height)
+        .expect("could not resize buffer");
+    world.set_size(width, height);
+}
+
+/// Finish up and stop the event loop, however the user asked to quit.
+fn close(world: &mut World, args: &cli::Args, control_flow: &mut ControlFlow, ending: &str) {
+    if *control_flow == ControlFlow::Exit {
+        return;
+    }
+    *control_flow = ControlFlow::Exit;
+    world.write_run_log(ending);
+    if let Some(path) = &args.summary {
+        save_summary(world, path);
+    }
+    if let Some(path) = &args.report {
+        world.write_report(path);
+    }
+    if let Some(path) = &args.svg {
+        world.write_svg(path);
+    }
+}
+
+/// Turn Ctrl+C and other requests from the terminal to shut down into `UserEvent::Interrupted`,
+/// so they close the window like anything else. A second request soon after the first exits
+/// right away, in case closing is stuck.
+fn handle_interrupts(proxy: EventLoopProxy<UserEvent>) -> Result<(), ctrlc::Error> {
+    let mut last_interrupt: Option<Instant> = None;
+    ctrlc::set_handler(move || {
+        let now = Instant::now();
+        let repeated = last_interrupt.is_some_and(|last| now - last < FORCE_EXIT_WINDOW);
+        if repeated || proxy.send_event(UserEvent::Interrupted).is_err() {
+            std::process::exit(130);
+        }
+        last_interrupt = Some(now);
+    })
+}
+
+/// Apply `config` to `world`, letting command-line flags take precedence.
+///
+/// When reloading, only what differs from the `previous` config is applied, so settings changed
+/// while running survive saving an unrelated change. New weights change what's rolled, so they
+/// wait for the next reset rather than mixing two dice's rolls.
+fn apply_config(world: &mut World, args: &cli::Args, config: &Config, previous: Option<&Config>) {
+    let starting = previous.is_none();
+    // Whether `changed` finds a difference from the previous config, or there isn't one.
+    let differs = |changed: &dyn Fn(&Config) -> bool| previous.is_none_or(changed);
+    if differs(&|previous| {
+        previous.rate != config.rate || previous.frame_budget_ms != config.frame_budget_ms
+    }) {
+        let rate = args.rate.or(config.rate).unwrap_or(config::DEFAULT_RATE);
+        let frame_budget_ms = args
+            .frame_budget_ms
+            .or(config.frame_budget_ms)
+            .unwrap_or(config::DEFAULT_FRAME_BUDGET_MS);
+        world.set_rate(rate, Duration::from_millis(frame_budget_ms));
+    }
+    if differs(&|previous| previous.stall_window != config.stall_window) {
+        world.set_stall_window(
+            args.stall_window
+                .or(config.stall_window)
+                .unwrap_or(config::DEFAULT_STALL_WINDOW),
+        );
+    }
+    if starting {
+        world.set_highlight_debounce(Duration::from_millis(args.highlight_debounce_ms));
+        world.set_first_face(if args.zero_indexed { 0 } else { 1 });
+    }
+    if differs(&|previous| previous.theme != config.theme || previous.hue != config.hue) {
+        let mut theme = config.theme.clone();
+        if let Some(text_color) = args.text_color {
+            theme.text = text_color;
+        }
+        world.set_theme(theme);
+        world.set_follow_accent(args.theme == cli::ThemeSource::Accent);
+        let hue = args.hue.or(match args.theme {
+            cli::ThemeSource::Config => config.hue,
+            cli::ThemeSource::Accent => None,
+            cli::ThemeSource::Seed => Some(world.seed_hue()),
+        });
+        world.set_bar_hue(hue);
+    }
+    if differs(&|previous| previous.stats_width != config.stats_width) {
+        world.set_stats_width(config.stats_width.unwrap_or(config::DEFAULT_STATS_WIDTH));
+    }
+    if differs(&|previous| previous.labels != config.labels) {
+        world.set_labels(
+            config
+                .labels
+                .clone()
+                .unwrap_or_else(|| default_labels(world.first_face)),
+        );
+    }
+    if starting {
+        world.set_weights(config.weights.clone());
+    } else if differs(&|previous| previous.weights != config.weights) {
+        world.defer_weights(config.weights.clone());
+    }
+    if differs(&|previous| previous.tolerances != config.tolerances) {
+        world.set_tolerances(config.tolerances.clone());
+    }
+    if differs(&|previous| previous.rollover != config.rollover) {
+        world.set_rollover(config.rollover);
+    }
+    if differs(&|previous| previous.title != config.title) {
+        world.set_title_template(config.title.clone().unwrap_or_default());
+    }
+    if differs(&|previous| previous.keys != config.keys) {
+        world.set_keys(config.keys.clone());
+    }
+    if differs(&|previous| previous.dc != config.dc) {
+        world.set_dc(args.dc.or(config.dc));
+    }
+    if differs(&|previous| previous.notation != config.notation) {
+        world.set_notation(args.notation.or(config.notation).unwrap_or_default());
+    }
+    if differs(&|previous| previous.hidden != config.hidden) {
+        world.set_hidden(config.hidden.as_deref().unwrap_or_default());
+    }
+}
+
+/// Reload the config at `path` whenever it changes, sending the result to the event loop.
+fn watch_config(
+    path: &Path,
+    proxy: EventLoopProxy<UserEvent>,
+) -> notify::Result<notify::RecommendedWatcher> {
+    let watched_path = path.to_path_buf();
+    let mut watcher =
+        notify::recommended_watcher(move |result: notify::Result<notify::Event>| match result {
+            Ok(event) => {
+                let changed = (event.kind.is_create() || event.kind.is_modify())
+                    && event
+                        .paths
+                        .iter()
+                        .any(|path| path.file_name() == watched_path.file_name());
+                if changed {
+                    // Sending only fails once the event loop is gone, leaving nobody to tell.
+                    let _ = proxy.send_event(UserEvent::ConfigChanged(Box::new(Config::load(
+                        &watched_path,
+                    ))));
+                }
+            }
+            Err(err) => log_error("config watcher", err),
+        })?;
+
+    // Editors often save by replacing the file, so watch its directory rather than the file.
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    watcher.watch(directory, notify::RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// Carry out a subcommand working with exported counts, exiting with an error if it fails.
+fn run_command(command: &cli::Command) {
+    let load = |path: &Path| {
+        export::load(path).unwrap_or_else(|err| {
+            log_error("export::load", err);
+            std::process::exit(1);
+        })
+    };
+    match command {
+        cli::Command::Stats { file } => {
+            let counts = load(file);
+            let metrics = Metrics::compute(&counts.counts);
+            print!(
+                "{}",
+                ascii::render(&counts.counts, &counts.labels, 1, ascii::terminal_width())
+            );
+            for line in summary::lines(&metrics, 1) {
+                println!("{line}");
+            }
+            if metrics.total > 0 {
+                println!("p-value: {:.6}", metrics.p_value());
+            }
+        }
+        cli::Command::Merge { files, output } => {
+            let mut merged = load(&files[0]);
+            for file in &files[1..] {
+                if let Err(err) = merged.merge(&load(file)) {
+                    error!("could not merge {}", file.display());
+                    log_error("Counts::merge", err);
+                    std::process::exit(1);
+                }
+            }
+            if let Err(err) = export::save(output, &merged) {
+                log_error("export::save", err);
+                std::process::exit(1);
+            }
+        }
+        cli::Command::Render { file, output, size } => {
+            let counts = load(file);
+            let (width, height) = *size;
+            let mut world = World::new(width, height);
+            if let Err(err) = world.set_counts(&counts) {
+                log_error("World::set_counts", err);
+                std::process::exit(1);
+            }
+            let mut frame = world
+                .theme
+                .background
+                .repeat(width as usize * height as usize);
+            world.draw(&mut frame);
+            if let Err(err) = summary::save(output, width, height, &frame) {
+                log_error("summary::save", err);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Run a single headless simulation and print it as a chart.
+fn print_ascii_chart(rolls: u64, seed: Option<u64>) {
+    // The world only needs a size to draw, which this never does.
+    let mut world = World::new(0, 0);
+    if let Some(seed) = seed {
+        world.set_seed(seed);
+    }
+    for _ in 0..rolls {
+        world.roll_once();
+    }
+    print!("{}", world.ascii_chart(ascii::terminal_width()));
+    world.write_run_log("batch finished");
+}
+
+impl World {
+    /// Size of the frame to draw for a window of `size` physical pixels.
+    fn frame_size(&self, size: PhysicalSize<u32>) -> (u32, u32) {
+        let ratio = self.pixel_ratio();
+        ((size.width / ratio).max(1), (size.height / ratio).max(1))
+    }
+
+    /// The settings currently in effect, including any changed since they were loaded.
+    fn config(&self) -> Config {
+        Config {
+            // Bake in the speed multiplier so the export runs at the speed shown.
+            rate: Some((self.rolls_per_frame as f64 * self.speed_multiplier()).round() as u64),
+            frame_budget_ms: Some(self.frame_budget.as_millis() as u64),
+            stall_window: Some(self.stall_window),
+            stats_width: Some(self.stats_width),
+            labels: Some(self.labels.clone())
+                .filter(|labels| *labels != default_labels(self.first_face)),
+            weights: self.configured_weights.clone(),
+            tolerances: self.tolerances.clone(),
+            rollover: self.rollover,
+            dc: self.show_dc.then_some(self.dc),
+            notation: Some(self.notation),
+            hidden: Some(
+                (1..=self.roll_counts.len())
+                    .filter(|&face| self.hidden[face - 1])
+                    .collect::<Vec<_>>(),
+            )
+            .filter(|hidden| !hidden.is_empty()),
+            title: Some(self.title.template().clone()),
+            keys: self.keys.clone(),
+            theme: self.theme.clone(),
+            hue: self.bar_hue,
+        }
+    }
+
+    /// Save the settings currently in effect so they can be loaded again with `--config`.
+    fn export_config(&mut self) {
+        let path = Path::new(config::EXPORT_PATH);
+        let saved = if self.failures.take(inject::Point::ExportIo) {
+            Err(config::Error::Write(inject::io_error()))
+        } else {
+            self.config().save(path)
+        };
+        match saved {
+            Ok(()) => {
+                info!("exported config to {}", path.display());
+                self.set_status(
+                    format!("Exported config to {}", path.display()),
+                    Some(STATUS_TIMEOUT),
+                );
+            }
+            Err(err) => {
+                self.set_status(format!("{err}: {}", err.reason()), Some(STATUS_TIMEOUT));
+                log_error("Config::save", err);
+            }
+        }
+    }
+
+    fn set_modifiers(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
+
+    /// Bind each action in `keys` to its chord in place of the default.
+    fn set_keys(&mut self, keys: BTreeMap<Action, keymap::Chord>) {
+        self.keymap = Keymap::new(&keys);
+        self.keys = keys;
+    }
+}
@@ -0,0 +1,173 @@
+//! Terminal rendering of the roll counts, for when there's no window to draw in.
+
+use crate::metrics::Metrics;
+
+/// Terminals at least this many columns wide get horizontal bars with labels.
+const HORIZONTAL_MIN_WIDTH: usize = 80;
+
+/// Rows in the vertical chart.
+const VERTICAL_ROWS: usize = 20;
+
+/// Partial blocks for horizontal bars, in eighths from empty to full.
+const HORIZONTAL_BLOCKS: [char; 9] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// Partial blocks for vertical bars, in eighths from empty to full.
+const VERTICAL_BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Width of the terminal, as reported by the shell, falling back to a classic 80 columns.
+pub fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80)
+}
+
+/// Render a bar chart of `counts`, for a die with as many faces, followed by summary
+/// statistics. Horizontal bars are named by `labels`; vertical ones, which have a single column
+/// each, by face number, counting from `first_face`.
+pub fn render(
+    counts: &[u64],
+    labels: &[String],
+    first_face: usize,
+    terminal_width: usize,
+) -> String {
+    let metrics = Metrics::compute(counts);
+    let mut output = if terminal_width >= HORIZONTAL_MIN_WIDTH {
+        render_horizontal(counts, labels, &metrics, terminal_width)
+    } else {
+        render_vertical(counts, &metrics, first_face)
+    };
+    output.push('\n');
+    output.push_str(&render_summary(counts, &metrics, first_face));
+    output
+}
+
+fn render_horizontal(
+    counts: &[u64],
+    labels: &[String],
+    metrics: &Metrics,
+    terminal_width: usize,
+) -> String {
+    let label_width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let count_width = metrics.max.to_string().len();
+    // "<label> │<bar> <count> (<share>%)"
+    let bar_width = terminal_width
+        .saturating_sub(label_width + count_width + 14)
+        .max(1);
+
+    let mut output = String::new();
+    for (roll_key, &count) in counts.iter().enumerate() {
+        let eighths = (metrics.bar_fraction(count) * (bar_width * 8) as f64).round() as usize;
+        let mut bar = "█".repeat(eighths / 8);
+        if !eighths.is_multiple_of(8) {
+            bar.push(HORIZONTAL_BLOCKS[eighths % 8]);
+        }
+        let padding = bar_width - bar.chars().count();
+        output.push_str(&format!(
+            "{:>label_width$} │{bar}{:padding$} {count:>count_width$} ({:5.2}%)\n",
+            labels[roll_key],
+            "",
+            metrics.shares[roll_key] * 100.,
+        ));
+    }
+    output
+}
+
+fn render_vertical(counts: &[u64], metrics: &Metrics, first_face: usize) -> String {
+    let heights: Vec<usize> = counts
+        .iter()
+        .map(|&count| (metrics.bar_fraction(count) * (VERTICAL_ROWS * 8) as f64).round() as usize)
+        .collect();
+
+    let mut output = String::new();
+    for row in (0..VERTICAL_ROWS).rev() {
+        output.push('│');
+        for &eighths in &heights {
+            let filled = eighths.saturating_sub(row * 8).min(8);
+            output.push(VERTICAL_BLOCKS[filled]);
+            output.push(' ');
+        }
+        output.push('\n');
+    }
+    output.push('└');
+    output.push_str(&"──".repeat(counts.len()));
+    output.push('\n');
+
+    // Face numbers are written downwards, one digit per line, so each fits a single column.
+    let labels: Vec<String> = (first_face..first_face + counts.len())
+        .map(|face| face.to_string())
+        .collect();
+    let label_rows = labels.iter().map(String::len).max().unwrap_or(0);
+    for digit in 0..label_rows {
+        output.push(' ');
+        for label in &labels {
+            let pad = label_rows - label.len();
+            let c = if digit < pad {
+                ' '
+            } else {
+                label.as_bytes()[digit - pad] as char
+            };
+            output.push(c);
+            output.push(' ');
+        }
+        output.push('\n');
+    }
+    output
+}
+
+fn render_summary(counts: &[u64], metrics: &Metrics, first_face: usize) -> String {
+    let mut lines = vec![
+        ("rolls", metrics.total.to_string()),
+        ("mean", format!("{:.4}", metrics.mean_face(first_face))),
+        (
+            "expected mean",
+            format!(
+                "{:.4}",
+                first_face as f64 + counts.len().saturating_sub(1) as f64 / 2.
+            ),
+        ),
+    ];
+    if let (Some(winner), Some(loser)) = (metrics.winner(), metrics.loser()) {
+        let most = format!("{} ({})", winner + first_face, metrics.max);
+        lines.push(("most rolled", most));
+        let least = format!("{} ({})", loser + first_face, metrics.min);
+        lines.push(("least rolled", least));
+    }
+    lines
+        .iter()
+        .map(|(name, value)| format!("{name:<14}{value}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_a_d6_numbered_from_zero() {
+        let counts = [1, 2, 3, 4, 5, 6];
+        let chart = render(&counts, &[], 0, 40);
+        let bars: Vec<&str> = chart.lines().take(VERTICAL_ROWS).collect();
+        assert!(bars
+            .iter()
+            .all(|row| row.chars().count() == 1 + 2 * counts.len()));
+        assert!(chart.contains(" 0 1 2 3 4 5 \n"));
+        assert!(chart.contains("expected mean 2.5000\n"));
+        assert!(chart.contains("most rolled   5 (6)\n"));
+    }
+
+    #[test]
+    fn labels_each_horizontal_bar() {
+        let labels: Vec<String> = (1..=6).map(|face| face.to_string()).collect();
+        let chart = render(&[0, 0, 0, 0, 0, 6], &labels, 1, 80);
+        let bars: Vec<&str> = chart.lines().take_while(|line| !line.is_empty()).collect();
+        assert_eq!(bars.len(), 6);
+        assert!(bars[5].starts_with("6 │████"));
+        assert!(bars[5].ends_with("6 (100.00%)"));
+        assert!(chart.contains("expected mean 3.5000\n"));
+    }
+}
@@ -0,0 +1,117 @@
+//! Headless throughput measurement of the roll loop.
+
+use crate::metrics::Metrics;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::time::Instant;
+
+/// Rolls made with each shard's RNG. Shards, not threads, own the seeds, so the counts for a
+/// given seed don't depend on how many threads the machine has.
+const SHARD_ROLLS: u64 = 1 << 20;
+
+/// Roll `rolls` dice with `sides` faces on every available thread, each accumulating into its
+/// own counts.
+pub fn roll_parallel(rolls: u64, sides: usize, seed: u64) -> Vec<u64> {
+    (0..shard_count(rolls))
+        .into_par_iter()
+        .fold(
+            || vec![0; sides],
+            |mut counts, shard| {
+                roll_shard(&mut counts, shard, rolls, seed);
+                counts
+            },
+        )
+        .reduce(|| vec![0; sides], add_counts)
+}
+
+/// Make exactly the rolls `roll_parallel` would, one shard after another on this thread.
+pub fn roll_serial(rolls: u64, sides: usize, seed: u64) -> Vec<u64> {
+    let mut counts = vec![0; sides];
+    for shard in 0..shard_count(rolls) {
+        roll_shard(&mut counts, shard, rolls, seed);
+    }
+    counts
+}
+
+fn shard_count(rolls: u64) -> u64 {
+    rolls.div_ceil(SHARD_ROLLS)
+}
+
+/// Roll `shard`'s share of `rolls` into `counts`.
+fn roll_shard(counts: &mut [u64], shard: u64, rolls: u64, seed: u64) {
+    let mut rng = StdRng::seed_from_u64(shard_seed(seed, shard));
+    let shard_rolls = (rolls - shard * SHARD_ROLLS).min(SHARD_ROLLS);
+    for _ in 0..shard_rolls {
+        counts[rng.gen_range(0..counts.len())] += 1;
+    }
+}
+
+/// Derive a shard's seed from the base seed, spreading consecutive shards far apart.
+fn shard_seed(seed: u64, shard: u64) -> u64 {
+    seed ^ (shard + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+}
+
+fn add_counts(mut counts: Vec<u64>, other: Vec<u64>) -> Vec<u64> {
+    for (count, other) in counts.iter_mut().zip(other) {
+        *count += other;
+    }
+    counts
+}
+
+/// Time `rolls` rolls on one thread and on all of them, and print how they compare.
+pub fn run(rolls: u64, sides: usize, seed: u64) {
+    let started = Instant::now();
+    // Only timed here; the tests check it matches the parallel counts.
+    roll_serial(rolls, sides, seed);
+    let serial_elapsed = started.elapsed();
+
+    let started = Instant::now();
+    let parallel = roll_parallel(rolls, sides, seed);
+    let parallel_elapsed = started.elapsed();
+
+    let rate = |elapsed: std::time::Duration| rolls as f64 / elapsed.as_secs_f64().max(1e-9);
+    println!("seed          {seed}");
+    println!(
+        "serial        {rolls} rolls in {serial_elapsed:.2?} ({:.0} rolls/s)",
+        rate(serial_elapsed)
+    );
+    println!(
+        "parallel      {rolls} rolls in {parallel_elapsed:.2?} ({:.0} rolls/s, {} threads)",
+        rate(parallel_elapsed),
+        rayon::current_num_threads()
+    );
+    println!(
+        "speedup       {:.2}x",
+        serial_elapsed.as_secs_f64() / parallel_elapsed.as_secs_f64().max(1e-9)
+    );
+    println!(
+        "chi-square    {:.2}",
+        Metrics::compute(&parallel).chi_square()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_matches_serial() {
+        // One shard, then a full one and the start of the next, then a partial third.
+        for rolls in [100, SHARD_ROLLS + 1, 2 * SHARD_ROLLS + 7] {
+            assert_eq!(
+                roll_serial(rolls, 20, 42),
+                roll_parallel(rolls, 20, 42),
+                "{rolls} rolls"
+            );
+        }
+    }
+
+    #[test]
+    fn shards_cover_every_roll() {
+        assert_eq!(shard_count(SHARD_ROLLS), 1);
+        assert_eq!(shard_count(SHARD_ROLLS + 1), 2);
+        let counts = roll_serial(SHARD_ROLLS + 7, 6, 1);
+        assert_eq!(counts.iter().sum::<u64>(), SHARD_ROLLS + 7);
+    }
+}
@@ -0,0 +1,49 @@
+//! A betting game that shows how a payout plays out against the dice over many rolls.
+//!
+//! Every roll stakes one chip on the chosen face. A hit pays `sides` chips (the stake
+//! included), which is exactly fair for a fair die, so any drift in the bankroll is luck, or
+//! the die being loaded.
+
+pub struct Bet {
+    /// The face being bet on.
+    pub roll_key: usize,
+    /// Rolls made since the bet was placed.
+    pub rolls: u64,
+    /// How many of those rolls came up on the chosen face.
+    pub hits: u64,
+}
+
+impl Bet {
+    pub fn new(roll_key: usize) -> Self {
+        Self {
+            roll_key,
+            rolls: 0,
+            hits: 0,
+        }
+    }
+
+    /// Settle the stake on a roll of `roll_key`, returning the change to the bankroll.
+    pub fn settle(&mut self, roll_key: usize, sides: usize) -> i64 {
+        self.rolls += 1;
+        if roll_key == self.roll_key {
+            self.hits += 1;
+            sides as i64 - 1
+        } else {
+            -1
+        }
+    }
+
+    /// A summary of how the bet is going, for the overlay.
+    pub fn lines(&self, label: &str, sides: usize, bankroll: i64) -> Vec<String> {
+        let expected_hits = self.rolls as f64 / sides as f64;
+        vec![
+            format!("Betting 1 on {label} each roll, pays {sides}"),
+            format!(
+                "Won {} of {} (expected {expected_hits:.1})",
+                self.hits, self.rolls
+            ),
+            format!("Lost {}", self.rolls - self.hits),
+            format!("Bankroll: {bankroll:+}"),
+        ]
+    }
+}
@@ -0,0 +1,104 @@
+//! Noticing moments worth keeping during a run, such as a new longest streak, so the chart can
+//! be saved as each happens during long runs nobody is watching.
+
+use clap::ValueEnum;
+use std::path::PathBuf;
+
+/// Length a streak has to reach before a new longest one is worth capturing, since short
+/// streaks set new records constantly early on.
+const MIN_STREAK: u64 = 5;
+
+/// Rolls a face has to have led for before losing the lead is worth capturing.
+const MIN_LEAD: u64 = 10_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Event {
+    /// A new longest run of one face coming up time after time.
+    Streak,
+    /// A face taking the lead from one that had held it for a while.
+    Winner,
+    /// The counts starting or stopping looking biased.
+    Verdict,
+}
+
+pub struct Watch {
+    events: Vec<Event>,
+    /// The face that came up last, and how many times in a row.
+    streak: Option<(usize, u64)>,
+    longest_streak: u64,
+    /// The longest streak already captured.
+    captured_streak: u64,
+    /// The face with the most rolls, and the total when it took the lead.
+    leader: Option<(usize, u64)>,
+    /// Moments noticed since they were last taken, as the paths to save them to.
+    pending: Vec<PathBuf>,
+}
+
+impl Watch {
+    pub fn new(events: Vec<Event>) -> Self {
+        Self {
+            events,
+            streak: None,
+            longest_streak: 0,
+            captured_streak: 0,
+            leader: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Start watching afresh after the counts are cleared.
+    pub fn clear(&mut self) {
+        *self = Self::new(std::mem::take(&mut self.events));
+    }
+
+    pub fn record(&mut self, roll_key: usize) {
+        let streak = match self.streak {
+            Some((face, streak)) if face == roll_key => streak + 1,
+            _ => 1,
+        };
+        self.streak = Some((roll_key, streak));
+        self.longest_streak = self.longest_streak.max(streak);
+    }
+
+    /// Look for a new longest streak or leader once `total_rolls` have been made, with
+    /// `winner` holding the most.
+    pub fn check(&mut self, total_rolls: u64, winner: Option<usize>) {
+        if self.longest_streak > self.captured_streak && self.longest_streak >= MIN_STREAK {
+            self.captured_streak = self.longest_streak;
+            let moment = format!("longest-streak-{}", self.longest_streak);
+            self.notice(Event::Streak, total_rolls, &moment);
+        }
+        // Ties leave the lead where it was.
+        let Some(winner) = winner else {
+            return;
+        };
+        match self.leader {
+            Some((leader, _)) if leader == winner => (),
+            Some((_, since)) => {
+                if total_rolls - since >= MIN_LEAD {
+                    self.notice(Event::Winner, total_rolls, "new-winner");
+                }
+                self.leader = Some((winner, total_rolls));
+            }
+            None => self.leader = Some((winner, total_rolls)),
+        }
+    }
+
+    /// Note that the counts have started looking `biased`, or stopped, at `total_rolls`.
+    pub fn verdict_changed(&mut self, biased: bool, total_rolls: u64) {
+        let moment = if biased { "looks-biased" } else { "looks-fair" };
+        self.notice(Event::Verdict, total_rolls, moment);
+    }
+
+    /// Where to save each moment noticed since this was last called.
+    pub fn take(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.pending)
+    }
+
+    fn notice(&mut self, event: Event, total_rolls: u64, moment: &str) {
+        if self.events.contains(&event) {
+            self.pending
+                .push(PathBuf::from(format!("d20-{total_rolls:012}-{moment}.png")));
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! A running hash of every roll in order, so two runs can be checked for having rolled exactly
+//! the same sequence. Nothing checks it against a saved run yet, since runs can't be saved and
+//! replayed roll by roll; for now, compare the checksums of a seeded run and its repeat.
+
+/// 64-bit FNV-1a over one byte per roll, the zero-based face. Since it takes rolls one at a
+/// time, it depends only on the sequence, however the rolls were batched.
+#[derive(Clone, Copy)]
+pub struct RollHash(u64);
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl RollHash {
+    pub fn record(&mut self, roll_key: usize) {
+        self.0 ^= roll_key as u8 as u64;
+        self.0 = self.0.wrapping_mul(PRIME);
+    }
+}
+
+impl Default for RollHash {
+    fn default() -> Self {
+        Self(OFFSET_BASIS)
+    }
+}
+
+impl std::fmt::Display for RollHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_in_batches(rolls: &[usize], batch: usize) -> String {
+        let mut hash = RollHash::default();
+        for chunk in rolls.chunks(batch) {
+            for &roll_key in chunk {
+                hash.record(roll_key);
+            }
+        }
+        hash.to_string()
+    }
+
+    #[test]
+    fn matches_fnv_1a() {
+        // The published FNV-1a test vectors for "" and "a".
+        assert_eq!(RollHash::default().to_string(), "cbf29ce484222325");
+        let mut hash = RollHash::default();
+        hash.record(usize::from(b'a'));
+        assert_eq!(hash.to_string(), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn independent_of_batch_size() {
+        let rolls: Vec<usize> = (0..1_000).map(|roll| roll * 7 % 20).collect();
+        let whole = hash_in_batches(&rolls, rolls.len());
+        for batch in [1, 3, 64, 999] {
+            assert_eq!(hash_in_batches(&rolls, batch), whole, "batches of {batch}");
+        }
+    }
+
+    #[test]
+    fn depends_on_order() {
+        assert_ne!(hash_in_batches(&[1, 2], 2), hash_in_batches(&[2, 1], 2));
+    }
+}
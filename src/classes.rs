@@ -0,0 +1,97 @@
+//! Rolls tallied by classes of faces, such as odd against even. Defects in random number
+//! generators often hide in the low bits, where they skew these classes without standing out
+//! among the faces.
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Classing {
+    Parity,
+    Mod4,
+    Halves,
+}
+
+impl Classing {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Parity => "Odd and even faces",
+            Self::Mod4 => "Faces by value mod 4",
+            Self::Halves => "Low and high halves",
+        }
+    }
+
+    pub fn labels(self) -> &'static [&'static str] {
+        match self {
+            Self::Parity => &["odd", "even"],
+            Self::Mod4 => &["0 mod 4", "1 mod 4", "2 mod 4", "3 mod 4"],
+            Self::Halves => &["low", "high"],
+        }
+    }
+
+    /// The class the face at `roll_key` falls in, on a die with `sides` faces.
+    pub fn class(self, roll_key: usize, sides: usize) -> usize {
+        let face = roll_key + 1;
+        match self {
+            Self::Parity => 1 - face % 2,
+            Self::Mod4 => face % 4,
+            Self::Halves => usize::from(roll_key >= sides / 2),
+        }
+    }
+
+    /// Each class's share of the rolls, given each face's relative chance in `weights`.
+    pub fn expected_shares(self, weights: &[f64]) -> Vec<f64> {
+        let weight_sum: f64 = weights.iter().sum();
+        let mut shares = vec![0.; self.labels().len()];
+        for (roll_key, weight) in weights.iter().enumerate() {
+            shares[self.class(roll_key, weights.len())] += weight / weight_sum;
+        }
+        shares
+    }
+
+    /// The classing shown after this one, or `None` to go back to the faces.
+    pub fn next(classing: Option<Self>) -> Option<Self> {
+        match classing {
+            None => Some(Self::Parity),
+            Some(Self::Parity) => Some(Self::Mod4),
+            Some(Self::Mod4) => Some(Self::Halves),
+            Some(Self::Halves) => None,
+        }
+    }
+}
+
+/// Rolls in each class of every classing, kept up to date alongside the face counts.
+#[derive(Default)]
+pub struct Tallies {
+    pub parity: [u64; 2],
+    pub mod4: [u64; 4],
+    pub halves: [u64; 2],
+}
+
+impl Tallies {
+    pub fn record(&mut self, roll_key: usize, sides: usize) {
+        self.parity[Classing::Parity.class(roll_key, sides)] += 1;
+        self.mod4[Classing::Mod4.class(roll_key, sides)] += 1;
+        self.halves[Classing::Halves.class(roll_key, sides)] += 1;
+    }
+
+    pub fn get(&self, classing: Classing) -> &[u64] {
+        match classing {
+            Classing::Parity => &self.parity,
+            Classing::Mod4 => &self.mod4,
+            Classing::Halves => &self.halves,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Start over from `counts` of each face, as if each had been recorded one at a time.
+    pub fn rebuild(&mut self, counts: &[u64]) {
+        self.clear();
+        let sides = counts.len();
+        for (roll_key, &count) in counts.iter().enumerate() {
+            self.parity[Classing::Parity.class(roll_key, sides)] += count;
+            self.mod4[Classing::Mod4.class(roll_key, sides)] += count;
+            self.halves[Classing::Halves.class(roll_key, sides)] += count;
+        }
+    }
+}
@@ -0,0 +1,473 @@
+use crate::easing::Easing;
+#[cfg(debug_assertions)]
+use crate::inject;
+use crate::notation::Notation;
+use crate::ramp::Ramp;
+use crate::{capture, ensemble, report, summary, svg, theme};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Visualize the distribution of d20 rolls.
+#[derive(Parser)]
+#[command(version)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Print an ASCII chart of a single simulation to the terminal instead of opening a window.
+    #[arg(long)]
+    pub ascii: bool,
+
+    /// Measure how quickly rolls can be made, serially and across every core, instead of
+    /// opening a window.
+    #[arg(long)]
+    pub bench: bool,
+
+    /// Roll --rolls dice with every built-in random number generator and rank how fair they
+    /// look, instead of opening a window.
+    #[arg(long)]
+    pub tournament: bool,
+
+    /// Also write the tournament's ranking as JSON.
+    #[arg(long, value_name = "PATH", requires = "tournament")]
+    pub tournament_json: Option<PathBuf>,
+
+    /// Number of rolls to simulate each frame [default: 10000]
+    #[arg(long)]
+    pub rate: Option<u64>,
+
+    /// Longest time in milliseconds to spend rolling each frame; any rolls left over are made
+    /// up in later frames so the window stays responsive [default: 50]
+    #[arg(long)]
+    pub frame_budget_ms: Option<u64>,
+
+    /// Flag any face that hasn't come up in this many rolls, or 0 to never flag faces
+    /// [default: 200000]
+    #[arg(long)]
+    pub stall_window: Option<u64>,
+
+    /// Wait until a new winner or loser has held its place this many milliseconds before moving
+    /// the highlight to it, or 0 to move it at once
+    #[arg(long, value_name = "MS", default_value_t = 300)]
+    pub highlight_debounce_ms: u64,
+
+    /// Color for text and labels, as #rrggbb or #rrggbbaa [default: from the theme]
+    #[arg(long, value_parser = theme::parse_hex_color)]
+    pub text_color: Option<[u8; 4]>,
+
+    /// Leave fading trails behind the bars, bringing each pixel this much closer to the background
+    /// every frame [default step: 16]
+    #[arg(
+        long,
+        value_name = "STEP",
+        num_args = 0..=1,
+        default_missing_value = "16"
+    )]
+    pub trail: Option<u8>,
+
+    /// Draw every face's bar at least this many pixels tall, even before it's been rolled
+    /// [default: 0]
+    #[arg(long, value_name = "PX")]
+    pub min_bar_height: Option<u32>,
+
+    /// Pin the top of the chart to this many rolls instead of scaling to the tallest bar, so
+    /// heights can be compared over time; bars past it are clipped. Shift+P pins and unpins.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub y_max: Option<u64>,
+
+    /// Draw one bar for every SIZE faces in a row instead of one per face.
+    #[arg(long, value_name = "SIZE", conflicts_with = "groups")]
+    pub group: Option<usize>,
+
+    /// Draw one bar for each range of faces, such as 1-7,8-14,15-20, instead of one per face.
+    /// The ranges must cover every face exactly once.
+    #[arg(long, value_name = "RANGES")]
+    pub groups: Option<String>,
+
+    /// Where the bar colors come from.
+    #[arg(long, value_enum, default_value_t = ThemeSource::Config)]
+    pub theme: ThemeSource,
+
+    /// Shade the bars from this hue, in degrees around the color wheel, in place of whatever
+    /// --theme picks.
+    #[arg(long, value_name = "DEGREES", value_parser = parse_hue)]
+    pub hue: Option<f64>,
+
+    /// Window system backend to use on Linux and the BSDs.
+    #[arg(long, value_enum, default_value_t = WmBackend::Auto)]
+    pub wm_backend: WmBackend,
+
+    /// Update the counts at most this many times a second, drawing every frame in between;
+    /// --rate then counts rolls per update [default: once per frame]
+    #[arg(long, value_name = "HZ", value_parser = clap::value_parser!(u32).range(1..))]
+    pub update_hz: Option<u32>,
+
+    /// Between updates, draw the bars partway from where they were toward where they are, so
+    /// they grow smoothly when drawing faster than updating.
+    #[arg(long, requires = "update_hz")]
+    pub interpolate: bool,
+
+    /// How interpolated bars move between updates
+    #[arg(long, value_enum, default_value_t = Easing::Linear, requires = "interpolate")]
+    pub easing: Easing,
+
+    /// Keep the window's width a multiple of the number of bars, so they fill it edge to edge.
+    #[arg(long)]
+    pub snap_width: bool,
+
+    /// Draw our own title bar, for window managers that don't decorate the window.
+    #[arg(long)]
+    pub client_decorations: bool,
+
+    /// Make the whole window translucent, from 0 (invisible) to 1 (opaque), to float it over
+    /// another. Needs a compositor that supports transparent windows.
+    #[arg(long, value_name = "ALPHA", value_parser = parse_probability)]
+    pub opacity: Option<f64>,
+
+    /// Let clicks pass through the window to whatever is beneath, where the platform allows it.
+    /// The window can then only be closed from the keyboard or the terminal.
+    #[arg(long)]
+    pub click_through: bool,
+
+    /// Work out bar heights and animations with integer math only, for machines where floating
+    /// point is slow. Heights are exact rather than rounded, so they match the usual path to
+    /// within a pixel; animation steps round down instead of to the nearest roll. Curves,
+    /// flashes, the big total, and the color blindness preview, which need floating point, are
+    /// left off. Statistics in panels still use it.
+    #[arg(long)]
+    pub integer_render: bool,
+
+    /// Config file to load [default: d20.toml, if it exists]
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Reload the config file whenever it changes.
+    #[arg(long)]
+    pub watch_config: bool,
+
+    /// Read rolls from standard input, as face numbers separated by whitespace, instead of
+    /// simulating them.
+    #[arg(long)]
+    pub stdin: bool,
+
+    /// Accept rolls and commands, one per line, from local scripts over TCP at this address,
+    /// such as 127.0.0.1:4020.
+    #[arg(long, value_name = "ADDRESS")]
+    pub listen: Option<SocketAddr>,
+
+    /// Accept the same commands as --listen, such as `rate 5000` or `seed 42`, one per line on
+    /// a Unix socket at this path, for scripting a demo.
+    #[cfg(all(unix, feature = "control-socket"))]
+    #[arg(long, value_name = "PATH")]
+    pub control_socket: Option<PathBuf>,
+
+    /// Alongside rolls from --stdin or --listen, roll a fair die once for each of them and
+    /// outline its bars over theirs for comparison.
+    #[arg(long)]
+    pub paired: bool,
+
+    /// Run back-to-back rounds of this many rolls, starting over after each, and chart how the
+    /// rounds' chi-square statistics are distributed instead of the faces.
+    #[arg(long, value_name = "ROLLS")]
+    pub rounds: Option<u64>,
+
+    /// Roll dice of each of these sizes, such as 6,12,20, and chart each face's share of its
+    /// die's rolls as lines stretched across one axis instead of the d20, so dice of every size
+    /// can be compared.
+    #[arg(
+        long,
+        value_name = "SIDES",
+        value_delimiter = ',',
+        conflicts_with_all = ["rounds", "stdin"]
+    )]
+    pub overlay_sizes: Option<Vec<usize>>,
+
+    /// Roll a d4, d6, d8, d10, d12, and d20 together, each charted in its own cell of a grid,
+    /// instead of only the d20.
+    #[arg(long, conflicts_with_all = ["rounds", "overlay_sizes", "stdin"])]
+    pub set: bool,
+
+    /// Roll this many more dice alongside the main one, up to 16, each from its own seed
+    /// derived from --seed, and draw each one's shares as a faint line over the bars along with
+    /// their average in bold.
+    #[arg(
+        long,
+        value_name = "RUNS",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new()
+            .range(1..=ensemble::MAX_RUNS as u64),
+        conflicts_with_all = ["rounds", "overlay_sizes", "set", "stdin"]
+    )]
+    pub ensemble: Option<usize>,
+
+    /// Make an operation fail its first few times, to try out how the failure is handled:
+    /// render, resize-surface, or export-io, optionally with a count, such as export-io:3. Only
+    /// in debug builds.
+    #[cfg(debug_assertions)]
+    #[arg(long, value_name = "POINT[:TIMES]", value_parser = inject::parse)]
+    pub inject_failure: Vec<inject::Injection>,
+
+    /// Climb from one rate of simulated rolls per second to another over a while, such as
+    /// "10..1000000 over 60s", speeding up by the same factor every second and then holding the
+    /// final rate, in place of --rate.
+    #[arg(long, value_name = "RAMP", value_parser = Ramp::parse, conflicts_with = "rate")]
+    pub ramp: Option<Ramp>,
+
+    /// Keep every pixel one roll: whenever a column fills, set the whole chart aside as a page,
+    /// shown shaded along the bottom, and start filling again above it, instead of scaling the
+    /// bars down.
+    #[arg(long)]
+    pub pixel_exact: bool,
+
+    /// Make exactly this many simulated rolls and then stop, showing what's left as a bar
+    /// across the top. Resetting refills it.
+    #[arg(long, value_name = "ROLLS")]
+    pub budget: Option<u64>,
+
+    /// Loop through a captioned tour of what the chart can show, for leaving running at a booth.
+    /// Pressing any key ends the tour.
+    #[arg(long, conflicts_with_all = ["rounds", "overlay_sizes", "set", "stdin"])]
+    pub demo: bool,
+
+    /// How to write counts in labels, which F cycles through [default: from the config, or
+    /// plain]
+    #[arg(long, value_enum)]
+    pub notation: Option<Notation>,
+
+    /// Number the faces from 0 instead of 1, as on dice marked 0-9. Only the labels, the mean,
+    /// and the faces typed in to bet on change; the faces roll the same.
+    #[arg(long)]
+    pub zero_indexed: bool,
+
+    /// Show the chance of rolling at least this face, plainly and with advantage or
+    /// disadvantage [default: from the config]
+    #[arg(
+        long,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=20)
+    )]
+    pub dc: Option<usize>,
+
+    /// Once no face's share of the rolls has strayed more than this far from an even share for
+    /// five seconds straight, pause and save a summary, noting how many rolls it took.
+    #[arg(long, value_name = "EPS")]
+    pub stop_when_converged: Option<f64>,
+
+    /// Count down the rolls the configured weights should take before the chi-square test can
+    /// tell the die is loaded.
+    #[arg(long)]
+    pub detection: bool,
+
+    /// Fit a normal distribution to the counts, drawn as a line through the bars, and compare
+    /// its mean and spread with those of the configured weights, along with the
+    /// Kolmogorov–Smirnov distance between the counts and the weights.
+    #[arg(long)]
+    pub fit: bool,
+
+    /// Flash the window when the chi-square test first finds the counts unlikely to be from a
+    /// fair die, once there are enough rolls for the test to be trusted.
+    #[arg(long)]
+    pub bias_alert: bool,
+
+    /// Beep as well as flash when the counts start to look biased.
+    #[cfg(feature = "audio")]
+    #[arg(long, requires = "bias_alert")]
+    pub beep: bool,
+
+    /// Save the chart as a PNG in the working directory whenever one of these, such as
+    /// streak,verdict, happens, named for the number of rolls and what happened, so long
+    /// unattended runs keep their notable moments.
+    #[arg(long, value_name = "EVENTS", value_enum, value_delimiter = ',')]
+    pub auto_capture: Option<Vec<capture::Event>>,
+
+    /// Never roll these faces, such as 1,20, as if the die were missing them. Their columns are
+    /// hatched.
+    #[arg(
+        long,
+        value_name = "FACES",
+        value_delimiter = ',',
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=20)
+    )]
+    pub exclude: Option<Vec<usize>>,
+
+    /// Repeat the previous face with this chance, from 0 to 1, instead of rolling fresh, to
+    /// simulate a die whose rolls run in streaks.
+    #[arg(long, value_name = "P", value_parser = parse_probability)]
+    pub stickiness: Option<f64>,
+
+    /// Roll a fair die by sampling a number from 0 up to 1 and sorting it into one of the faces'
+    /// equal-width buckets, to check the generator's floats are uniform rather than its integers.
+    #[arg(long, conflicts_with_all = ["exclude", "stdin"])]
+    pub continuous: bool,
+
+    /// Seed the random number generator, so a run can be repeated.
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Save every frame drawn as a numbered PNG in this directory.
+    #[arg(long, value_name = "DIR")]
+    pub record: Option<PathBuf>,
+
+    /// Save the frame as a numbered PNG this often, such as every 10s, and once more on exit,
+    /// for assembling into a time-lapse. Snapshots the disk can't keep up with are skipped
+    /// rather than slowing the window down.
+    #[arg(
+        long,
+        value_name = "INTERVAL",
+        value_parser = parse_interval,
+        requires = "snapshot_dir"
+    )]
+    pub snapshot_every: Option<Duration>,
+
+    /// Directory to save --snapshot-every's PNGs in, which is created if needed.
+    #[arg(long, value_name = "DIR", requires = "snapshot_every")]
+    pub snapshot_dir: Option<PathBuf>,
+
+    /// On exit, save the final chart annotated with its statistics as a PNG [default path:
+    /// d20-summary.png]
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = summary::DEFAULT_PATH
+    )]
+    pub summary: Option<PathBuf>,
+
+    /// On exit, and whenever R is pressed, write a plain-text report of the seed, settings,
+    /// counts, and statistics needed to reproduce and interpret the run [default path:
+    /// d20-report.txt]
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = report::DEFAULT_PATH
+    )]
+    pub report: Option<PathBuf>,
+
+    /// On exit, and whenever G is pressed, write a bar chart of the counts with their
+    /// statistics as an SVG [default path: d20-chart.svg]
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = svg::DEFAULT_PATH
+    )]
+    pub svg: Option<PathBuf>,
+
+    /// Start from the counts in a JSON file of statistics, as sent to remote clients, to pick
+    /// up a run where it left off.
+    #[arg(long, value_name = "FILE")]
+    pub import: Option<PathBuf>,
+
+    /// Show the imported counts as a ghost behind the bars to compare against, instead of
+    /// starting from them.
+    #[arg(long, requires = "import")]
+    pub import_as_ghost: bool,
+
+    /// Number of rolls to simulate in headless modes.
+    #[arg(long, default_value_t = 1_000_000)]
+    pub rolls: u64,
+}
+
+/// Tools for counts exported as CSV, run in place of opening a window.
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print statistics for exported counts.
+    Stats { file: PathBuf },
+    /// Add up exported counts for the same faces into one export.
+    Merge {
+        #[arg(required = true, num_args = 2..)]
+        files: Vec<PathBuf>,
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Draw a chart of exported d20 counts into a PNG, the same way the window would.
+    Render {
+        file: PathBuf,
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Width and height in pixels, such as 1200x600.
+        #[arg(long, default_value = "1200x600", value_parser = parse_size)]
+        size: (u32, u32),
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ThemeSource {
+    /// The config file's theme.
+    Config,
+    /// Shades of the desktop's accent color on Windows and macOS, lightening toward the high
+    /// faces on a dark desktop and darkening toward them on a light one.
+    Accent,
+    /// Shades of a hue picked from --seed, or at random without one, so that windows side by
+    /// side are easy to tell apart.
+    Seed,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WmBackend {
+    /// Let winit pick, preferring Wayland when it's available.
+    Auto,
+    Wayland,
+    X11,
+}
+
+/// Parse a size such as `1200x600`, neither of which can be zero.
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| format!("{value:?} isn't a width and height, such as 1200x600"))?;
+    let width: u32 = width
+        .parse()
+        .map_err(|err: std::num::ParseIntError| err.to_string())?;
+    let height: u32 = height
+        .parse()
+        .map_err(|err: std::num::ParseIntError| err.to_string())?;
+    if width == 0 || height == 0 {
+        return Err(format!("{value:?} has no area"));
+    }
+    Ok((width, height))
+}
+
+/// Parse a hue in degrees, wrapping it around into 0 up to 360.
+fn parse_hue(value: &str) -> Result<f64, String> {
+    let hue = value.parse::<f64>().map_err(|err| err.to_string())?;
+    if hue.is_finite() {
+        Ok(hue.rem_euclid(360.))
+    } else {
+        Err(format!("{hue} isn't a hue"))
+    }
+}
+
+/// Parse a time between events such as `10s`, `500ms`, or `2m`, which can't be zero. A bare
+/// number is taken as seconds.
+fn parse_interval(value: &str) -> Result<Duration, String> {
+    let (number, unit) = value
+        .find(|character: char| character.is_ascii_alphabetic())
+        .map_or((value, "s"), |split| value.split_at(split));
+    let number: u64 = number
+        .trim()
+        .parse()
+        .map_err(|err: std::num::ParseIntError| err.to_string())?;
+    let interval = match unit {
+        "ms" => Duration::from_millis(number),
+        "s" => Duration::from_secs(number),
+        "m" => Duration::from_secs(number * 60),
+        "h" => Duration::from_secs(number * 60 * 60),
+        _ => return Err(format!("{unit:?} isn't one of ms, s, m, or h")),
+    };
+    if interval.is_zero() {
+        return Err(format!("{value:?} is no time at all"));
+    }
+    Ok(interval)
+}
+
+/// Parse a chance from 0 to 1.
+fn parse_probability(value: &str) -> Result<f64, String> {
+    let probability = value.parse::<f64>().map_err(|err| err.to_string())?;
+    if (0. ..=1.).contains(&probability) {
+        Ok(probability)
+    } else {
+        Err(format!("{probability} isn't between 0 and 1"))
+    }
+}
@@ -0,0 +1,299 @@
+//! Finding every overlay a spot on the chart where it doesn't cover another.
+//!
+//! Each overlay asks for a corner and a size. Overlays sharing a corner stack away from it, the
+//! higher priority nearer the corner, and anything pushed off the edge of the chart is left out.
+//!
+//! In a small window, whole kinds of furniture are left out first, so a thumbnail is all bars.
+
+use crate::text;
+use std::cmp::Reverse;
+
+/// Lines of text, and characters across, the window needs for each tier above bars alone:
+/// labels beneath the chart, then the status strip, then any overlay at all.
+const LABELS_SIZE: (u32, u32) = (20, 40);
+const STATUS_SIZE: (u32, u32) = (12, 24);
+const OVERLAYS_SIZE: (u32, u32) = (8, 16);
+
+/// How much besides the bars a window has room for, from everything down to bars alone. Each
+/// tier leaves out what the one before it does, and more.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tier {
+    Full,
+    /// No labels beneath the chart.
+    NoLabels,
+    /// No status strip either.
+    NoStatus,
+    /// Nothing but the bars.
+    BarsOnly,
+}
+
+/// The tier for a `width` by `height` window with text drawn at `scale`.
+pub fn tier(width: u32, height: u32, scale: u32) -> Tier {
+    // A line as labels are spaced, and a character with the gap after it.
+    let line_height = text::text_height(scale) + 2 * scale;
+    let char_width = text::text_width("0", scale) + scale;
+    let fits =
+        |(lines, chars): (u32, u32)| height >= lines * line_height && width >= chars * char_width;
+    if fits(LABELS_SIZE) {
+        Tier::Full
+    } else if fits(STATUS_SIZE) {
+        Tier::NoLabels
+    } else if fits(OVERLAYS_SIZE) {
+        Tier::NoStatus
+    } else {
+        Tier::BarsOnly
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Anchor {
+    fn is_top(self) -> bool {
+        matches!(self, Self::TopLeft | Self::TopRight)
+    }
+
+    fn is_left(self) -> bool {
+        matches!(self, Self::TopLeft | Self::BottomLeft)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Request {
+    pub anchor: Anchor,
+    pub width: u32,
+    pub height: u32,
+    /// Higher sits nearer the corner and is placed first when space runs out.
+    pub priority: u8,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+}
+
+/// Where each of `requests` goes within `area`, in the same order, or `None` for any that no
+/// longer fit once those with higher priority are placed. Equal priorities are placed in the
+/// order they were requested.
+///
+/// Each request starts in its corner and slides along the left or right edge, away from the
+/// corner, until it clears everything already placed.
+pub fn layout(area: Rect, requests: &[Request]) -> Vec<Option<Rect>> {
+    let mut order: Vec<usize> = (0..requests.len()).collect();
+    order.sort_by_key(|&index| Reverse(requests[index].priority));
+
+    let mut placements = vec![None; requests.len()];
+    let mut placed: Vec<Rect> = Vec::new();
+    for index in order {
+        let request = requests[index];
+        let mut rect = Rect {
+            x: if request.anchor.is_left() {
+                area.x
+            } else {
+                area.right() - request.width as i32
+            },
+            y: if request.anchor.is_top() {
+                area.y
+            } else {
+                area.bottom() - request.height as i32
+            },
+            width: request.width,
+            height: request.height,
+        };
+        let placement = loop {
+            if rect.y < area.y || rect.bottom() > area.bottom() {
+                break None;
+            }
+            let blockers = placed.iter().filter(|other| other.overlaps(&rect));
+            // Jump past every overlay in the way at once; each jump moves strictly away from
+            // the corner, so this ends.
+            let next_y = if request.anchor.is_top() {
+                blockers.map(Rect::bottom).max()
+            } else {
+                blockers.map(|other| other.y - request.height as i32).min()
+            };
+            match next_y {
+                Some(y) => rect.y = y,
+                None => break Some(rect),
+            }
+        };
+        if let Some(rect) = placement {
+            placed.push(rect);
+        }
+        placements[index] = placement;
+    }
+    placements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 200,
+        height: 100,
+    };
+
+    fn request(anchor: Anchor, width: u32, height: u32, priority: u8) -> Request {
+        Request {
+            anchor,
+            width,
+            height,
+            priority,
+        }
+    }
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Option<Rect> {
+        Some(Rect {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    #[test]
+    fn each_corner_gets_its_own() {
+        let placements = layout(
+            AREA,
+            &[
+                request(Anchor::TopLeft, 50, 20, 0),
+                request(Anchor::TopRight, 50, 20, 0),
+                request(Anchor::BottomLeft, 50, 20, 0),
+                request(Anchor::BottomRight, 50, 20, 0),
+            ],
+        );
+        assert_eq!(
+            placements,
+            [
+                rect(0, 0, 50, 20),
+                rect(150, 0, 50, 20),
+                rect(0, 80, 50, 20),
+                rect(150, 80, 50, 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn stacks_away_from_the_corner_by_priority() {
+        let placements = layout(
+            AREA,
+            &[
+                request(Anchor::TopLeft, 50, 20, 1),
+                request(Anchor::TopLeft, 40, 30, 5),
+                request(Anchor::BottomRight, 60, 10, 0),
+                request(Anchor::BottomRight, 60, 15, 0),
+            ],
+        );
+        assert_eq!(
+            placements,
+            [
+                // Below the higher priority one, which takes the corner.
+                rect(0, 30, 50, 20),
+                rect(0, 0, 40, 30),
+                // Equal priorities stack in the order requested.
+                rect(140, 90, 60, 10),
+                rect(140, 75, 60, 15),
+            ]
+        );
+    }
+
+    #[test]
+    fn pushes_along_the_edge_past_overlays_from_other_corners() {
+        // Too wide to share the top edge, so the top-right one slides down past the top-left.
+        let placements = layout(
+            AREA,
+            &[
+                request(Anchor::TopLeft, 120, 20, 1),
+                request(Anchor::TopRight, 120, 25, 0),
+            ],
+        );
+        assert_eq!(placements, [rect(0, 0, 120, 20), rect(80, 20, 120, 25)]);
+    }
+
+    #[test]
+    fn drops_what_overflows_the_window() {
+        let placements = layout(
+            AREA,
+            &[
+                request(Anchor::TopLeft, 50, 60, 2),
+                request(Anchor::TopLeft, 50, 60, 1),
+                // Taller than the whole area.
+                request(Anchor::BottomRight, 50, 101, 3),
+                request(Anchor::BottomRight, 50, 40, 0),
+            ],
+        );
+        assert_eq!(
+            placements,
+            [rect(0, 0, 50, 60), None, None, rect(150, 60, 50, 40)]
+        );
+    }
+
+    #[test]
+    fn offset_areas_keep_their_origin() {
+        let area = Rect {
+            x: 10,
+            y: 30,
+            width: 100,
+            height: 50,
+        };
+        let placements = layout(area, &[request(Anchor::BottomRight, 20, 10, 0)]);
+        assert_eq!(placements, [rect(90, 70, 20, 10)]);
+    }
+
+    #[test]
+    fn tiers_for_a_table_of_sizes() {
+        // At scale 1 a line is 9 pixels and a character 6, so labels need 240×180, the status
+        // strip 144×108 and overlays 96×72.
+        for (width, height, expected) in [
+            (1920, 1080, Tier::Full),
+            (240, 180, Tier::Full),
+            (239, 180, Tier::NoLabels),
+            (240, 179, Tier::NoLabels),
+            (200, 150, Tier::NoLabels),
+            (144, 108, Tier::NoLabels),
+            (143, 108, Tier::NoStatus),
+            (96, 72, Tier::NoStatus),
+            (96, 71, Tier::BarsOnly),
+            (95, 72, Tier::BarsOnly),
+            (1, 1, Tier::BarsOnly),
+        ] {
+            assert_eq!(tier(width, height, 1), expected, "{width}×{height}");
+        }
+    }
+
+    #[test]
+    fn larger_text_needs_more_room() {
+        assert_eq!(tier(240, 180, 2), Tier::NoStatus);
+        assert_eq!(tier(480, 360, 2), Tier::Full);
+        assert_eq!(tier(200, 150, 2), Tier::NoStatus);
+        assert_eq!(tier(190, 140, 2), Tier::BarsOnly);
+    }
+}
@@ -0,0 +1,423 @@
+//! Settings loaded from a TOML file, such as `d20.toml`:
+//!
+//! ```toml
+//! rate = 20000
+//! title = "{app} — {total} rolls — {mode}"
+//!
+//! labels = ["common", "uncommon", "rare", ...]
+//! weights = [1, 1, 1, ..., 3]
+//! tolerances = [0.003, 0.003, 0.003, ..., 0.01]
+//! rollover = "day"
+//! dc = 15
+//! notation = "si"
+//! hidden = [1, 2, 3]
+//! hue = 212
+//!
+//! [keys]
+//! reset = "ctrl+r"
+//! export_config = "ctrl+shift+s"
+//!
+//! [theme]
+//! background = "#222222"
+//! bar = "#3366ff"
+//! ```
+//!
+//! Command-line flags take precedence over anything set here.
+
+use crate::history::Rollover;
+#[cfg(feature = "app")]
+use crate::keymap::{Action, Chord, InvalidChord};
+use crate::notation::Notation;
+use crate::theme::{self, InvalidColor, Theme};
+use crate::title::{Template, UnknownPlaceholder};
+use rand::distributions::{WeightedError, WeightedIndex};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Loaded when no `--config` is given, if it exists.
+pub const DEFAULT_PATH: &str = "d20.toml";
+
+/// Where the live settings are exported to, ready to be loaded with `--config`.
+pub const EXPORT_PATH: &str = "d20-exported.toml";
+
+pub const DEFAULT_RATE: u64 = 10000;
+pub const DEFAULT_FRAME_BUDGET_MS: u64 = 50;
+pub const DEFAULT_STALL_WINDOW: u64 = 200_000;
+pub const DEFAULT_STATS_WIDTH: u32 = 240;
+
+/// Number of faces on the die, which custom labels must match.
+const SIDES: usize = 20;
+
+#[derive(Clone, Default, PartialEq)]
+pub struct Config {
+    pub rate: Option<u64>,
+    pub frame_budget_ms: Option<u64>,
+    pub stall_window: Option<u64>,
+    /// Width in pixels of the stats panel on the right, when it's shown.
+    pub stats_width: Option<u32>,
+    /// Names shown for each face instead of its number.
+    pub labels: Option<Vec<String>>,
+    /// Relative chance of each face coming up, for simulating a loaded die.
+    pub weights: Option<Vec<f64>>,
+    /// How far each face's share of the rolls may stray either way from the share its weight
+    /// gives it, such as 0.003 for ±0.3%.
+    pub tolerances: Option<Vec<f64>>,
+    /// How often to move the counts into the history and start afresh.
+    pub rollover: Option<Rollover>,
+    /// Difficulty class to show the chance of meeting, as a face number.
+    pub dc: Option<usize>,
+    /// How counts are written in labels.
+    pub notation: Option<Notation>,
+    /// Faces, numbered from 1, left off the chart while still being rolled.
+    pub hidden: Option<Vec<usize>>,
+    /// Layout of the window title, built from `{app}`, `{total}`, `{mode}`, `{hover}`, and
+    /// `{alert}`.
+    pub title: Option<Template>,
+    /// Shortcuts bound in place of the defaults.
+    #[cfg(feature = "app")]
+    pub keys: BTreeMap<Action, Chord>,
+    pub theme: Theme,
+    /// Hue in degrees to shade the bars from, in place of the theme's bar colors.
+    pub hue: Option<f64>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rate: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frame_budget_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stall_window: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats_width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    labels: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weights: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tolerances: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rollover: Option<Rollover>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dc: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notation: Option<Notation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hidden: Option<Vec<usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[cfg(feature = "app")]
+    keys: BTreeMap<Action, String>,
+    theme: RawTheme,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hue: Option<f64>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawTheme {
+    background: Option<String>,
+    winner: Option<String>,
+    loser: Option<String>,
+    overdue: Option<String>,
+    expected: Option<String>,
+    ghost: Option<String>,
+    alarm: Option<String>,
+    simulated: Option<String>,
+    text: Option<String>,
+    panel: Option<String>,
+    /// The brightest bar color; the rest of the bars ramp up to it.
+    bar: Option<String>,
+    dim: Option<f64>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path).map_err(Error::Io)?;
+        Self::parse(&contents)
+    }
+
+    /// Write the config to `path` in the form `load` reads.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let raw = RawConfig {
+            rate: self.rate,
+            frame_budget_ms: self.frame_budget_ms,
+            stall_window: self.stall_window,
+            stats_width: self.stats_width,
+            labels: self.labels.clone(),
+            weights: self.weights.clone(),
+            tolerances: self.tolerances.clone(),
+            rollover: self.rollover,
+            dc: self.dc,
+            notation: self.notation,
+            hidden: self.hidden.clone(),
+            title: self.title.as_ref().map(|title| title.as_str().to_string()),
+            #[cfg(feature = "app")]
+            keys: self
+                .keys
+                .iter()
+                .map(|(&action, chord)| (action, chord.to_string()))
+                .collect(),
+            theme: RawTheme::from_theme(&self.theme),
+            hue: self.hue,
+        };
+        let contents = toml::to_string(&raw).map_err(Error::Serialize)?;
+        std::fs::write(path, contents).map_err(Error::Write)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, Error> {
+        let raw: RawConfig = toml::from_str(contents).map_err(Error::Parse)?;
+        if let Some(labels) = &raw.labels {
+            if labels.len() != SIDES {
+                return Err(Error::Labels(labels.len()));
+            }
+        }
+        if let Some(weights) = &raw.weights {
+            if weights.len() != SIDES {
+                return Err(Error::WeightCount(weights.len()));
+            }
+            WeightedIndex::new(weights).map_err(Error::Weights)?;
+        }
+        if let Some(tolerances) = &raw.tolerances {
+            if tolerances.len() != SIDES {
+                return Err(Error::ToleranceCount(tolerances.len()));
+            }
+            let invalid = tolerances
+                .iter()
+                .find(|tolerance| !(0. ..=1.).contains(*tolerance));
+            if let Some(&tolerance) = invalid {
+                return Err(Error::Tolerance(tolerance));
+            }
+        }
+        if let Some(dc) = raw.dc {
+            if !(1..=SIDES).contains(&dc) {
+                return Err(Error::Dc(dc));
+            }
+        }
+        for &face in raw.hidden.iter().flatten() {
+            if !(1..=SIDES).contains(&face) {
+                return Err(Error::HiddenFace(face));
+            }
+        }
+        if let Some(hue) = raw.hue {
+            if !hue.is_finite() {
+                return Err(Error::Hue(hue));
+            }
+        }
+        Ok(Self {
+            rate: raw.rate,
+            frame_budget_ms: raw.frame_budget_ms,
+            stall_window: raw.stall_window,
+            stats_width: raw.stats_width,
+            labels: raw.labels,
+            weights: raw.weights,
+            tolerances: raw.tolerances,
+            rollover: raw.rollover,
+            dc: raw.dc,
+            notation: raw.notation,
+            hidden: raw.hidden,
+            title: raw
+                .title
+                .as_deref()
+                .map(Template::parse)
+                .transpose()
+                .map_err(Error::Title)?,
+            #[cfg(feature = "app")]
+            keys: raw
+                .keys
+                .iter()
+                .map(|(&action, chord)| Ok((action, Chord::parse(chord)?)))
+                .collect::<Result<_, _>>()
+                .map_err(Error::Key)?,
+            theme: raw.theme.resolve().map_err(Error::Color)?,
+            hue: raw.hue.map(|hue| hue.rem_euclid(360.)),
+        })
+    }
+}
+
+impl RawTheme {
+    fn from_theme(theme: &Theme) -> Self {
+        let hex = |color| Some(theme::format_hex_color(color));
+        Self {
+            background: hex(theme.background),
+            winner: hex(theme.winner),
+            loser: hex(theme.loser),
+            overdue: hex(theme.overdue),
+            expected: hex(theme.expected),
+            ghost: hex(theme.ghost),
+            alarm: hex(theme.alarm),
+            simulated: hex(theme.simulated),
+            text: hex(theme.text),
+            panel: hex(theme.panel),
+            bar: hex(theme.bars[theme.bars.len() - 1]),
+            dim: Some(theme.dim),
+        }
+    }
+
+    fn resolve(&self) -> Result<Theme, InvalidColor> {
+        let mut theme = Theme::default();
+        let colors = [
+            (&self.background, &mut theme.background),
+            (&self.winner, &mut theme.winner),
+            (&self.loser, &mut theme.loser),
+            (&self.overdue, &mut theme.overdue),
+            (&self.expected, &mut theme.expected),
+            (&self.ghost, &mut theme.ghost),
+            (&self.alarm, &mut theme.alarm),
+            (&self.simulated, &mut theme.simulated),
+            (&self.text, &mut theme.text),
+            (&self.panel, &mut theme.panel),
+        ];
+        for (hex, color) in colors {
+            if let Some(hex) = hex {
+                *color = theme::parse_hex_color(hex)?;
+            }
+        }
+        if let Some(hex) = &self.bar {
+            theme.bars = theme::bar_ramp(theme::parse_hex_color(hex)?);
+        }
+        if let Some(dim) = self.dim {
+            theme.dim = dim.clamp(0., 1.);
+        }
+        Ok(theme)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Write(std::io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+    Color(InvalidColor),
+    Title(UnknownPlaceholder),
+    #[cfg(feature = "app")]
+    Key(InvalidChord),
+    /// The number of labels given, which doesn't match the number of faces.
+    Labels(usize),
+    /// The number of weights given, which doesn't match the number of faces.
+    WeightCount(usize),
+    Weights(WeightedError),
+    /// The number of tolerances given, which doesn't match the number of faces.
+    ToleranceCount(usize),
+    /// A tolerance given, which isn't a share of the rolls.
+    Tolerance(f64),
+    /// The DC given, which isn't one of the faces.
+    Dc(usize),
+    /// A face given to hide, which isn't one of the faces.
+    HiddenFace(usize),
+    /// The hue given, which isn't a number of degrees.
+    Hue(f64),
+}
+
+impl Error {
+    /// A short, single-line explanation suitable for the status strip.
+    pub fn reason(&self) -> String {
+        match self {
+            Self::Io(err) | Self::Write(err) => err.to_string(),
+            Self::Parse(err) => err.message().to_string(),
+            Self::Serialize(err) => err.to_string(),
+            Self::Color(err) => err.to_string(),
+            Self::Title(err) => err.to_string(),
+            #[cfg(feature = "app")]
+            Self::Key(err) => err.to_string(),
+            Self::Labels(found) => format!("expected {SIDES} labels, found {found}"),
+            Self::WeightCount(found) => format!("expected {SIDES} weights, found {found}"),
+            Self::Weights(err) => err.to_string(),
+            Self::ToleranceCount(found) => format!("expected {SIDES} tolerances, found {found}"),
+            Self::Tolerance(tolerance) => format!("tolerance {tolerance} isn't between 0 and 1"),
+            Self::Dc(dc) => format!("DC {dc} isn't between 1 and {SIDES}"),
+            Self::HiddenFace(face) => format!("hidden face {face} isn't between 1 and {SIDES}"),
+            Self::Hue(hue) => format!("hue {hue} isn't a number of degrees"),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "could not read config"),
+            Self::Write(_) => write!(f, "could not write config"),
+            Self::Parse(_) => write!(f, "could not parse config"),
+            Self::Serialize(_) => write!(f, "could not serialize config"),
+            Self::Color(_) => write!(f, "invalid theme"),
+            Self::Title(_) => write!(f, "invalid title"),
+            #[cfg(feature = "app")]
+            Self::Key(_) => write!(f, "invalid key binding"),
+            Self::Labels(found) => write!(f, "expected {SIDES} labels, found {found}"),
+            Self::WeightCount(found) => write!(f, "expected {SIDES} weights, found {found}"),
+            Self::Weights(_) => write!(f, "invalid weights"),
+            Self::ToleranceCount(found) => write!(f, "expected {SIDES} tolerances, found {found}"),
+            Self::Tolerance(tolerance) => write!(f, "tolerance {tolerance} isn't between 0 and 1"),
+            Self::Dc(dc) => write!(f, "DC {dc} isn't between 1 and {SIDES}"),
+            Self::HiddenFace(face) => write!(f, "hidden face {face} isn't between 1 and {SIDES}"),
+            Self::Hue(hue) => write!(f, "hue {hue} isn't a number of degrees"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) | Self::Write(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            Self::Serialize(err) => Some(err),
+            Self::Color(err) => Some(err),
+            Self::Title(err) => Some(err),
+            #[cfg(feature = "app")]
+            Self::Key(err) => Some(err),
+            Self::Labels(_)
+            | Self::WeightCount(_)
+            | Self::ToleranceCount(_)
+            | Self::Tolerance(_)
+            | Self::Dc(_)
+            | Self::HiddenFace(_)
+            | Self::Hue(_) => None,
+            Self::Weights(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tolerances(values: &str) -> Result<Config, Error> {
+        Config::parse(&format!("tolerances = [{values}]"))
+    }
+
+    #[test]
+    fn parses_a_tolerance_per_face() {
+        let config = tolerances(&["0.003"; SIDES].join(", ")).unwrap();
+        assert_eq!(config.tolerances, Some(vec![0.003; SIDES]));
+        assert_eq!(Config::parse("").unwrap().tolerances, None);
+    }
+
+    #[test]
+    fn rejects_the_wrong_number_of_tolerances() {
+        assert!(matches!(
+            tolerances("0.003, 0.003"),
+            Err(Error::ToleranceCount(2))
+        ));
+    }
+
+    #[test]
+    fn rejects_tolerances_that_are_not_shares() {
+        let mut values = vec!["0.003"; SIDES];
+        values[7] = "1.5";
+        assert!(matches!(
+            tolerances(&values.join(", ")),
+            Err(Error::Tolerance(tolerance)) if tolerance == 1.5
+        ));
+        values[7] = "-0.1";
+        assert!(matches!(
+            tolerances(&values.join(", ")),
+            Err(Error::Tolerance(_))
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+//! Detection of the distribution settling close enough to even for long enough.
+
+use std::time::{Duration, Instant};
+
+/// How long the distribution has to stay within the threshold to count as converged.
+pub const HOLD: Duration = Duration::from_secs(5);
+
+/// How far past the threshold the deviation has to climb to restart the hold, as a fraction of
+/// the threshold, so a deviation hovering right at the edge doesn't keep restarting it.
+const HYSTERESIS: f64 = 0.1;
+
+pub struct Detector {
+    /// Largest deviation from an even share that still counts as converging.
+    threshold: f64,
+    /// When the deviation last came within the threshold, if it's still there.
+    within_since: Option<Instant>,
+    converged: bool,
+}
+
+impl Detector {
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            within_since: None,
+            converged: false,
+        }
+    }
+
+    /// Take the latest `deviation`, returning `true` only on the update it converges.
+    pub fn update(&mut self, deviation: f64, now: Instant) -> bool {
+        if self.converged {
+            return false;
+        }
+        match self.within_since {
+            None if deviation < self.threshold => self.within_since = Some(now),
+            Some(_) if deviation > self.threshold * (1. + HYSTERESIS) => self.within_since = None,
+            _ => (),
+        }
+        self.converged = self
+            .within_since
+            .is_some_and(|since| now.saturating_duration_since(since) >= HOLD);
+        self.converged
+    }
+
+    /// Start watching for convergence over again.
+    pub fn clear(&mut self) {
+        self.within_since = None;
+        self.converged = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: f64 = 0.01;
+
+    #[test]
+    fn converges_after_holding_within_the_threshold() {
+        let start = Instant::now();
+        let mut detector = Detector::new(THRESHOLD);
+        assert!(!detector.update(0.005, start));
+        assert!(!detector.update(0.005, start + HOLD / 2));
+        assert!(detector.update(0.005, start + HOLD));
+    }
+
+    #[test]
+    fn hovering_at_the_threshold_doesnt_restart_the_hold() {
+        let start = Instant::now();
+        let mut detector = Detector::new(THRESHOLD);
+        assert!(!detector.update(THRESHOLD * 0.99, start));
+        // Flickering back and forth across the threshold, but never out of the band above it.
+        for step in 1..50_u32 {
+            let deviation = if step % 2 == 0 {
+                THRESHOLD * 0.99
+            } else {
+                THRESHOLD * 1.09
+            };
+            assert!(!detector.update(deviation, start + HOLD * step / 50));
+        }
+        assert!(detector.update(THRESHOLD * 1.09, start + HOLD));
+    }
+
+    #[test]
+    fn leaving_the_band_restarts_the_hold() {
+        let start = Instant::now();
+        let mut detector = Detector::new(THRESHOLD);
+        detector.update(0.005, start);
+        detector.update(THRESHOLD * 1.2, start + HOLD / 2);
+        // The hold starts over from when it came back within the threshold.
+        assert!(!detector.update(0.005, start + HOLD));
+        assert!(!detector.update(0.005, start + HOLD * 2 - Duration::from_millis(1)));
+        assert!(detector.update(0.005, start + HOLD * 2));
+    }
+
+    #[test]
+    fn latches_once_converged() {
+        let start = Instant::now();
+        let mut detector = Detector::new(THRESHOLD);
+        detector.update(0., start);
+        assert!(detector.update(0., start + HOLD));
+        // Reports it once, then stays quiet however far the deviation wanders.
+        assert!(!detector.update(0., start + HOLD * 2));
+        assert!(!detector.update(1., start + HOLD * 3));
+        assert!(!detector.update(0., start + HOLD * 10));
+
+        detector.clear();
+        assert!(!detector.update(0., start + HOLD * 11));
+        assert!(detector.update(0., start + HOLD * 12));
+    }
+}
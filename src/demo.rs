@@ -0,0 +1,117 @@
+//! A scripted tour that loops forever, for leaving the window running at a booth.
+
+use std::time::{Duration, Instant};
+
+/// Chance of each total from 3 to 18 on three six-sided dice, placed on the faces with those
+/// numbers.
+const THREE_D6: [f64; 20] = [
+    0., 0., 1., 3., 6., 10., 15., 21., 25., 27., 27., 25., 21., 15., 10., 6., 3., 1., 0., 0.,
+];
+
+/// A d20 that comes up 20 three times as often as any other face.
+const LOADED_TWENTY: [f64; 20] = [
+    1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 1., 3.,
+];
+
+/// The settings the tour changes, so each step can apply its own and the tour can put back the
+/// ones it found.
+#[derive(Clone)]
+pub struct Setup {
+    /// Relative chance of each face, or `None` for a fair die.
+    pub weights: Option<Vec<f64>>,
+    pub show_p_value: bool,
+    /// Threshold in standard deviations to spotlight faces beyond, if any.
+    pub spotlight: Option<f64>,
+    /// DC to show the chances of meeting, if any.
+    pub dc: Option<usize>,
+}
+
+pub struct Step {
+    /// Shown across the top of the chart, explaining what's on screen.
+    pub caption: &'static str,
+    pub duration: Duration,
+    weights: Option<&'static [f64; 20]>,
+    show_p_value: bool,
+    spotlight: Option<f64>,
+    dc: Option<usize>,
+}
+
+impl Step {
+    pub fn setup(&self) -> Setup {
+        Setup {
+            weights: self.weights.map(|weights| weights.to_vec()),
+            show_p_value: self.show_p_value,
+            spotlight: self.spotlight,
+            dc: self.dc,
+        }
+    }
+}
+
+pub const STEPS: [Step; 4] = [
+    Step {
+        caption: "A fair d20: every face is equally likely, so the bars grow evenly",
+        duration: Duration::from_secs(20),
+        weights: None,
+        show_p_value: false,
+        spotlight: None,
+        dc: None,
+    },
+    Step {
+        caption: "Weighted like the total of 3d6: middle totals come up far more than 3 or 18",
+        duration: Duration::from_secs(20),
+        weights: Some(&THREE_D6),
+        show_p_value: false,
+        spotlight: None,
+        dc: None,
+    },
+    Step {
+        caption: "A loaded die: 20 comes up three times as often, and the p-value collapses",
+        duration: Duration::from_secs(20),
+        weights: Some(&LOADED_TWENTY),
+        show_p_value: true,
+        spotlight: Some(3.),
+        dc: None,
+    },
+    Step {
+        caption: "Advantage: rolling twice and keeping the higher makes DC 15 far easier",
+        duration: Duration::from_secs(20),
+        weights: None,
+        show_p_value: false,
+        spotlight: None,
+        dc: Some(15),
+    },
+];
+
+pub struct Demo {
+    /// Index into `STEPS` of the step underway.
+    pub step: usize,
+    /// When the step underway started.
+    pub started: Instant,
+    /// The settings to go back to when the tour is interrupted.
+    pub restore: Setup,
+}
+
+impl Demo {
+    pub fn new(restore: Setup, now: Instant) -> Self {
+        Self {
+            step: 0,
+            started: now,
+            restore,
+        }
+    }
+
+    pub fn current(&self) -> &'static Step {
+        &STEPS[self.step]
+    }
+
+    /// Move on to the next step, starting over after the last, if the current one is done by
+    /// `now`. Returns the step moved on to, if any.
+    pub fn advance(&mut self, now: Instant) -> Option<&'static Step> {
+        if now.saturating_duration_since(self.started) < self.current().duration {
+            return None;
+        }
+        self.step = (self.step + 1) % STEPS.len();
+        self.started = now;
+        Some(self.current())
+    }
+}
@@ -0,0 +1,99 @@
+//! Curves for how interpolated bars move between updates.
+
+use clap::ValueEnum;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Easing {
+    /// At a steady pace.
+    #[default]
+    Linear,
+    /// Quickly at first, slowing as the bars arrive.
+    EaseOut,
+    /// Speeding up from rest, then slowing into place.
+    EaseInOut,
+}
+
+impl Easing {
+    /// How far along the bars should be once `progress` of the way from one update to the next,
+    /// both from 0 to 1. Never goes past 1, so the bars don't overshoot their counts.
+    pub fn apply(self, progress: f64) -> f64 {
+        let progress = progress.clamp(0., 1.);
+        match self {
+            Self::Linear => progress,
+            Self::EaseOut => progress * (2. - progress),
+            Self::EaseInOut if progress < 0.5 => 2. * progress * progress,
+            Self::EaseInOut => 1. - 2. * (1. - progress) * (1. - progress),
+        }
+    }
+
+    /// `apply` without floating point: how many of `interval` steps along the bars should be
+    /// after `elapsed` of them, rounding down.
+    pub fn apply_integer(self, elapsed: u128, interval: u128) -> u128 {
+        let interval = interval.max(1);
+        let elapsed = elapsed.min(interval);
+        let left = interval - elapsed;
+        match self {
+            Self::Linear => elapsed,
+            Self::EaseOut => elapsed * (interval + left) / interval,
+            Self::EaseInOut if 2 * elapsed < interval => 2 * elapsed * elapsed / interval,
+            // Round the remainder up, so the result rounds down like the other curves.
+            Self::EaseInOut => interval - (2 * left * left).div_ceil(interval),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 3] = [Easing::Linear, Easing::EaseOut, Easing::EaseInOut];
+
+    #[test]
+    fn starts_at_0_and_ends_at_1() {
+        for easing in ALL {
+            assert_eq!(easing.apply(0.), 0.);
+            assert_eq!(easing.apply(1.), 1.);
+            // Clamped either side, so late frames don't overshoot.
+            assert_eq!(easing.apply(-0.5), 0.);
+            assert_eq!(easing.apply(1.5), 1.);
+            assert_eq!(easing.apply_integer(0, 1_000), 0);
+            assert_eq!(easing.apply_integer(1_000, 1_000), 1_000);
+            assert_eq!(easing.apply_integer(5_000, 1_000), 1_000);
+        }
+    }
+
+    #[test]
+    fn reaches_the_target_monotonically() {
+        for easing in ALL {
+            let mut previous = 0.;
+            let mut previous_integer = 0;
+            for step in 0..=1_000_u32 {
+                let progress = easing.apply(f64::from(step) / 1_000.);
+                assert!((previous..=1.).contains(&progress));
+                previous = progress;
+                let integer = easing.apply_integer(step.into(), 1_000);
+                assert!((previous_integer..=1_000).contains(&integer));
+                previous_integer = integer;
+            }
+        }
+    }
+
+    #[test]
+    fn integer_curves_follow_the_float_ones() {
+        for easing in ALL {
+            for step in 0..=1_000_u32 {
+                let float = easing.apply(f64::from(step) / 1_000.) * 1_000.;
+                let integer = easing.apply_integer(step.into(), 1_000) as f64;
+                assert!(float - integer < 1. + 1e-9 && integer <= float + 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn curves_have_their_shape() {
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+        assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseInOut.apply(0.75) > 0.75);
+    }
+}
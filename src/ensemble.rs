@@ -0,0 +1,75 @@
+//! Independent runs rolled alongside the main one, each from its own seed, to show how much a
+//! single run wobbles and how closely their average keeps to an even share.
+
+use rand::distributions::{Distribution as _, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng};
+
+/// Most runs allowed, since each one rolls as often as the main run.
+pub const MAX_RUNS: usize = 16;
+
+struct Run {
+    rng: StdRng,
+    counts: Vec<u64>,
+}
+
+pub struct Ensemble {
+    /// Seed each run's own is derived from.
+    base_seed: u64,
+    runs: Vec<Run>,
+}
+
+impl Ensemble {
+    /// `runs` runs of a die with `sides` faces, at most `MAX_RUNS`, seeded from `base_seed`.
+    pub fn new(runs: usize, sides: usize, base_seed: u64) -> Self {
+        let mut ensemble = Self {
+            base_seed,
+            runs: Vec::new(),
+        };
+        ensemble.runs = (0..runs.min(MAX_RUNS))
+            .map(|index| Run {
+                rng: StdRng::seed_from_u64(ensemble.run_seed(index)),
+                counts: vec![0; sides],
+            })
+            .collect();
+        ensemble
+    }
+
+    /// Spread `base_seed` out for each run, so neighboring runs don't start from neighboring
+    /// seeds. This is SplitMix64's mixing step.
+    fn run_seed(&self, index: usize) -> u64 {
+        let mut seed = self
+            .base_seed
+            .wrapping_add((index as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        seed ^ (seed >> 31)
+    }
+
+    /// Roll every run `rolls` times, drawing from `loaded` if the die is loaded.
+    pub fn roll(&mut self, rolls: u64, loaded: Option<&WeightedIndex<f64>>) {
+        for run in &mut self.runs {
+            for _ in 0..rolls {
+                let roll_key = match loaded {
+                    Some(loaded) => loaded.sample(&mut run.rng),
+                    None => run.rng.gen_range(0..run.counts.len()),
+                };
+                run.counts[roll_key] += 1;
+            }
+        }
+    }
+
+    /// Zero every run's counts and start each from its seed again.
+    pub fn restart(&mut self) {
+        for index in 0..self.runs.len() {
+            let seed = self.run_seed(index);
+            let run = &mut self.runs[index];
+            run.rng = StdRng::seed_from_u64(seed);
+            run.counts.fill(0);
+        }
+    }
+
+    pub fn runs(&self) -> impl Iterator<Item = &[u64]> {
+        self.runs.iter().map(|run| &run.counts[..])
+    }
+}
@@ -0,0 +1,128 @@
+//! Every face's count as CSV, for spreadsheets and for the `stats`, `merge`, and `render`
+//! subcommands:
+//!
+//! ```csv
+//! face,label,count
+//! 1,"1",50123
+//! 2,"2",49877
+//! ```
+
+use std::path::Path;
+
+/// Where counts are saved when no path is given.
+pub const DEFAULT_PATH: &str = "d20-counts.csv";
+
+const HEADER: &str = "face,label,count";
+
+/// Each face's label and count, in face order.
+#[derive(Clone)]
+pub struct Counts {
+    pub labels: Vec<String>,
+    pub counts: Vec<u64>,
+}
+
+impl Counts {
+    /// Add `other`'s counts to these, if it's for the same faces.
+    pub fn merge(&mut self, other: &Counts) -> Result<(), Error> {
+        if other.labels != self.labels {
+            return Err(Error::Mismatch);
+        }
+        for (count, &other) in self.counts.iter_mut().zip(&other.counts) {
+            *count = count.saturating_add(other);
+        }
+        Ok(())
+    }
+}
+
+pub fn save(path: &Path, counts: &Counts) -> Result<(), Error> {
+    std::fs::write(path, render(counts)).map_err(Error::Io)
+}
+
+pub fn render(counts: &Counts) -> String {
+    let mut contents = format!("{HEADER}\n");
+    for (roll_key, (label, count)) in counts.labels.iter().zip(&counts.counts).enumerate() {
+        let label = label.replace('"', "\"\"");
+        contents.push_str(&format!("{},\"{label}\",{count}\n", roll_key + 1));
+    }
+    contents
+}
+
+pub fn load(path: &Path) -> Result<Counts, Error> {
+    let contents = std::fs::read_to_string(path).map_err(Error::Io)?;
+    parse(&contents)
+}
+
+pub fn parse(contents: &str) -> Result<Counts, Error> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line));
+    match lines.next() {
+        Some((_, header)) if header.trim() == HEADER => (),
+        _ => return Err(Error::Header),
+    }
+    let mut counts = Counts {
+        labels: Vec::new(),
+        counts: Vec::new(),
+    };
+    for (line_number, line) in lines.filter(|(_, line)| !line.trim().is_empty()) {
+        let invalid = || Error::Line(line_number);
+        let (face, rest) = line.split_once(',').ok_or_else(invalid)?;
+        let (label, count) = rest.rsplit_once(',').ok_or_else(invalid)?;
+        if face.trim().parse::<usize>().ok() != Some(counts.counts.len() + 1) {
+            return Err(invalid());
+        }
+        let label = label
+            .trim()
+            .strip_prefix('"')
+            .and_then(|label| label.strip_suffix('"'))
+            .map_or_else(
+                || label.trim().to_string(),
+                |label| label.replace("\"\"", "\""),
+            );
+        counts.labels.push(label);
+        counts
+            .counts
+            .push(count.trim().parse().map_err(|_| invalid())?);
+    }
+    if counts.counts.is_empty() {
+        return Err(Error::Empty);
+    }
+    Ok(counts)
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    /// The first line isn't the expected header.
+    Header,
+    /// The line number of a row that isn't the next face, a label, and a count.
+    Line(usize),
+    Empty,
+    /// Exports being merged are for different faces.
+    Mismatch,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "could not access counts"),
+            Self::Header => write!(f, "expected counts to start with \"{HEADER}\""),
+            Self::Line(line) => write!(
+                f,
+                "line {line} of counts isn't the next face, a label, and a count"
+            ),
+            Self::Empty => write!(f, "counts have no faces"),
+            Self::Mismatch => write!(f, "counts are for different faces"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Header | Self::Line(_) | Self::Empty | Self::Mismatch => None,
+        }
+    }
+}
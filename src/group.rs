@@ -0,0 +1,82 @@
+//! Ranges of faces shown together as a single bar, such as low, middle, and high rolls.
+
+use std::ops::RangeInclusive;
+
+/// A run of neighboring faces, by zero-based index.
+#[derive(Clone)]
+pub struct Group {
+    pub faces: RangeInclusive<usize>,
+}
+
+/// Split `1..=sides` into consecutive groups of `size` faces, the last taking whatever is left.
+pub fn by_size(size: usize, sides: usize) -> Result<Vec<Group>, Error> {
+    if size == 0 {
+        return Err(Error::ZeroSize);
+    }
+    Ok((0..sides)
+        .step_by(size)
+        .map(|first| Group {
+            faces: first..=(first + size - 1).min(sides - 1),
+        })
+        .collect())
+}
+
+/// Parse ranges of face numbers such as `1-7,8-14,15-20`, which must cover `1..=sides` without
+/// overlapping. A single face can be given on its own, as in `1,2-19,20`.
+pub fn parse(spec: &str, sides: usize) -> Result<Vec<Group>, Error> {
+    let mut groups = spec
+        .split(',')
+        .map(|range| parse_range(range.trim(), sides))
+        .collect::<Result<Vec<_>, _>>()?;
+    groups.sort_by_key(|group| *group.faces.start());
+
+    let mut next = 0;
+    for group in &groups {
+        match group.faces.start().cmp(&next) {
+            std::cmp::Ordering::Less => return Err(Error::Overlap(*group.faces.start() + 1)),
+            std::cmp::Ordering::Greater => return Err(Error::Gap(next + 1)),
+            std::cmp::Ordering::Equal => next = group.faces.end() + 1,
+        }
+    }
+    if next < sides {
+        return Err(Error::Gap(next + 1));
+    }
+    Ok(groups)
+}
+
+fn parse_range(range: &str, sides: usize) -> Result<Group, Error> {
+    let invalid = || Error::Range(range.to_string());
+    let (first, last) = range.split_once('-').unwrap_or((range, range));
+    let first: usize = first.trim().parse().map_err(|_| invalid())?;
+    let last: usize = last.trim().parse().map_err(|_| invalid())?;
+    if first == 0 || first > last || last > sides {
+        return Err(invalid());
+    }
+    Ok(Group {
+        faces: first - 1..=last - 1,
+    })
+}
+
+#[derive(Debug)]
+pub enum Error {
+    ZeroSize,
+    /// A range that isn't `first-last` with `1 <= first <= last <= sides`.
+    Range(String),
+    /// The first face, numbered from 1, that's in more than one group.
+    Overlap(usize),
+    /// The first face, numbered from 1, that isn't in any group.
+    Gap(usize),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroSize => write!(f, "groups must have at least one face"),
+            Self::Range(range) => write!(f, "invalid group {range:?}; expected first-last"),
+            Self::Overlap(face) => write!(f, "face {face} is in more than one group"),
+            Self::Gap(face) => write!(f, "face {face} isn't in any group"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
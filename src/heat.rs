@@ -0,0 +1,107 @@
+//! Which face came up most in each recent slice of time, for a strip showing the texture of the
+//! rolls as they arrive.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How much time each slice covers.
+pub const SLICE_LENGTH: Duration = Duration::from_millis(250);
+
+/// Most slices kept, which is more than any window is wide.
+const CAPACITY: usize = 4096;
+
+/// How far back `window_counts` looks.
+pub const WINDOW: Duration = Duration::from_secs(1);
+
+/// Slices whose counts for every face are kept, to cover `WINDOW`.
+const WINDOW_SLICES: usize = (WINDOW.as_millis() / SLICE_LENGTH.as_millis()) as usize;
+
+#[derive(Clone, Copy, Default)]
+pub struct Slice {
+    /// The face rolled most during the slice, lowest first on ties, if any were rolled.
+    pub top: Option<usize>,
+    pub top_rolls: u64,
+    pub rolls: u64,
+}
+
+pub struct Slices {
+    /// Counts for each face in the slice still underway.
+    current: Vec<u64>,
+    /// When the slice underway started, once the first one has.
+    started: Option<Instant>,
+    /// Finished slices, newest first.
+    past: VecDeque<Slice>,
+    /// Counts for each face in the most recent finished slices, newest first.
+    recent: VecDeque<Vec<u64>>,
+}
+
+impl Slices {
+    pub fn new(sides: usize) -> Self {
+        Self {
+            current: vec![0; sides],
+            started: None,
+            past: VecDeque::new(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, roll_key: usize) {
+        self.current[roll_key] += 1;
+    }
+
+    /// Finish every slice that has ended by `now`, recording any that passed without a frame as
+    /// empty.
+    pub fn advance(&mut self, now: Instant) {
+        let started = self.started.get_or_insert(now);
+        if now.saturating_duration_since(*started) > SLICE_LENGTH * CAPACITY as u32 {
+            // Everything kept would be empty anyway, so skip straight to now.
+            *started = now;
+            self.past.clear();
+            self.recent.clear();
+            self.current.fill(0);
+            return;
+        }
+        while now.saturating_duration_since(*started) >= SLICE_LENGTH {
+            *started += SLICE_LENGTH;
+            let top = (0..self.current.len())
+                .max_by_key(|&roll_key| (self.current[roll_key], std::cmp::Reverse(roll_key)));
+            let slice = Slice {
+                top: top.filter(|&roll_key| self.current[roll_key] > 0),
+                top_rolls: top.map_or(0, |roll_key| self.current[roll_key]),
+                rolls: self.current.iter().sum(),
+            };
+            if self.recent.len() == WINDOW_SLICES {
+                self.recent.pop_back();
+            }
+            self.recent.push_front(self.current.clone());
+            self.current.fill(0);
+            if self.past.len() == CAPACITY {
+                self.past.pop_back();
+            }
+            self.past.push_front(slice);
+        }
+    }
+
+    /// The finished slice `age` slices before the newest, if it's been kept.
+    pub fn get(&self, age: usize) -> Option<&Slice> {
+        self.past.get(age)
+    }
+
+    /// Rolls of each face in the finished slices covering the last `WINDOW`.
+    pub fn window_counts(&self) -> Vec<u64> {
+        let mut counts = vec![0; self.current.len()];
+        for slice in &self.recent {
+            for (count, &rolls) in counts.iter_mut().zip(slice) {
+                *count += rolls;
+            }
+        }
+        counts
+    }
+
+    pub fn clear(&mut self) {
+        self.current.fill(0);
+        self.started = None;
+        self.past.clear();
+        self.recent.clear();
+    }
+}
@@ -0,0 +1,126 @@
+//! Holding the winner and loser highlights on a face until another has kept the lead for a
+//! while, so they don't flicker between columns early in a run.
+
+use std::time::{Duration, Instant};
+
+/// The face shown as leading, which only changes once a new leader has held on for `delay`.
+pub struct Debounced {
+    delay: Duration,
+    shown: Option<usize>,
+    /// A new leader waiting out the delay, and when it took the lead.
+    candidate: Option<(Option<usize>, Instant)>,
+}
+
+impl Debounced {
+    /// Follow the leader, changing only after a new one has led for `delay`; zero follows it at
+    /// once.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            shown: None,
+            candidate: None,
+        }
+    }
+
+    /// Forget the leader, as after a reset.
+    pub fn clear(&mut self) {
+        self.shown = None;
+        self.candidate = None;
+    }
+
+    /// The face to highlight.
+    pub fn shown(&self) -> Option<usize> {
+        self.shown
+    }
+
+    /// Take `leader` as leading at `now`, showing it once it has led for the delay.
+    pub fn update(&mut self, leader: Option<usize>, now: Instant) {
+        if leader == self.shown {
+            self.candidate = None;
+            return;
+        }
+        let since = match self.candidate {
+            Some((candidate, since)) if candidate == leader => since,
+            // A new challenger, or a different one than before, starts the wait over.
+            _ => {
+                self.candidate = Some((leader, now));
+                now
+            }
+        };
+        if now.saturating_duration_since(since) >= self.delay {
+            self.shown = leader;
+            self.candidate = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(300);
+
+    fn after(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn zero_delay_follows_at_once() {
+        let start = Instant::now();
+        let mut debounced = Debounced::new(Duration::ZERO);
+        for (millis, leader) in [(0, Some(3)), (1, Some(4)), (2, None), (3, Some(3))] {
+            debounced.update(leader, after(start, millis));
+            assert_eq!(debounced.shown(), leader);
+        }
+    }
+
+    #[test]
+    fn holds_until_the_new_leader_has_led_for_the_delay() {
+        let start = Instant::now();
+        let mut debounced = Debounced::new(DELAY);
+        debounced.update(Some(3), start);
+        assert_eq!(debounced.shown(), None);
+        debounced.update(Some(3), after(start, 299));
+        assert_eq!(debounced.shown(), None);
+        debounced.update(Some(3), after(start, 300));
+        assert_eq!(debounced.shown(), Some(3));
+    }
+
+    #[test]
+    fn rapid_oscillation_keeps_the_shown_leader() {
+        let start = Instant::now();
+        let mut debounced = Debounced::new(DELAY);
+        debounced.update(Some(3), start);
+        debounced.update(Some(3), after(start, 300));
+        // Two faces trade the lead every 50ms for two seconds.
+        for step in 1..=40 {
+            let leader = if step % 2 == 0 { Some(3) } else { Some(7) };
+            debounced.update(leader, after(start, 300 + step * 50));
+            assert_eq!(debounced.shown(), Some(3), "step {step}");
+        }
+    }
+
+    #[test]
+    fn a_new_challenger_restarts_the_wait() {
+        let start = Instant::now();
+        let mut debounced = Debounced::new(DELAY);
+        debounced.update(Some(1), start);
+        debounced.update(Some(1), after(start, 300));
+        debounced.update(Some(2), after(start, 400));
+        debounced.update(Some(5), after(start, 600));
+        debounced.update(Some(5), after(start, 800));
+        assert_eq!(debounced.shown(), Some(1));
+        debounced.update(Some(5), after(start, 900));
+        assert_eq!(debounced.shown(), Some(5));
+    }
+
+    #[test]
+    fn clear_forgets_the_leader() {
+        let start = Instant::now();
+        let mut debounced = Debounced::new(DELAY);
+        debounced.update(Some(1), start);
+        debounced.update(Some(1), after(start, 300));
+        debounced.clear();
+        assert_eq!(debounced.shown(), None);
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+/// Counts of durations in logarithmically sized buckets, so milliseconds and minutes can share a
+/// chart.
+pub struct LogHistogram {
+    min: Duration,
+    max: Duration,
+    buckets: Vec<u64>,
+    total: u64,
+}
+
+impl LogHistogram {
+    /// Buckets spanning `min..max`; anything outside that range lands in the first or last bucket.
+    pub fn new(min: Duration, max: Duration, bucket_count: usize) -> Self {
+        Self {
+            min,
+            max,
+            buckets: vec![0; bucket_count.max(1)],
+            total: 0,
+        }
+    }
+
+    pub fn record(&mut self, duration: Duration) {
+        let index = self.bucket_index(duration);
+        self.buckets[index] += 1;
+        self.total += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(|count| *count = 0);
+        self.total = 0;
+    }
+
+    pub fn buckets(&self) -> &[u64] {
+        &self.buckets
+    }
+
+    fn bucket_index(&self, duration: Duration) -> usize {
+        let position = (duration.as_secs_f64() / self.min.as_secs_f64()).ln()
+            / (self.max.as_secs_f64() / self.min.as_secs_f64()).ln();
+        let index = (position * self.buckets.len() as f64).floor();
+        (index.max(0.) as usize).min(self.buckets.len() - 1)
+    }
+
+    /// The upper edge of `index`'s bucket.
+    pub fn bucket_upper_bound(&self, index: usize) -> Duration {
+        let ratio = self.max.as_secs_f64() / self.min.as_secs_f64();
+        let position = (index + 1) as f64 / self.buckets.len() as f64;
+        Duration::from_secs_f64(self.min.as_secs_f64() * ratio.powf(position))
+    }
+
+    /// Approximate `quantile` (from 0 to 1) of the recorded durations, as the upper edge of the
+    /// bucket it falls in.
+    pub fn quantile(&self, quantile: f64) -> Option<Duration> {
+        if self.total == 0 {
+            return None;
+        }
+        let target = (quantile * self.total as f64).ceil().max(1.) as u64;
+        let mut seen = 0;
+        for (index, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return Some(self.bucket_upper_bound(index));
+            }
+        }
+        Some(self.max)
+    }
+}
+
+/// Format `duration` compactly, picking whichever unit keeps it readable.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds < 1. {
+        format!("{:.0}ms", seconds * 1000.)
+    } else {
+        format!("{seconds:.1}s")
+    }
+}
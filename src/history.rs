@@ -0,0 +1,144 @@
+//! Counts from past segments of a long-running session, closed off at regular wall-clock
+//! boundaries and kept on disk one JSON line each.
+
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where segments are appended, relative to the working directory.
+pub const PATH: &str = "d20-history.jsonl";
+
+/// How often to close off a segment and start counting afresh, on UTC boundaries.
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rollover {
+    Hour,
+    Day,
+}
+
+impl Rollover {
+    fn period(self) -> u64 {
+        match self {
+            Self::Hour => 60 * 60,
+            Self::Day => 24 * 60 * 60,
+        }
+    }
+
+    /// The latest boundary at or before `now`, in seconds since the Unix epoch.
+    pub fn last_boundary(self, now: u64) -> u64 {
+        now / self.period() * self.period()
+    }
+
+    /// The first boundary after `now`, in seconds since the Unix epoch.
+    pub fn next_boundary(self, now: u64) -> u64 {
+        self.last_boundary(now) + self.period()
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Segment {
+    /// When counting started, in seconds since the Unix epoch.
+    pub start: u64,
+    /// The boundary that closed the segment, in seconds since the Unix epoch.
+    pub end: u64,
+    pub total_rolls: u64,
+    /// How many times each face came up.
+    pub counts: Vec<u64>,
+}
+
+impl Segment {
+    /// When the segment ran, for display.
+    pub fn describe(&self) -> String {
+        format!(
+            "{} to {} UTC",
+            format_time(self.start),
+            format_time(self.end)
+        )
+    }
+}
+
+/// Load every segment recorded at `path`, if there are any.
+pub fn load(path: &Path) -> Result<Vec<Segment>, Error> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(Error::Io(err)),
+    };
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(Error::Json))
+        .collect()
+}
+
+/// Add `segment` to the end of the history at `path`.
+pub fn append(path: &Path, segment: &Segment) -> Result<(), Error> {
+    let mut line = serde_json::to_string(segment).map_err(Error::Json)?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(Error::Io)?;
+    file.write_all(line.as_bytes()).map_err(Error::Io)
+}
+
+/// `time` in seconds since the Unix epoch.
+pub fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Format seconds since the Unix epoch as `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_time(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64;
+    let minutes = seconds % 86400 / 60;
+
+    // Convert days since the epoch to a civil date, per Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "could not access history"),
+            Self::Json(_) => write!(f, "could not parse history"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Json(err) => Some(err),
+        }
+    }
+}
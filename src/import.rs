@@ -0,0 +1,97 @@
+//! Counts read back from the statistics JSON sent to remote clients, to pick a run up where it
+//! left off or to compare against someone else's.
+
+use serde::Deserialize;
+use std::path::Path;
+
+/// The parts of the statistics that matter for importing; anything else is ignored.
+#[derive(Deserialize)]
+struct Stats {
+    /// Faces on the die, which older statistics leave out.
+    sides: Option<usize>,
+    total_rolls: Option<u64>,
+    counts: Vec<u64>,
+}
+
+/// Each face's count from the statistics at `path`, checked against a die with `sides` faces.
+pub fn load(path: &Path, sides: usize) -> Result<Vec<u64>, Error> {
+    let contents = std::fs::read_to_string(path).map_err(Error::Io)?;
+    parse(&contents, sides)
+}
+
+pub fn parse(contents: &str, sides: usize) -> Result<Vec<u64>, Error> {
+    let stats: Stats = serde_json::from_str(contents).map_err(Error::Json)?;
+    let found = stats.sides.unwrap_or(stats.counts.len());
+    if found != sides {
+        return Err(Error::Sides {
+            expected: sides,
+            found,
+        });
+    }
+    if stats.counts.len() != sides {
+        return Err(Error::Counts {
+            sides,
+            found: stats.counts.len(),
+        });
+    }
+    let sum = stats
+        .counts
+        .iter()
+        .fold(0u64, |total, &count| total.saturating_add(count));
+    match stats.total_rolls {
+        Some(total_rolls) if total_rolls != sum => Err(Error::Total { total_rolls, sum }),
+        _ => Ok(stats.counts),
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// The counts are for a die with a different number of faces.
+    Sides {
+        expected: usize,
+        found: usize,
+    },
+    /// There isn't one count for each of the die's faces.
+    Counts {
+        sides: usize,
+        found: usize,
+    },
+    /// The total doesn't match the counts added up.
+    Total {
+        total_rolls: u64,
+        sum: u64,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "could not access imported counts"),
+            Self::Json(_) => write!(f, "could not parse imported counts"),
+            Self::Sides { expected, found } => write!(
+                f,
+                "imported counts are for a d{found}, but the die is a d{expected}"
+            ),
+            Self::Counts { sides, found } => write!(
+                f,
+                "imported counts have {found} faces' counts for a d{sides}"
+            ),
+            Self::Total { total_rolls, sum } => write!(
+                f,
+                "imported counts add up to {sum}, not their total of {total_rolls}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Json(err) => Some(err),
+            Self::Sides { .. } | Self::Counts { .. } | Self::Total { .. } => None,
+        }
+    }
+}
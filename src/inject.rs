@@ -0,0 +1,102 @@
+//! Making operations fail on purpose, to try out how their failures are handled.
+//!
+//! Failures can only be asked for in debug builds, with `--inject-failure`; release builds never
+//! inject any.
+
+/// An operation that can be made to fail.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Point {
+    /// Presenting a frame, failing as if the surface were lost.
+    Render,
+    /// Resizing the surface to match the window.
+    ResizeSurface,
+    /// Writing reports, exports, and summaries.
+    ExportIo,
+}
+
+impl Point {
+    const ALL: [Self; 3] = [Self::Render, Self::ResizeSurface, Self::ExportIo];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Render => "render",
+            Self::ResizeSurface => "resize-surface",
+            Self::ExportIo => "export-io",
+        }
+    }
+}
+
+/// Make `point` fail its first `times` times.
+#[cfg(debug_assertions)]
+#[derive(Clone, Copy)]
+pub struct Injection {
+    pub point: Point,
+    pub times: u32,
+}
+
+/// Parse an injection such as `render` or `export-io:3`, failing once if no count is given.
+#[cfg(debug_assertions)]
+pub fn parse(spec: &str) -> Result<Injection, InvalidInjection> {
+    let invalid = || InvalidInjection(spec.to_string());
+    let (name, times) = match spec.split_once(':') {
+        Some((name, times)) => (name, times.parse().map_err(|_| invalid())?),
+        None => (spec, 1),
+    };
+    let point = Point::ALL
+        .into_iter()
+        .find(|point| point.name() == name)
+        .ok_or_else(invalid)?;
+    Ok(Injection { point, times })
+}
+
+/// How many more times each point has to fail.
+#[derive(Default)]
+pub struct Failures {
+    remaining: [u32; Point::ALL.len()],
+}
+
+impl Failures {
+    #[cfg(debug_assertions)]
+    pub fn new(injections: &[Injection]) -> Self {
+        let mut failures = Self::default();
+        for injection in injections {
+            let remaining = &mut failures.remaining[injection.point as usize];
+            *remaining = remaining.saturating_add(injection.times);
+        }
+        failures
+    }
+
+    /// Whether `point` should fail this time, counting it off if so.
+    pub fn take(&mut self, point: Point) -> bool {
+        let remaining = &mut self.remaining[point as usize];
+        if *remaining == 0 {
+            return false;
+        }
+        *remaining -= 1;
+        log::warn!("injecting a {} failure", point.name());
+        true
+    }
+}
+
+/// The error injected in place of writing a file.
+pub fn io_error() -> std::io::Error {
+    std::io::Error::other("injected failure")
+}
+
+#[cfg(debug_assertions)]
+#[derive(Debug)]
+pub struct InvalidInjection(pub String);
+
+#[cfg(debug_assertions)]
+impl std::fmt::Display for InvalidInjection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid failure {:?}; expected render, resize-surface, or export-io, optionally followed by a count, such as \"export-io:3\"",
+            self.0
+        )
+    }
+}
+
+#[cfg(debug_assertions)]
+impl std::error::Error for InvalidInjection {}
@@ -0,0 +1,391 @@
+//! Keyboard shortcuts and the actions they trigger. Any action can be rebound in the config file
+//! to a key along with the modifiers to hold, such as `ctrl+shift+s`:
+//!
+//! ```toml
+//! [keys]
+//! pause = "p"
+//! toggle_p_value = "shift+p"
+//! quit = "ctrl+q"
+//! ```
+//!
+//! Actions left out keep their default keys.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use winit::event::{ModifiersState, VirtualKeyCode};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Reset,
+    /// Even out the counts without losing any rolls, as if the die had come up perfectly fair.
+    SoftReset,
+    Pause,
+    ToggleExpected,
+    TogglePValue,
+    ToggleSmoothed,
+    ToggleMirrored,
+    ToggleBigTotal,
+    /// Pin the top of the chart to the tallest bar's count, or go back to scaling to fit.
+    TogglePin,
+    /// Order the bars from most to least rolled instead of by face.
+    ToggleSorted,
+    /// Switch the bars between counts and how far each is above or below what's expected.
+    ToggleDeviations,
+    /// Switch to a grid of how often each face follows each other face.
+    ToggleTransitions,
+    /// Preview the chart as seen with each kind of color blindness.
+    ToggleColorblindPreview,
+    /// Switch between laying the chart out in physical and logical pixels.
+    ToggleLogicalLayout,
+    ExportConfig,
+    SaveCounts,
+    WriteReport,
+    WriteSvg,
+    ToggleSpotlight,
+    /// Raise the spotlight threshold while spotlighting, or else the DC.
+    Raise,
+    /// Lower the spotlight threshold while spotlighting, or else the DC.
+    Lower,
+    ToggleDc,
+    CycleNotation,
+    CycleHighlights,
+    /// Bring back every face hidden from the chart.
+    ShowHidden,
+    PrintChart,
+    CycleView,
+    /// Open another window showing the current view, which can then be changed on its own.
+    NewWindow,
+    DeleteLastNote,
+    ToggleLegend,
+    ToggleStats,
+    CycleSnapshot,
+    /// Switch to the next place rolls can come from, keeping the counts.
+    CycleSource,
+    OlderSegment,
+    NewerSegment,
+    #[serde(alias = "rate_up")]
+    SpeedUp,
+    #[serde(alias = "rate_down")]
+    SlowDown,
+    #[serde(alias = "quit")]
+    Close,
+}
+
+/// A key pressed while holding exactly a set of modifiers.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    pub key: VirtualKeyCode,
+    pub modifiers: ModifiersState,
+}
+
+impl Chord {
+    const fn plain(key: VirtualKeyCode) -> Self {
+        Self {
+            key,
+            modifiers: ModifiersState::empty(),
+        }
+    }
+
+    /// Parse modifiers and a key joined by `+`, such as `ctrl+shift+s` or `f5`.
+    pub fn parse(spec: &str) -> Result<Self, InvalidChord> {
+        let invalid = || InvalidChord(spec.to_string());
+        let lowercase = spec.to_ascii_lowercase();
+        // Split off the key first, since it may itself be `+` or `-`.
+        let (modifier_names, key_name) = match lowercase.rsplit_once('+') {
+            Some(("", "")) => ("", "+"),
+            Some((modifiers, "")) => (modifiers.strip_suffix('+').ok_or_else(invalid)?, "+"),
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", lowercase.as_str()),
+        };
+        let mut modifiers = ModifiersState::empty();
+        for name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= match name.trim() {
+                "ctrl" | "control" => ModifiersState::CTRL,
+                "shift" => ModifiersState::SHIFT,
+                "alt" | "option" => ModifiersState::ALT,
+                "logo" | "super" | "cmd" | "meta" => ModifiersState::LOGO,
+                _ => return Err(invalid()),
+            };
+        }
+        let key = KEY_NAMES
+            .iter()
+            .find(|(name, _)| *name == key_name.trim())
+            .map(|&(_, key)| key)
+            .ok_or_else(invalid)?;
+        Ok(Self { key, modifiers })
+    }
+}
+
+impl std::fmt::Display for Chord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = [
+            (ModifiersState::CTRL, "ctrl"),
+            (ModifiersState::SHIFT, "shift"),
+            (ModifiersState::ALT, "alt"),
+            (ModifiersState::LOGO, "logo"),
+        ];
+        for (modifier, name) in names {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+        let name = KEY_NAMES
+            .iter()
+            .find(|&&(_, key)| key == self.key)
+            .map_or("?", |&(name, _)| name);
+        write!(f, "{name}")
+    }
+}
+
+/// Every action's chords, with overrides from the config taking the place of the defaults.
+pub struct Keymap {
+    bindings: Vec<(Chord, Action)>,
+}
+
+impl Keymap {
+    /// The default bindings, except that each action in `overrides` is bound to its chord instead.
+    pub fn new(overrides: &BTreeMap<Action, Chord>) -> Self {
+        // Overrides come first so they win when they reuse a default's chord.
+        let bindings = overrides
+            .iter()
+            .map(|(&action, &chord)| (chord, action))
+            .chain(
+                DEFAULTS
+                    .iter()
+                    .filter(|(_, action)| !overrides.contains_key(action))
+                    .copied(),
+            )
+            .collect();
+        Self { bindings }
+    }
+
+    /// The action bound to pressing `key` while holding `modifiers`, if any.
+    pub fn action(&self, key: VirtualKeyCode, modifiers: ModifiersState) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(chord, _)| chord.key == key && chord.modifiers == modifiers)
+            .map(|&(_, action)| action)
+    }
+
+    /// The chord bound to `action`, if any, preferring one from the config.
+    pub fn chord(&self, action: Action) -> Option<Chord> {
+        self.bindings
+            .iter()
+            .find(|&&(_, bound)| bound == action)
+            .map(|&(chord, _)| chord)
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new())
+    }
+}
+
+const DEFAULTS: [(Chord, Action); 40] = [
+    (Chord::plain(VirtualKeyCode::F5), Action::Reset),
+    (
+        Chord {
+            key: VirtualKeyCode::F5,
+            modifiers: ModifiersState::SHIFT,
+        },
+        Action::SoftReset,
+    ),
+    (Chord::plain(VirtualKeyCode::Space), Action::Pause),
+    (Chord::plain(VirtualKeyCode::E), Action::ToggleExpected),
+    (Chord::plain(VirtualKeyCode::P), Action::TogglePValue),
+    (
+        Chord {
+            key: VirtualKeyCode::P,
+            modifiers: ModifiersState::SHIFT,
+        },
+        Action::TogglePin,
+    ),
+    (Chord::plain(VirtualKeyCode::Y), Action::ToggleSmoothed),
+    (Chord::plain(VirtualKeyCode::M), Action::ToggleMirrored),
+    (Chord::plain(VirtualKeyCode::O), Action::ToggleBigTotal),
+    (
+        Chord {
+            key: VirtualKeyCode::O,
+            modifiers: ModifiersState::SHIFT,
+        },
+        Action::ToggleSorted,
+    ),
+    (Chord::plain(VirtualKeyCode::L), Action::ToggleLogicalLayout),
+    (Chord::plain(VirtualKeyCode::C), Action::ExportConfig),
+    (
+        Chord {
+            key: VirtualKeyCode::C,
+            modifiers: ModifiersState::SHIFT,
+        },
+        Action::SaveCounts,
+    ),
+    (
+        Chord {
+            key: VirtualKeyCode::C,
+            modifiers: ModifiersState::CTRL.union(ModifiersState::SHIFT),
+        },
+        Action::ToggleColorblindPreview,
+    ),
+    (Chord::plain(VirtualKeyCode::R), Action::WriteReport),
+    (Chord::plain(VirtualKeyCode::G), Action::WriteSvg),
+    (Chord::plain(VirtualKeyCode::S), Action::ToggleSpotlight),
+    (Chord::plain(VirtualKeyCode::Up), Action::Raise),
+    (Chord::plain(VirtualKeyCode::Down), Action::Lower),
+    (Chord::plain(VirtualKeyCode::D), Action::ToggleDc),
+    (Chord::plain(VirtualKeyCode::F), Action::CycleNotation),
+    (Chord::plain(VirtualKeyCode::H), Action::CycleHighlights),
+    (
+        Chord {
+            key: VirtualKeyCode::H,
+            modifiers: ModifiersState::CTRL,
+        },
+        Action::ShowHidden,
+    ),
+    (Chord::plain(VirtualKeyCode::V), Action::CycleView),
+    (
+        Chord {
+            key: VirtualKeyCode::N,
+            modifiers: ModifiersState::CTRL,
+        },
+        Action::NewWindow,
+    ),
+    (
+        Chord {
+            key: VirtualKeyCode::V,
+            modifiers: ModifiersState::SHIFT,
+        },
+        Action::ToggleDeviations,
+    ),
+    (Chord::plain(VirtualKeyCode::X), Action::ToggleTransitions),
+    (Chord::plain(VirtualKeyCode::T), Action::PrintChart),
+    (
+        Chord {
+            key: VirtualKeyCode::Semicolon,
+            modifiers: ModifiersState::CTRL,
+        },
+        Action::DeleteLastNote,
+    ),
+    (Chord::plain(VirtualKeyCode::K), Action::ToggleLegend),
+    (
+        Chord {
+            key: VirtualKeyCode::S,
+            modifiers: ModifiersState::SHIFT,
+        },
+        Action::ToggleStats,
+    ),
+    (Chord::plain(VirtualKeyCode::Tab), Action::CycleSnapshot),
+    (
+        Chord {
+            key: VirtualKeyCode::I,
+            modifiers: ModifiersState::CTRL,
+        },
+        Action::CycleSource,
+    ),
+    (Chord::plain(VirtualKeyCode::PageUp), Action::OlderSegment),
+    (Chord::plain(VirtualKeyCode::PageDown), Action::NewerSegment),
+    (Chord::plain(VirtualKeyCode::Equals), Action::SpeedUp),
+    (Chord::plain(VirtualKeyCode::Period), Action::SpeedUp),
+    (Chord::plain(VirtualKeyCode::Minus), Action::SlowDown),
+    (Chord::plain(VirtualKeyCode::Comma), Action::SlowDown),
+    (Chord::plain(VirtualKeyCode::Escape), Action::Close),
+];
+
+/// Names keys can be given by, with the one shown for each key first.
+const KEY_NAMES: &[(&str, VirtualKeyCode)] = &[
+    ("a", VirtualKeyCode::A),
+    ("b", VirtualKeyCode::B),
+    ("c", VirtualKeyCode::C),
+    ("d", VirtualKeyCode::D),
+    ("e", VirtualKeyCode::E),
+    ("f", VirtualKeyCode::F),
+    ("g", VirtualKeyCode::G),
+    ("h", VirtualKeyCode::H),
+    ("i", VirtualKeyCode::I),
+    ("j", VirtualKeyCode::J),
+    ("k", VirtualKeyCode::K),
+    ("l", VirtualKeyCode::L),
+    ("m", VirtualKeyCode::M),
+    ("n", VirtualKeyCode::N),
+    ("o", VirtualKeyCode::O),
+    ("p", VirtualKeyCode::P),
+    ("q", VirtualKeyCode::Q),
+    ("r", VirtualKeyCode::R),
+    ("s", VirtualKeyCode::S),
+    ("t", VirtualKeyCode::T),
+    ("u", VirtualKeyCode::U),
+    ("v", VirtualKeyCode::V),
+    ("w", VirtualKeyCode::W),
+    ("x", VirtualKeyCode::X),
+    ("y", VirtualKeyCode::Y),
+    ("z", VirtualKeyCode::Z),
+    ("0", VirtualKeyCode::Key0),
+    ("1", VirtualKeyCode::Key1),
+    ("2", VirtualKeyCode::Key2),
+    ("3", VirtualKeyCode::Key3),
+    ("4", VirtualKeyCode::Key4),
+    ("5", VirtualKeyCode::Key5),
+    ("6", VirtualKeyCode::Key6),
+    ("7", VirtualKeyCode::Key7),
+    ("8", VirtualKeyCode::Key8),
+    ("9", VirtualKeyCode::Key9),
+    ("f1", VirtualKeyCode::F1),
+    ("f2", VirtualKeyCode::F2),
+    ("f3", VirtualKeyCode::F3),
+    ("f4", VirtualKeyCode::F4),
+    ("f5", VirtualKeyCode::F5),
+    ("f6", VirtualKeyCode::F6),
+    ("f7", VirtualKeyCode::F7),
+    ("f8", VirtualKeyCode::F8),
+    ("f9", VirtualKeyCode::F9),
+    ("f10", VirtualKeyCode::F10),
+    ("f11", VirtualKeyCode::F11),
+    ("f12", VirtualKeyCode::F12),
+    ("up", VirtualKeyCode::Up),
+    ("down", VirtualKeyCode::Down),
+    ("left", VirtualKeyCode::Left),
+    ("right", VirtualKeyCode::Right),
+    ("pageup", VirtualKeyCode::PageUp),
+    ("pagedown", VirtualKeyCode::PageDown),
+    ("home", VirtualKeyCode::Home),
+    ("end", VirtualKeyCode::End),
+    ("insert", VirtualKeyCode::Insert),
+    ("delete", VirtualKeyCode::Delete),
+    ("backspace", VirtualKeyCode::Back),
+    ("tab", VirtualKeyCode::Tab),
+    ("space", VirtualKeyCode::Space),
+    ("enter", VirtualKeyCode::Return),
+    ("return", VirtualKeyCode::Return),
+    ("escape", VirtualKeyCode::Escape),
+    ("esc", VirtualKeyCode::Escape),
+    ("=", VirtualKeyCode::Equals),
+    ("equals", VirtualKeyCode::Equals),
+    ("+", VirtualKeyCode::Plus),
+    ("plus", VirtualKeyCode::Plus),
+    ("-", VirtualKeyCode::Minus),
+    ("minus", VirtualKeyCode::Minus),
+    (".", VirtualKeyCode::Period),
+    ("period", VirtualKeyCode::Period),
+    (",", VirtualKeyCode::Comma),
+    ("comma", VirtualKeyCode::Comma),
+    ("/", VirtualKeyCode::Slash),
+    ("slash", VirtualKeyCode::Slash),
+    (";", VirtualKeyCode::Semicolon),
+    ("semicolon", VirtualKeyCode::Semicolon),
+];
+
+#[derive(Debug)]
+pub struct InvalidChord(pub String);
+
+impl std::fmt::Display for InvalidChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid key {:?}; expected a key and any modifiers, such as \"ctrl+shift+s\"",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidChord {}
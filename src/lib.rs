@@ -0,0 +1,5691 @@
+//! A bar chart of dice rolls, counting every roll of a d20 and drawing how often each face
+//! has come up into any RGBA buffer.
+//!
+//! Embed it by feeding it your own rolls and drawing it into a frame whenever you redraw. A
+//! [`pixels`](https://docs.rs/pixels) frame, or any other buffer of `width * height` RGBA
+//! pixels, will do:
+//!
+//! ```
+//! use d20::{View, World};
+//!
+//! let (width, height) = (320, 200);
+//! let mut world = World::new(width, height);
+//! // Zero-based faces, so these are a 1, two 20s and an 8.
+//! world.push_rolls(&[0, 19, 19, 7]);
+//! world.set_view(View::Expected);
+//!
+//! let mut frame = vec![0; width as usize * height as usize * 4];
+//! world.draw_into(&mut frame, width, height);
+//! ```
+//!
+//! [`World`] and [`View`] follow semver. Everything else is there for the `d20` binary and can
+//! change in any release.
+//!
+//! The binary needs the default `app` feature. Embedders can turn it off to leave out winit and
+//! the rest of its windowing dependencies:
+//!
+//! ```toml
+//! d20 = { version = "0.1", default-features = false }
+//! ```
+
+#![deny(clippy::all)]
+#![forbid(unsafe_code)]
+// Much of `World` is only driven from the windowed app.
+#![cfg_attr(not(feature = "app"), allow(dead_code))]
+
+use error_iter::ErrorIter as _;
+use log::{error, info, warn};
+use rand::distributions::{Distribution as _, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+#[cfg(feature = "app")]
+use winit::event::ModifiersState;
+
+mod accounting;
+#[cfg(feature = "app")]
+#[doc(hidden)]
+pub mod app;
+mod ascii;
+mod bench;
+mod bet;
+mod capture;
+mod checksum;
+mod classes;
+mod cli;
+mod compositor;
+mod config;
+mod convergence;
+mod demo;
+mod easing;
+mod ensemble;
+mod export;
+mod group;
+mod heat;
+mod highlight;
+mod histogram;
+mod history;
+mod import;
+mod inject;
+#[cfg(feature = "app")]
+mod keymap;
+mod menu;
+mod meter;
+mod metrics;
+mod notation;
+mod overlay;
+mod pages;
+mod platform;
+mod ramp;
+mod record;
+mod remote;
+mod report;
+mod rounds;
+mod runlog;
+mod smooth;
+mod snapshot;
+mod source;
+mod stall;
+mod summary;
+mod svg;
+mod text;
+mod theme;
+mod title;
+mod tolerance;
+mod tournament;
+mod transitions;
+
+use accounting::Accounting;
+use bet::Bet;
+use checksum::RollHash;
+use classes::{Classing, Tallies};
+use compositor::{Anchor, Rect, Request, Tier};
+use demo::Demo;
+use easing::Easing;
+use ensemble::Ensemble;
+use group::Group;
+use highlight::Debounced;
+use histogram::LogHistogram;
+use history::Rollover;
+#[cfg(feature = "app")]
+use keymap::{Action, Keymap};
+use menu::Menu;
+use metrics::Metrics;
+use notation::Notation;
+use overlay::Overlay;
+use pages::Pages;
+use ramp::Ramp;
+use rounds::Rounds;
+use snapshot::Snapshot;
+use source::RollSource;
+use stall::Stall;
+use theme::Theme;
+use title::{Segment, TitleBar};
+use tolerance::Fit;
+use transitions::Transitions;
+
+/// Rolls made between checks of the frame budget, so checking the clock stays cheap.
+const ROLL_CHUNK: u64 = 1024;
+
+/// Speed multipliers available through the speed keys, slowest first.
+const SPEEDS: [f64; 10] = [0.1, 0.2, 0.5, 1., 2., 5., 10., 20., 50., 100.];
+
+/// Buckets in the histogram of time between externally sourced rolls.
+const ARRIVAL_GAP_BUCKETS: usize = 24;
+
+/// Shown at the start of the window title.
+const APP_NAME: &str = "d20 visualizer";
+
+/// Starting threshold for the spotlight, in standard deviations from a fair die.
+const DEFAULT_SPOTLIGHT_THRESHOLD: f64 = 2.;
+
+/// How much each press of Up or Down changes the spotlight threshold.
+const SPOTLIGHT_STEP: f64 = 0.5;
+
+/// DC shown when it's first turned on, if none is configured.
+const DEFAULT_DC: usize = 11;
+
+/// How long the window flashes when the counts start to look biased.
+const FLASH_DURATION: Duration = Duration::from_millis(800);
+
+/// Rolls each face should expect before the chi-square test is trusted to call the die biased.
+const MIN_EXPECTED_PER_FACE: u64 = 5;
+
+/// How often sorted bars are put back in order, so close counts don't swap places every frame.
+const SORT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How far from chance a pair of faces has to follow each other, as a share of what chance
+/// gives, to be shaded fully in the winner's or loser's color.
+const TRANSITION_FULL_SCALE: f64 = 0.5;
+
+/// How long informational status messages stay on screen.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long to wait for a roll source's thread to stop when switching away from it.
+const SOURCE_STOP_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// How soon a second interrupt has to follow the first to exit without cleaning up.
+const FORCE_EXIT_WINDOW: Duration = Duration::from_secs(2);
+
+/// Room left above the tallest bar whenever the scale is set, so it can grow this much before
+/// the scale has to change again.
+const SCALE_HEADROOM: f64 = 1.1;
+
+/// How far the tallest bar can shrink from where it was when the scale was set before the scale
+/// changes to match.
+const SCALE_SHRINK: f64 = 0.8;
+
+/// How long the bars take to drain to nothing when resetting by hand.
+const DRAIN_DURATION: Duration = Duration::from_millis(500);
+
+/// How long the border blinks after the counts converge before it stays lit.
+const CONVERGENCE_BLINK: Duration = Duration::from_secs(2);
+
+/// Most recent notes listed over the chart.
+const NOTES_SHOWN: usize = 5;
+
+/// Shortest window that still has room for the heat strip beneath the labels.
+const MIN_HEAT_STRIP_WINDOW_HEIGHT: u32 = 300;
+
+/// What a window shows, apart from the counts and settings every window shares.
+#[derive(Default)]
+struct WindowView {
+    classing: Option<Classing>,
+    meter: Option<meter::Meter>,
+}
+
+/// Parts of the client-side title bar that respond to clicks.
+enum TitleBarHit {
+    Close,
+    Drag,
+}
+
+/// A fixed number of rolls to make before stopping.
+struct Budget {
+    total: u64,
+    spent: u64,
+}
+
+impl Budget {
+    fn is_spent(&self) -> bool {
+        self.spent >= self.total
+    }
+}
+
+/// Where the legend's swatches go.
+struct LegendLayout {
+    columns: u32,
+    entry_width: u32,
+    entry_height: u32,
+    /// Widest a label can be drawn before it's cut short.
+    label_width: u32,
+    height: u32,
+}
+
+/// Draws an overlay into the frame at the spot the compositor gave it.
+type DrawFn<'a> = Box<dyn Fn(&mut [u8], Rect) + 'a>;
+
+/// An overlay waiting for the compositor to say where to draw it.
+struct Panel<'a> {
+    request: Request,
+    draw: DrawFn<'a>,
+}
+
+struct StatusMessage {
+    text: String,
+    /// When the message should disappear, or `None` to keep it until replaced.
+    expires: Option<Instant>,
+}
+
+/// A note typed during a run, such as what was just changed.
+struct Note {
+    text: String,
+    /// When the note was made, for placing it on the heat strip.
+    at: Instant,
+    /// When the note was made, in seconds since the Unix epoch.
+    unix_seconds: u64,
+    /// Rolls made since the last reset when the note was made.
+    total_rolls: u64,
+}
+
+/// How the counts settled, once they have.
+struct Converged {
+    rolls: u64,
+    /// Time from the start of counting to converging.
+    elapsed: Duration,
+    at: Instant,
+}
+
+/// What the chart shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum View {
+    /// A bar per face, as tall as the times it's come up.
+    #[default]
+    Counts,
+    /// Each bar split into what's been rolled, on the left, and what's expected by now, on the
+    /// right.
+    Expected,
+    /// Every face dimmed except those whose counts are furthest from what's expected.
+    Spotlight,
+}
+
+/// Every roll counted so far, and how to draw them.
+pub struct World {
+    roll_counts: [u64; 20],
+    /// Statistics for `face_totals`, recomputed once per update.
+    metrics: Metrics,
+    /// Total number of rolls since the last reset, unaffected by rescaling.
+    total_rolls: u64,
+    /// Number of times each face has come up since the last reset, unaffected by rescaling.
+    face_totals: [u64; 20],
+    /// Where every roll since the last reset went.
+    accounting: Accounting,
+    /// Rolls in each class of faces, such as odd or even, counted with `face_totals`.
+    tallies: Tallies,
+    /// The classes shown in place of the faces, if any.
+    classing: Option<Classing>,
+    /// Each face's share of the last second's rolls, when showing those in place of the faces.
+    meter: Option<meter::Meter>,
+    rng: StdRng,
+    /// Seed `rng` is reset to, or `None` to reseed from entropy.
+    seed: Option<u64>,
+    /// Relative chance of each face coming up as configured, or `None` for a fair die.
+    configured_weights: Option<Vec<f64>>,
+    /// Faces that never come up, as if the die were missing them.
+    excluded: [bool; 20],
+    /// Relative chance of each face coming up, with excluded faces given none.
+    weights: Vec<f64>,
+    /// How far each face's share of the rolls may stray from what its weight gives it, or
+    /// `None` to not hold the faces to a target.
+    tolerances: Option<Vec<f64>>,
+    /// Where each face's share lies relative to its tolerance.
+    fits: [Fit; 20],
+    /// Distribution rolls are drawn from when some faces are likelier than others, or `None`
+    /// for a fair die.
+    loaded: Option<WeightedIndex<f64>>,
+    /// Weights from a reloaded config, waiting for the next reset so the counts aren't a mix
+    /// of two dice's rolls.
+    pending_weights: Option<Option<Vec<f64>>>,
+    /// Chance of each simulated roll repeating the one before it instead of rolling fresh.
+    stickiness: f64,
+    /// Hash of every roll recorded since the last reset, in order.
+    roll_hash: RollHash,
+    /// Whether a count has reached `u64::MAX` and stopped there since the last reset.
+    saturated: bool,
+    /// Whether fair rolls bucket a float from 0 up to 1 instead of drawing a face directly.
+    continuous: bool,
+    /// The face rolled most recently since the last reset.
+    last_roll_key: Option<usize>,
+    /// Value of `total_rolls` when each face last came up.
+    last_seen: [u64; 20],
+    /// The face that has gone the longest without being rolled.
+    overdue_roll_key: Option<usize>,
+    /// The winner and loser as highlighted, which lag a change of leader so they don't flicker.
+    winner_highlight: Debounced,
+    loser_highlight: Debounced,
+    /// Rolls a face may go without coming up before it's flagged as stalled, or 0 to disable.
+    stall_window: u64,
+    stalls: [Stall; 20],
+    /// Size of the frame drawn into, which is the window's size in logical pixels when laying
+    /// out in them.
+    width: u32,
+    height: u32,
+    /// The window's ratio of physical to logical pixels.
+    scale_factor: f64,
+    /// Whether to lay the chart out in logical pixels, so it looks the same size on any display.
+    logical_layout: bool,
+    /// Whether the frame needs resizing to match a change in layout.
+    layout_changed: bool,
+    /// The window title, also shown in our own title bar.
+    title: TitleBar,
+    /// Whether to draw our own title bar across the top of the window.
+    title_bar: bool,
+    /// Top of the bar area, below the title bar if there is one.
+    chart_top: u32,
+    /// Height of the bar area; the remainder below it holds the per-face labels.
+    chart_height: u32,
+    /// Name of each face, shown beneath its column.
+    labels: Vec<String>,
+    /// Number the first face goes by: 1, or 0 for dice marked from 0.
+    first_face: usize,
+    label_scale: u32,
+    /// How much besides the bars fits in the window.
+    tier: Tier,
+    /// Height of the heat strip along the bottom of the window, or 0 when it's hidden.
+    heat_strip_height: u32,
+    /// Show the statistics in a panel along the right, beside the chart.
+    show_stats: bool,
+    /// Width in pixels of the stats panel, when it's shown.
+    stats_width: u32,
+    /// Show which color goes with which face.
+    show_legend: bool,
+    /// Height the legend takes from the chart, or 0 when it's hidden or laid over the chart.
+    legend_height: u32,
+    /// Ranges of faces drawn as one bar each, or `None` for a bar per face.
+    groups: Option<Vec<Group>>,
+    /// Faces left off the chart, which are still rolled and counted.
+    hidden: [bool; 20],
+    /// Indexes of the groups, or faces if they aren't grouped, that have a bar, leaving out any
+    /// whose faces are all hidden.
+    shown_columns: Vec<usize>,
+    column_width: u32,
+    offset: u32,
+    theme: Theme,
+    /// Index into `theme::HIGHLIGHT_PAIRS` of the winner and loser colors used in place of the
+    /// theme's, if any.
+    highlight_pair: Option<usize>,
+    /// The theme's own winner and loser colors, to go back to after trying the pairs.
+    theme_highlights: ([u8; 4], [u8; 4]),
+    /// Whether to shade the bars from the desktop's accent color instead of the theme's.
+    follow_accent: bool,
+    /// Hue in degrees to shade the bars from instead of the theme's, ahead of the accent color.
+    bar_hue: Option<f64>,
+    /// Whether the desktop prefers dark windows, or is assumed to when it doesn't say.
+    desktop_dark: bool,
+    /// Split each column into observed (left) and expected (right) halves, the expected
+    /// counts following the configured weights.
+    show_expected: bool,
+    /// Whether to draw a smoothed curve over the bars.
+    show_smoothed: bool,
+    /// Whether bars run both up and down from a midline, half as far each way.
+    mirrored: bool,
+    /// Whether bars are ordered from most to least rolled instead of by face.
+    sorted: bool,
+    /// When the bars were last put in order, while sorted.
+    last_sort: Option<Instant>,
+    /// Whether to show the total rolls in large, faint digits behind the bars.
+    show_big_total: bool,
+    /// How far each chart pixel fades toward the background per frame instead of being
+    /// repainted, or 0 to repaint it.
+    trail: u8,
+    /// Pixels of every bar drawn however few rolls it has, so faces that haven't come up still
+    /// show.
+    min_bar_height: u32,
+    /// Show the chi-square test's p-value beneath the speed.
+    show_p_value: bool,
+    /// Whether to count down the rolls until the configured bias should be detectable.
+    show_detection: bool,
+    /// Whether to fit a normal distribution to the counts and compare it with the weights'.
+    show_fit: bool,
+    /// Whether bars show how far each column's count is above or below what's expected, from a
+    /// midline, instead of the count itself.
+    show_deviations: bool,
+    /// Whether to show how often each face follows each other face instead of the bars.
+    show_transitions: bool,
+    /// How often each face has come up straight after each other face since the last reset.
+    transitions: Transitions,
+    /// Whether to show the chart as seen with each kind of color blindness, for checking a
+    /// palette.
+    show_colorblind_preview: bool,
+    /// How opaque to make every pixel, if the window is translucent.
+    opacity: Option<f64>,
+    /// Whether to work out bar heights and animations without floating point.
+    integer_render: bool,
+    /// The deviation drawn at the top and bottom of the chart, kept steady like `scale`.
+    deviation_scale: u64,
+    /// Dim every face except those whose z-score is beyond this many standard deviations, or
+    /// `None` to show every face normally.
+    spotlight: Option<f64>,
+    /// The spotlight threshold to use the next time it's turned on.
+    spotlight_threshold: f64,
+    snapshots: Vec<Snapshot>,
+    /// How often to close off the counts into the history and start afresh, if ever.
+    rollover: Option<Rollover>,
+    /// When the current segment started, in seconds since the Unix epoch.
+    segment_start: u64,
+    /// The boundary that will close the current segment, once one has been scheduled.
+    next_rollover: Option<u64>,
+    history: Vec<history::Segment>,
+    /// The past segment shown instead of the live counts.
+    viewing: Option<usize>,
+    /// Difficulty class to show the chance of meeting, counting faces from 1 like the config
+    /// does, whatever `first_face` is.
+    dc: usize,
+    show_dc: bool,
+    /// The snapshot drawn as a ghost behind the bars.
+    ghost: Option<usize>,
+    /// Name typed so far for a new snapshot, while being prompted for one.
+    snapshot_name: Option<String>,
+    /// Face number typed so far for a new bet, while being prompted for one.
+    bet_entry: Option<String>,
+    /// Text typed so far for a new note, while being prompted for one.
+    note_entry: Option<String>,
+    /// Notes made this session, oldest first.
+    notes: Vec<Note>,
+    bet: Option<Bet>,
+    /// Chips won or lost on bets since the last reset.
+    bankroll: i64,
+    status: Option<StatusMessage>,
+    cursor_position: Option<(u32, u32)>,
+    /// Modifier keys held down, as last reported by the window.
+    #[cfg(feature = "app")]
+    modifiers: ModifiersState,
+    #[cfg(feature = "app")]
+    keymap: Keymap,
+    /// Shortcuts bound in place of the defaults, kept for exporting.
+    #[cfg(feature = "app")]
+    keys: BTreeMap<Action, keymap::Chord>,
+    /// Where rolls come from, or `None` for the built-in random number generator.
+    source: Option<Box<dyn RollSource>>,
+    source_kind: source::Kind,
+    /// When the last roll from an external source arrived.
+    last_arrival: Option<Instant>,
+    /// Time between consecutive rolls from an external source.
+    arrival_gaps: LogHistogram,
+    /// The most rolled face in each recent slice of time.
+    slices: heat::Slices,
+    /// Counts from a fair die rolled once for every external roll, when comparing a physical die
+    /// against one, or `None` when not comparing.
+    simulated: Option<Vec<u64>>,
+    /// Independent runs rolled alongside this one, each drawn as a faint line.
+    ensemble: Option<Ensemble>,
+    /// Chi-square statistics of repeated short experiments, when charting those instead of the
+    /// faces.
+    rounds: Option<Rounds>,
+    /// Dice of other sizes rolled together and charted by share of rolls, when comparing sizes
+    /// instead of showing the d20.
+    overlay: Option<Overlay>,
+    /// Whether rolling is stopped; rolls sent over the remote interface are still recorded.
+    paused: bool,
+    /// Watches for the counts settling close to even, when asked to stop once they do.
+    convergence: Option<convergence::Detector>,
+    /// When counting last started from nothing.
+    run_started: Instant,
+    /// When the app started, for the run log.
+    launched: Instant,
+    converged: Option<Converged>,
+    /// Whether a summary of the convergence is waiting to be saved.
+    summary_pending: bool,
+    /// Whether another window has been asked for and is waiting to be opened.
+    window_requested: bool,
+    /// Rolls the random number generator is allowed in all, if limited.
+    budget: Option<Budget>,
+    /// How the roll rate climbs over time, if it does.
+    ramp: Option<Ramp>,
+    /// The tour underway, if running one.
+    demo: Option<Demo>,
+    /// The right-click menu, while it's open.
+    menu: Option<Menu>,
+    /// Counts set aside each time a column fills, in pixel-exact mode, which keeps every live
+    /// pixel one roll instead of scaling the bars down.
+    pages: Option<Pages>,
+    /// How counts are written in labels.
+    notation: Notation,
+    /// Shortest time between updates, or `None` to update before every draw.
+    update_interval: Option<Duration>,
+    /// When the counts were last updated.
+    last_update: Option<Instant>,
+    /// Whether to draw the bars partway between updates instead of jumping at each one.
+    interpolate: bool,
+    /// How the bars move when interpolating.
+    easing: Easing,
+    /// `roll_counts` before the last update's rolls, to interpolate from.
+    previous_counts: [u64; 20],
+    /// Rolls drawn at the top of the chart, or 0 until the first update. Only changes when the
+    /// tallest bar leaves a band around it, so the bars don't jitter with every roll.
+    scale: u64,
+    /// Rolls pinned to the top of the chart, drawn in place of `scale` so bars grow truthfully
+    /// and clip past it, if any.
+    y_max: Option<u64>,
+    /// When a reset started draining the bars, holding off rolling until it finishes.
+    draining: Option<Instant>,
+    /// Whether the chi-square test found the counts biased at the last update.
+    biased: bool,
+    /// Whether to flash the window when the counts start to look biased.
+    bias_alert: bool,
+    /// Moments to save the chart at, if any are being watched for.
+    captures: Option<capture::Watch>,
+    /// Whether to beep as well.
+    beep_on_bias: bool,
+    /// When the window started flashing.
+    flash: Option<Instant>,
+    /// Operations made to fail on purpose, in debug builds.
+    failures: inject::Failures,
+    rolls_per_frame: u64,
+    /// Index into `SPEEDS` of the multiplier applied to `rolls_per_frame`.
+    speed: usize,
+    /// Fraction of a roll left over from applying the speed multiplier, carried to the next frame.
+    speed_remainder: f64,
+    /// Longest a single `update` may spend rolling before deferring the rest to the next frame.
+    frame_budget: Duration,
+    /// Rolls deferred from earlier frames that ran out of time.
+    rolls_owed: u64,
+    /// Backlog size that triggers the next warning, doubled each time so the log isn't flooded.
+    next_backlog_warning: u64,
+}
+
+/// Replace `counts` with their total shared as evenly as whole counts allow, lowest faces
+/// first.
+fn even_out(counts: &mut [u64]) {
+    let total: u64 = counts.iter().sum();
+    let sides = counts.len() as u64;
+    for (roll_key, count) in counts.iter_mut().enumerate() {
+        *count = total / sides + u64::from((roll_key as u64) < total % sides);
+    }
+}
+
+/// The nearest width to `width` that divides evenly into `columns` bars, so none is left over
+/// as margin.
+fn snapped_width(width: u32, columns: u32) -> u32 {
+    let columns = columns.max(1);
+    ((width + columns / 2) / columns).max(1) * columns
+}
+
+/// How fast `ramp` has the die rolling, and whether it's still climbing.
+fn ramp_line(ramp: &Ramp) -> String {
+    let state = if ramp.is_finished() {
+        "Holding"
+    } else {
+        "Ramping"
+    };
+    format!("{state} at {:.0} rolls/s", ramp.rate())
+}
+
+/// Save the chart annotated with its statistics as a PNG at `path`.
+fn save_summary(world: &mut World, path: &Path) {
+    let frame = world.render_summary();
+    let saved = if world.failures.take(inject::Point::ExportIo) {
+        Err(summary::Error::Io(inject::io_error()))
+    } else {
+        summary::save(path, world.width, world.height, &frame)
+    };
+    match saved {
+        Ok(()) => info!("saved summary to {}", path.display()),
+        Err(err) => log_error("summary::save", err),
+    }
+}
+
+/// The count to draw at the top of the chart: `scale` as it is, unless the tallest bar,
+/// `max_found`, has grown past it or shrunk well below where it was when the scale was set, in
+/// which case it's set afresh with headroom above the tallest bar. Never less than `capacity`,
+/// the rolls a column holds at one per pixel.
+fn hysteresis_scale(scale: u64, max_found: u64, capacity: u64) -> u64 {
+    let set_for = scale as f64 / SCALE_HEADROOM;
+    let scale = if max_found > scale || (max_found as f64) < set_for * SCALE_SHRINK {
+        (max_found as f64 * SCALE_HEADROOM) as u64
+    } else {
+        scale
+    };
+    scale.max(capacity)
+}
+
+/// Face numbers counting from `first_face`, used when no custom labels are configured.
+fn default_labels(first_face: usize) -> Vec<String> {
+    face_numbers(20, first_face)
+}
+
+/// The numbers of a die with `sides` faces, counting from `first_face`.
+fn face_numbers(sides: usize, first_face: usize) -> Vec<String> {
+    (first_face..first_face + sides)
+        .map(|face| face.to_string())
+        .collect()
+}
+
+fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
+    error!("{method_name}() failed: {err}");
+    for source in err.sources().skip(1) {
+        error!("  Caused by: {source}");
+    }
+}
+
+impl World {
+    /// A chart of a fair d20 with nothing rolled yet, to be drawn `width` by `height` pixels.
+    pub fn new(width: u32, height: u32) -> Self {
+        let mut world = Self {
+            roll_counts: [0; 20],
+            metrics: Metrics::default(),
+            total_rolls: 0,
+            face_totals: [0; 20],
+            accounting: Accounting::default(),
+            tallies: Tallies::default(),
+            classing: None,
+            meter: None,
+            rng: StdRng::from_entropy(),
+            seed: None,
+            configured_weights: None,
+            excluded: [false; 20],
+            weights: vec![1.; 20],
+            tolerances: None,
+            fits: [Fit::default(); 20],
+            loaded: None,
+            pending_weights: None,
+            stickiness: 0.,
+            continuous: false,
+            roll_hash: RollHash::default(),
+            saturated: false,
+            last_roll_key: None,
+            last_seen: [0; 20],
+            overdue_roll_key: None,
+            winner_highlight: Debounced::new(Duration::ZERO),
+            loser_highlight: Debounced::new(Duration::ZERO),
+            stall_window: 0,
+            stalls: [Stall::default(); 20],
+            width: 0,
+            height: 0,
+            scale_factor: 1.,
+            logical_layout: false,
+            layout_changed: false,
+            title: TitleBar::new(title::Template::default()),
+            title_bar: false,
+            chart_top: 0,
+            chart_height: 0,
+            labels: default_labels(1),
+            first_face: 1,
+            label_scale: 1,
+            tier: Tier::Full,
+            heat_strip_height: 0,
+            show_stats: false,
+            stats_width: config::DEFAULT_STATS_WIDTH,
+            show_legend: false,
+            legend_height: 0,
+            groups: None,
+            hidden: [false; 20],
+            shown_columns: (0..20).collect(),
+            column_width: 0,
+            offset: 0,
+            theme: Theme::default(),
+            highlight_pair: None,
+            theme_highlights: (Theme::default().winner, Theme::default().loser),
+            follow_accent: false,
+            bar_hue: None,
+            desktop_dark: true,
+            show_expected: false,
+            show_smoothed: false,
+            show_big_total: false,
+            mirrored: false,
+            sorted: false,
+            last_sort: None,
+            trail: 0,
+            min_bar_height: 0,
+            show_p_value: false,
+            show_detection: false,
+            show_fit: false,
+            show_deviations: false,
+            show_transitions: false,
+            transitions: Transitions::new(20),
+            show_colorblind_preview: false,
+            opacity: None,
+            integer_render: false,
+            deviation_scale: 0,
+            spotlight: None,
+            spotlight_threshold: DEFAULT_SPOTLIGHT_THRESHOLD,
+            snapshots: Vec::new(),
+            rollover: None,
+            segment_start: 0,
+            next_rollover: None,
+            history: Vec::new(),
+            viewing: None,
+            dc: DEFAULT_DC,
+            show_dc: false,
+            ghost: None,
+            snapshot_name: None,
+            bet_entry: None,
+            note_entry: None,
+            notes: Vec::new(),
+            bet: None,
+            bankroll: 0,
+            status: None,
+            cursor_position: None,
+            #[cfg(feature = "app")]
+            modifiers: ModifiersState::empty(),
+            #[cfg(feature = "app")]
+            keymap: Keymap::default(),
+            #[cfg(feature = "app")]
+            keys: BTreeMap::new(),
+            source: None,
+            source_kind: source::Kind::Rng,
+            last_arrival: None,
+            arrival_gaps: LogHistogram::new(
+                Duration::from_millis(1),
+                Duration::from_secs(60),
+                ARRIVAL_GAP_BUCKETS,
+            ),
+            slices: heat::Slices::new(20),
+            simulated: None,
+            ensemble: None,
+            rounds: None,
+            overlay: None,
+            paused: false,
+            convergence: None,
+            run_started: Instant::now(),
+            launched: Instant::now(),
+            converged: None,
+            summary_pending: false,
+            window_requested: false,
+            budget: None,
+            ramp: None,
+            demo: None,
+            menu: None,
+            pages: None,
+            notation: Notation::Plain,
+            update_interval: None,
+            last_update: None,
+            interpolate: false,
+            easing: Easing::Linear,
+            previous_counts: [0; 20],
+            scale: 0,
+            y_max: None,
+            draining: None,
+            biased: false,
+            bias_alert: false,
+            captures: None,
+            beep_on_bias: false,
+            flash: None,
+            failures: inject::Failures::default(),
+            rolls_per_frame: 10000,
+            speed: SPEEDS.iter().position(|&speed| speed == 1.).unwrap_or(0),
+            speed_remainder: 0.,
+            frame_budget: Duration::from_millis(50),
+            rolls_owed: 0,
+            next_backlog_warning: 0,
+        };
+        world.set_size(width, height);
+        world.title.set(Segment::App, APP_NAME);
+        world
+    }
+
+    /// Count each of `rolls`, given as zero-based faces so a d20's run from 0 to 19. Anything
+    /// past the last face is skipped.
+    pub fn push_rolls(&mut self, rolls: &[u8]) {
+        for &roll in rolls {
+            let roll_key = usize::from(roll);
+            if roll_key < self.roll_counts.len() {
+                self.record_roll(roll_key);
+            }
+        }
+    }
+
+    /// Switch what the chart shows from the next draw on.
+    pub fn set_view(&mut self, view: View) {
+        self.show_expected = view == View::Expected;
+        self.spotlight = (view == View::Spotlight).then_some(self.spotlight_threshold);
+    }
+
+    /// Draw the chart into `frame`, an RGBA buffer of `width` by `height` pixels.
+    ///
+    /// # Panics
+    ///
+    /// If `frame` is smaller than `width * height * 4` bytes.
+    pub fn draw_into(&mut self, frame: &mut [u8], width: u32, height: u32) {
+        assert!(
+            frame.len() >= width as usize * height as usize * 4,
+            "a {width}x{height} frame needs {} bytes, not {}",
+            width as usize * height as usize * 4,
+            frame.len()
+        );
+        if (width, height) != (self.width, self.height) {
+            self.set_size(width, height);
+        }
+        self.update_statistics();
+        self.draw(frame);
+    }
+
+    fn set_size(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.tier = Tier::Full;
+        self.fit_columns();
+        self.tier = compositor::tier(width, height, self.label_scale);
+        if self.tier == Tier::BarsOnly && self.show_stats {
+            // Without the stats panel, the columns have the whole width to share.
+            self.fit_columns();
+        }
+        // Two lines of labels: face names, then rolls since each was last seen.
+        let label_height = if self.tier < Tier::NoLabels {
+            2 * text::text_height(self.label_scale) + 6 * self.label_scale
+        } else {
+            0
+        };
+        self.chart_top = if self.title_bar {
+            self.title_bar_height()
+        } else {
+            0
+        };
+        self.heat_strip_height =
+            if height >= MIN_HEAT_STRIP_WINDOW_HEIGHT && self.tier < Tier::BarsOnly {
+                3 * self.label_scale
+            } else {
+                0
+            };
+        let reserved = self.chart_top + label_height + self.heat_strip_height;
+        // The legend only takes room from the chart if that leaves the chart half the window;
+        // otherwise it's laid over the chart.
+        let legend_height = self.legend_layout().height;
+        self.legend_height = if self.show_legend
+            && self.tier < Tier::BarsOnly
+            && height.saturating_sub(reserved + legend_height) >= height / 2
+        {
+            legend_height
+        } else {
+            0
+        };
+        self.chart_height = height.saturating_sub(reserved + self.legend_height).max(1);
+    }
+
+    /// Split the chart's width into equal columns, centered, with labels sized to match.
+    fn fit_columns(&mut self) {
+        let columns = self.column_count() as u32;
+        let chart_width = self.chart_width();
+        self.column_width = (chart_width as f64 / columns as f64).floor() as u32;
+        self.offset = (chart_width - self.column_width * columns) / 2;
+        self.label_scale = (self.column_width / 24).clamp(1, 4);
+    }
+
+    /// Width left for the chart beside the stats panel.
+    fn chart_width(&self) -> u32 {
+        self.width - self.stats_panel_width()
+    }
+
+    /// Width the stats panel takes from the chart, or 0 when it's hidden. The chart always
+    /// keeps at least half the window.
+    fn stats_panel_width(&self) -> u32 {
+        if self.show_stats && self.tier < Tier::BarsOnly {
+            self.stats_width.min(self.width / 2)
+        } else {
+            0
+        }
+    }
+
+    fn set_stats_width(&mut self, stats_width: u32) {
+        self.stats_width = stats_width;
+        self.set_size(self.width, self.height);
+    }
+
+    fn toggle_stats(&mut self) {
+        self.show_stats = !self.show_stats;
+        self.set_size(self.width, self.height);
+    }
+
+    fn toggle_legend(&mut self) {
+        self.show_legend = !self.show_legend;
+        self.set_size(self.width, self.height);
+    }
+
+    /// How the legend's entries fit into a grid across the window.
+    fn legend_layout(&self) -> LegendLayout {
+        let scale = self.label_scale;
+        let padding = 2 * scale;
+        let swatch = text::text_height(scale);
+        let label_width = self
+            .labels
+            .iter()
+            .map(|label| text::text_width(label, scale))
+            .max()
+            .unwrap_or(0)
+            .min(self.width / 3);
+        let entry_width = swatch + padding + label_width + 2 * padding;
+        let entry_height = text::text_height(scale) + padding;
+        let columns = (self.width.saturating_sub(padding) / entry_width.max(1)).max(1);
+        let rows = (self.labels.len() as u32).div_ceil(columns);
+        LegendLayout {
+            columns,
+            entry_width,
+            entry_height,
+            label_width,
+            height: rows * entry_height + padding,
+        }
+    }
+
+    /// Show each group of faces as a single bar, or every face on its own.
+    fn set_groups(&mut self, groups: Option<Vec<Group>>) {
+        self.groups = groups;
+        self.update_columns();
+    }
+
+    /// Leave the faces numbered in `faces` off the chart, showing the rest. Hiding every face
+    /// shows them all instead.
+    fn set_hidden(&mut self, faces: &[usize]) {
+        self.hidden = [false; 20];
+        for &face in faces {
+            self.hidden[face - 1] = true;
+        }
+        if !self.hidden.contains(&false) {
+            warn!("every face is hidden; showing them all");
+            self.hidden = [false; 20];
+        }
+        self.update_columns();
+    }
+
+    /// Hide the faces in the bar under the cursor, unless they're the last ones shown.
+    fn hide_hovered_column(&mut self) {
+        let Some(column) = self
+            .hovered_column()
+            .filter(|_| self.heat_strip_hit().is_none())
+        else {
+            return;
+        };
+        if self.column_count() == 1 {
+            self.set_status(
+                "The last bar can't be hidden".to_string(),
+                Some(STATUS_TIMEOUT),
+            );
+            return;
+        }
+        for roll_key in self.column_faces(column) {
+            self.hidden[roll_key] = true;
+        }
+        self.update_columns();
+    }
+
+    fn show_hidden(&mut self) {
+        self.hidden = [false; 20];
+        self.update_columns();
+    }
+
+    /// Work out which bars are drawn from the groups and hidden faces, then lay them out.
+    fn update_columns(&mut self) {
+        let all_columns = self
+            .groups
+            .as_ref()
+            .map_or(self.roll_counts.len(), Vec::len);
+        self.shown_columns = (0..all_columns)
+            .filter(|&index| {
+                !self
+                    .group_faces(index)
+                    .all(|roll_key| self.hidden[roll_key])
+            })
+            .collect();
+        if self.sorted {
+            self.sort_columns();
+        }
+        self.set_size(self.width, self.height);
+    }
+
+    /// Put the shown bars in order from most to least rolled, keeping face order among ties.
+    fn sort_columns(&mut self) {
+        let mut shown_columns = std::mem::take(&mut self.shown_columns);
+        shown_columns.sort_by_key(|&index| {
+            let count: u64 = self
+                .group_faces(index)
+                .map(|roll_key| self.roll_counts[roll_key])
+                .sum();
+            (Reverse(count), index)
+        });
+        self.shown_columns = shown_columns;
+    }
+
+    /// How many faces are hidden, for the status strip, if any are.
+    fn hidden_line(&self) -> Option<String> {
+        let hidden = self.hidden.iter().filter(|&&hidden| hidden).count();
+        #[cfg(feature = "app")]
+        let shortcut = self.keymap.chord(Action::ShowHidden);
+        // Embedders have their own keys, if any.
+        #[cfg(not(feature = "app"))]
+        let shortcut: Option<&str> = None;
+        (hidden > 0).then(|| match shortcut {
+            Some(chord) => format!("{hidden} hidden ({chord} shows them)"),
+            None => format!("{hidden} hidden"),
+        })
+    }
+
+    #[cfg(debug_assertions)]
+    fn set_failures(&mut self, failures: inject::Failures) {
+        self.failures = failures;
+    }
+
+    fn set_title_bar(&mut self, title_bar: bool) {
+        self.title_bar = title_bar;
+        self.set_size(self.width, self.height);
+    }
+
+    fn title_bar_height(&self) -> u32 {
+        text::text_height(self.label_scale) + 4 * self.label_scale
+    }
+
+    /// What part of the title bar, if any, the cursor is over.
+    fn title_bar_hit(&self) -> Option<TitleBarHit> {
+        let (cursor_x, cursor_y) = self.cursor_position?;
+        if !self.title_bar || cursor_y >= self.chart_top {
+            return None;
+        }
+        // The close button is a square at the right end of the bar.
+        if cursor_x + self.chart_top >= self.width {
+            Some(TitleBarHit::Close)
+        } else {
+            Some(TitleBarHit::Drag)
+        }
+    }
+
+    /// Open the right-click menu at the cursor, unless typing into a prompt.
+    fn open_menu(&mut self) {
+        let Some((x, y)) = self.cursor_position.filter(|_| !self.is_typing()) else {
+            return;
+        };
+        self.menu = Some(Menu::open(
+            x,
+            y,
+            self.width,
+            self.height,
+            self.label_scale,
+            self.paused,
+        ));
+    }
+
+    /// The menu item under the cursor, if the menu is open and the cursor is over one.
+    fn menu_item_under_cursor(&self) -> Option<menu::Item> {
+        let (x, y) = self.cursor_position?;
+        let menu = self.menu.as_ref()?;
+        let index = menu.item_at(x, y, self.label_scale, self.paused)?;
+        Some(menu::ITEMS[index])
+    }
+
+    /// Highlight the menu item under the cursor, if any.
+    fn hover_menu(&mut self) {
+        let index = match (&self.menu, self.cursor_position) {
+            (Some(menu), Some((x, y))) => menu.item_at(x, y, self.label_scale, self.paused),
+            _ => None,
+        };
+        if let (Some(menu), Some(index)) = (&mut self.menu, index) {
+            menu.selected = index;
+        }
+    }
+
+    fn move_menu_selection(&mut self, step: isize) {
+        if let Some(menu) = &mut self.menu {
+            menu.move_selection(step);
+        }
+    }
+
+    /// How many slices before the newest the heat strip shows under the cursor, if it's over
+    /// one.
+    fn heat_strip_hit(&self) -> Option<usize> {
+        let (cursor_x, cursor_y) = self.cursor_position?;
+        if cursor_y + self.heat_strip_height < self.height || cursor_x >= self.width {
+            return None;
+        }
+        Some((self.width - 1 - cursor_x) as usize)
+    }
+
+    /// Describe the heat strip's slice under the cursor in the status strip.
+    fn inspect_heat_slice(&mut self) {
+        let Some(age) = self.heat_strip_hit() else {
+            return;
+        };
+        let when = heat::SLICE_LENGTH.as_secs_f64() * (age + 1) as f64;
+        let status = match self.slices.get(age) {
+            Some(heat::Slice {
+                top: Some(top),
+                top_rolls,
+                rolls,
+            }) => format!(
+                "{when:.2}s ago: {} came up most, {top_rolls} of {rolls} rolls",
+                self.labels[*top]
+            ),
+            _ => format!("{when:.2}s ago: no rolls"),
+        };
+        self.set_status(status, Some(STATUS_TIMEOUT));
+    }
+
+    fn set_rate(&mut self, rolls_per_frame: u64, frame_budget: Duration) {
+        self.rolls_per_frame = rolls_per_frame;
+        self.frame_budget = frame_budget;
+    }
+
+    fn set_trail(&mut self, trail: u8) {
+        self.trail = trail;
+    }
+
+    fn set_min_bar_height(&mut self, min_bar_height: u32) {
+        self.min_bar_height = min_bar_height;
+    }
+
+    fn toggle_expected(&mut self) {
+        self.show_expected = !self.show_expected;
+    }
+
+    fn toggle_smoothed(&mut self) {
+        self.show_smoothed = !self.show_smoothed;
+        if self.show_smoothed && self.column_count() < smooth::MIN_BUCKETS {
+            self.set_status(
+                format!("Smoothing needs at least {} bars", smooth::MIN_BUCKETS),
+                Some(STATUS_TIMEOUT),
+            );
+        }
+    }
+
+    fn toggle_mirrored(&mut self) {
+        self.mirrored = !self.mirrored;
+    }
+
+    fn toggle_sorted(&mut self) {
+        self.sorted = !self.sorted;
+        self.last_sort = None;
+        // Going back to face order means laying the bars out from scratch.
+        self.update_columns();
+        let status = if self.sorted {
+            "Bars: most rolled first"
+        } else {
+            "Bars: by face"
+        };
+        self.set_status(status.to_string(), Some(STATUS_TIMEOUT));
+    }
+
+    /// Pin the top of the chart to `y_max` rolls, or scale it to fit the bars again.
+    fn set_y_max(&mut self, y_max: Option<u64>) {
+        self.y_max = y_max;
+    }
+
+    /// Pin the top of the chart to the tallest bar as it is now, or unpin it.
+    fn toggle_pin(&mut self) {
+        if self.y_max.take().is_some() {
+            self.set_status("Scale: fit to bars".to_string(), Some(STATUS_TIMEOUT));
+            return;
+        }
+        let tallest = self
+            .column_counts(&self.roll_counts)
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+        if tallest == 0 {
+            self.set_status("Nothing rolled to pin to".to_string(), Some(STATUS_TIMEOUT));
+            return;
+        }
+        self.set_y_max(Some(tallest));
+        let status = format!("Scale: pinned at {}", self.format_count(tallest));
+        self.set_status(status, Some(STATUS_TIMEOUT));
+    }
+
+    fn toggle_big_total(&mut self) {
+        self.show_big_total = !self.show_big_total;
+    }
+
+    fn set_scale_factor(&mut self, scale_factor: f64) {
+        if scale_factor != self.scale_factor {
+            self.scale_factor = scale_factor;
+            self.layout_changed |= self.logical_layout;
+        }
+    }
+
+    fn toggle_logical_layout(&mut self) {
+        self.logical_layout = !self.logical_layout;
+        self.layout_changed = true;
+        let units = if self.logical_layout {
+            "logical"
+        } else {
+            "physical"
+        };
+        self.set_status(
+            format!("Laying out in {units} pixels"),
+            Some(STATUS_TIMEOUT),
+        );
+    }
+
+    /// Whether the frame needs resizing since the layout changed, clearing it.
+    fn take_layout_changed(&mut self) -> bool {
+        std::mem::take(&mut self.layout_changed)
+    }
+
+    /// Physical pixels per pixel of the frame. The frame is only ever scaled up by whole
+    /// factors, so fractional scale factors round to the nearest one.
+    fn pixel_ratio(&self) -> u32 {
+        if self.logical_layout {
+            self.scale_factor.round().max(1.) as u32
+        } else {
+            1
+        }
+    }
+
+    fn toggle_p_value(&mut self) {
+        self.show_p_value = !self.show_p_value;
+    }
+
+    fn set_detection(&mut self, show_detection: bool) {
+        self.show_detection = show_detection;
+    }
+
+    fn set_integer_render(&mut self, integer_render: bool) {
+        self.integer_render = integer_render;
+    }
+
+    fn set_opacity(&mut self, opacity: Option<f64>) {
+        self.opacity = opacity;
+    }
+
+    fn toggle_colorblind_preview(&mut self) {
+        self.show_colorblind_preview = !self.show_colorblind_preview;
+    }
+
+    fn toggle_deviations(&mut self) {
+        self.show_deviations = !self.show_deviations;
+        let status = if self.show_deviations {
+            "Bars: above or below expected"
+        } else {
+            "Bars: counts"
+        };
+        self.set_status(status.to_string(), Some(STATUS_TIMEOUT));
+    }
+
+    fn toggle_transitions(&mut self) {
+        self.show_transitions = !self.show_transitions;
+        let status = if self.show_transitions {
+            "Showing which faces follow which"
+        } else {
+            "Showing the bars"
+        };
+        self.set_status(status.to_string(), Some(STATUS_TIMEOUT));
+    }
+
+    fn set_fit(&mut self, show_fit: bool) {
+        self.show_fit = show_fit;
+    }
+
+    fn set_bias_alert(&mut self, bias_alert: bool) {
+        self.bias_alert = bias_alert;
+    }
+
+    /// Save the chart whenever any of `events` happens.
+    fn set_auto_capture(&mut self, events: Vec<capture::Event>) {
+        self.captures = Some(capture::Watch::new(events));
+    }
+
+    /// Where to save the chart for each moment noticed since the last call.
+    fn take_captures(&mut self) -> Vec<PathBuf> {
+        self.captures
+            .as_mut()
+            .map_or_else(Vec::new, capture::Watch::take)
+    }
+
+    #[cfg(feature = "audio")]
+    fn set_beep_on_bias(&mut self, beep_on_bias: bool) {
+        self.beep_on_bias = beep_on_bias;
+    }
+
+    fn is_spotlighting(&self) -> bool {
+        self.spotlight.is_some()
+    }
+
+    /// Switch between dimming all but the most anomalous faces and showing every face normally.
+    fn toggle_spotlight(&mut self) {
+        self.spotlight = match self.spotlight {
+            Some(_) => None,
+            None => Some(self.spotlight_threshold),
+        };
+        let status = match self.spotlight {
+            Some(threshold) => format!("Spotlight on faces beyond {threshold} sd"),
+            None => "Spotlight off".to_string(),
+        };
+        self.set_status(status, Some(STATUS_TIMEOUT));
+    }
+
+    /// Raise or lower the spotlight threshold by `step` standard deviations.
+    fn adjust_spotlight(&mut self, step: f64) {
+        self.spotlight_threshold = (self.spotlight_threshold + step).max(SPOTLIGHT_STEP);
+        self.spotlight = Some(self.spotlight_threshold);
+        self.set_status(
+            format!("Spotlight on faces beyond {} sd", self.spotlight_threshold),
+            Some(STATUS_TIMEOUT),
+        );
+    }
+
+    /// Show the chance of meeting `dc`, or stop showing it.
+    fn set_dc(&mut self, dc: Option<usize>) {
+        if let Some(dc) = dc {
+            self.dc = dc.clamp(1, self.roll_counts.len());
+        }
+        self.show_dc = dc.is_some();
+    }
+
+    fn toggle_dc(&mut self) {
+        self.show_dc = !self.show_dc;
+    }
+
+    /// Move the DC by `step` faces, staying on the die; only while it's shown.
+    fn adjust_dc(&mut self, step: isize) {
+        if !self.show_dc {
+            return;
+        }
+        self.dc = self
+            .dc
+            .saturating_add_signed(step)
+            .clamp(1, self.roll_counts.len());
+    }
+
+    /// The face the DC is set at.
+    fn dc_roll_key(&self) -> usize {
+        self.dc - 1
+    }
+
+    /// The chance of meeting the DC with a single roll, with advantage (the higher of two), and
+    /// with disadvantage (the lower of two), going by how often each face has come up.
+    fn dc_chances(&self, metrics: &Metrics) -> (f64, f64, f64) {
+        let chance = metrics.share_at_least(self.dc_roll_key());
+        (chance, 1. - (1. - chance).powi(2), chance.powi(2))
+    }
+
+    /// The DC and the chances of meeting it, for display.
+    fn dc_lines(&self, metrics: &Metrics) -> [String; 4] {
+        let (chance, advantage, disadvantage) = self.dc_chances(metrics);
+        let dc = self.face_number(self.dc_roll_key());
+        [
+            format!("DC {dc}"),
+            format!("Roll {dc}+: {:.1}%", chance * 100.),
+            format!("Advantage: {:.1}%", advantage * 100.),
+            format!("Disadvantage: {:.1}%", disadvantage * 100.),
+        ]
+    }
+
+    fn set_snapshots(&mut self, snapshots: Vec<Snapshot>) {
+        // Imported counts aren't on disk, so keep them when the saved snapshots are reloaded.
+        let imported: Vec<Snapshot> = self
+            .snapshots
+            .drain(..)
+            .filter(|snapshot| snapshot.imported)
+            .collect();
+        self.snapshots = snapshots
+            .into_iter()
+            .filter(|snapshot| {
+                let matches = snapshot.counts.len() == self.roll_counts.len();
+                if !matches {
+                    warn!(
+                        "ignoring snapshot {:?} with {} faces",
+                        snapshot.name,
+                        snapshot.counts.len()
+                    );
+                }
+                matches
+            })
+            .chain(imported)
+            .collect();
+        self.ghost = None;
+    }
+
+    /// Add imported counts to the snapshots and show them as the ghost.
+    fn add_imported_ghost(&mut self, name: String, counts: Vec<u64>) {
+        self.snapshots.push(Snapshot {
+            name,
+            total_rolls: counts.iter().sum(),
+            counts,
+            imported: true,
+        });
+        self.ghost = Some(self.snapshots.len() - 1);
+    }
+
+    fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    /// Whether a prompt is taking typed characters.
+    fn is_typing(&self) -> bool {
+        self.snapshot_name.is_some() || self.bet_entry.is_some() || self.note_entry.is_some()
+    }
+
+    fn cancel_typing(&mut self) {
+        self.snapshot_name = None;
+        self.bet_entry = None;
+        self.note_entry = None;
+    }
+
+    /// Start prompting for a note about what's happening in the run.
+    fn start_note_entry(&mut self) {
+        self.note_entry = Some(String::new());
+    }
+
+    /// Handle a character typed while writing a note. Entering an empty note makes none.
+    fn type_note(&mut self, c: char) {
+        let Some(entry) = &mut self.note_entry else {
+            return;
+        };
+        match c {
+            '\r' | '\n' => {
+                let text = self.note_entry.take().unwrap_or_default();
+                let text = text.trim();
+                if text.is_empty() {
+                    return;
+                }
+                self.push_note(text.to_string());
+                self.set_status(format!("Noted {text}"), Some(STATUS_TIMEOUT));
+            }
+            '\u{8}' | '\u{7f}' => {
+                entry.pop();
+            }
+            c if !c.is_control() => entry.push(c),
+            _ => (),
+        }
+    }
+
+    /// Note `text` at the current point in the run.
+    fn push_note(&mut self, text: String) {
+        self.notes.push(Note {
+            text,
+            at: Instant::now(),
+            unix_seconds: history::unix_seconds(SystemTime::now()),
+            total_rolls: self.total_rolls,
+        });
+    }
+
+    fn delete_last_note(&mut self) {
+        let status = match self.notes.pop() {
+            Some(note) => format!("Deleted note {}", note.text),
+            None => "No notes to delete".to_string(),
+        };
+        self.set_status(status, Some(STATUS_TIMEOUT));
+    }
+
+    /// Start prompting for the name of a new snapshot of the current counts.
+    fn start_snapshot_name(&mut self) {
+        self.snapshot_name = Some(String::new());
+    }
+
+    /// Handle a character typed while naming a snapshot, returning whether it saved one.
+    fn type_snapshot_name(&mut self, c: char) -> bool {
+        let Some(name) = &mut self.snapshot_name else {
+            return false;
+        };
+        match c {
+            '\r' | '\n' => {
+                let name = self.snapshot_name.take().unwrap_or_default();
+                let name = if name.trim().is_empty() {
+                    format!("Snapshot {}", self.snapshots.len() + 1)
+                } else {
+                    name.trim().to_string()
+                };
+                self.snapshots.push(Snapshot {
+                    name: name.clone(),
+                    total_rolls: self.total_rolls,
+                    counts: self.roll_counts.to_vec(),
+                    imported: false,
+                });
+                self.set_status(format!("Saved snapshot {name}"), Some(STATUS_TIMEOUT));
+                return true;
+            }
+            // Backspace, and what macOS sends for it.
+            '\u{8}' | '\u{7f}' => {
+                name.pop();
+            }
+            c if !c.is_control() => name.push(c),
+            _ => (),
+        }
+        false
+    }
+
+    /// Number faces from `first_face`, such as 0 for dice marked 0-9.
+    fn set_first_face(&mut self, first_face: usize) {
+        self.first_face = first_face;
+    }
+
+    /// Number the face at `roll_key` goes by.
+    fn face_number(&self, roll_key: usize) -> usize {
+        self.first_face + roll_key
+    }
+
+    /// Number the last face goes by.
+    fn last_face(&self) -> usize {
+        self.face_number(self.roll_counts.len() - 1)
+    }
+
+    /// Start prompting for the face to bet on.
+    fn start_bet_entry(&mut self) {
+        self.bet_entry = Some(String::new());
+    }
+
+    /// Handle a character typed while choosing a face to bet on. Entering no face calls off the
+    /// current bet.
+    fn type_bet_face(&mut self, c: char) {
+        let Some(entry) = &mut self.bet_entry else {
+            return;
+        };
+        match c {
+            '\r' | '\n' => {
+                let entry = self.bet_entry.take().unwrap_or_default();
+                if entry.is_empty() {
+                    self.bet = None;
+                    self.set_status("Bet called off".to_string(), Some(STATUS_TIMEOUT));
+                    return;
+                }
+                let roll_key = entry
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|face| face.checked_sub(self.first_face))
+                    .filter(|&roll_key| roll_key < self.roll_counts.len());
+                match roll_key {
+                    Some(roll_key) => {
+                        self.bet = Some(Bet::new(roll_key));
+                        let status = format!("Betting on {}", self.labels[roll_key]);
+                        self.set_status(status, Some(STATUS_TIMEOUT));
+                    }
+                    None => self.set_status(
+                        format!(
+                            "No face {entry}; pick {} to {}",
+                            self.first_face,
+                            self.last_face()
+                        ),
+                        Some(STATUS_TIMEOUT),
+                    ),
+                }
+            }
+            '\u{8}' | '\u{7f}' => {
+                entry.pop();
+            }
+            c if c.is_ascii_digit() => entry.push(c),
+            _ => (),
+        }
+    }
+
+    fn set_history(&mut self, history: Vec<history::Segment>) {
+        self.history = history
+            .into_iter()
+            .filter(|segment| segment.counts.len() == self.roll_counts.len())
+            .collect();
+        self.viewing = None;
+    }
+
+    fn set_rollover(&mut self, rollover: Option<Rollover>) {
+        if rollover != self.rollover {
+            self.rollover = rollover;
+            self.next_rollover = None;
+        }
+    }
+
+    /// Close off the current segment if a rollover boundary has passed, resetting the counts
+    /// and returning the segment so it can be recorded.
+    fn roll_over(&mut self, now: SystemTime) -> Option<history::Segment> {
+        let rollover = self.rollover?;
+        let now = history::unix_seconds(now);
+        let Some(boundary) = self.next_rollover else {
+            self.segment_start = now;
+            self.next_rollover = Some(rollover.next_boundary(now));
+            return None;
+        };
+        if now < boundary {
+            return None;
+        }
+
+        // If the machine slept through more than one boundary, nothing was rolled after the
+        // first, so the segment ends there and counting resumes from the latest one.
+        let last_boundary = rollover.last_boundary(now);
+        if last_boundary > boundary {
+            info!("caught up on rollovers missed while asleep");
+        }
+        let segment = history::Segment {
+            start: self.segment_start,
+            end: boundary,
+            total_rolls: self.total_rolls,
+            counts: self.face_totals.to_vec(),
+        };
+        self.history.push(segment.clone());
+        self.reset();
+        self.segment_start = last_boundary;
+        self.next_rollover = Some(rollover.next_boundary(now));
+        Some(segment)
+    }
+
+    /// Show the segment before the one being viewed, starting from the most recent.
+    fn view_older_segment(&mut self) {
+        let viewing = match self.viewing {
+            Some(index) => index.saturating_sub(1),
+            None => match self.history.len().checked_sub(1) {
+                Some(newest) => newest,
+                None => {
+                    self.set_status("No past segments".to_string(), Some(STATUS_TIMEOUT));
+                    return;
+                }
+            },
+        };
+        self.view_segment(Some(viewing));
+    }
+
+    /// Show the segment after the one being viewed, or the live counts after the newest.
+    fn view_newer_segment(&mut self) {
+        let viewing = self
+            .viewing
+            .map(|index| index + 1)
+            .filter(|&index| index < self.history.len());
+        self.view_segment(viewing);
+    }
+
+    fn view_segment(&mut self, viewing: Option<usize>) {
+        self.viewing = viewing;
+        let status = match viewing {
+            Some(index) => format!(
+                "Segment {} of {}: {}",
+                index + 1,
+                self.history.len(),
+                self.history[index].describe()
+            ),
+            None => "Live".to_string(),
+        };
+        self.set_status(status, Some(STATUS_TIMEOUT));
+    }
+
+    fn viewed_segment(&self) -> Option<&history::Segment> {
+        self.history.get(self.viewing?)
+    }
+
+    /// The count drawn at the top of a column: the pinned count or live scale, or the tallest
+    /// bar of the past `segment` being viewed, which is fit exactly as it doesn't change.
+    fn top_count(&self, segment: Option<&history::Segment>) -> u64 {
+        match segment {
+            Some(segment) => self
+                .column_counts(&segment.counts)
+                .into_iter()
+                .max()
+                .unwrap_or(0),
+            None => self.y_max.unwrap_or(self.scale),
+        }
+    }
+
+    /// Whether the live counts are drawn against a pinned top, rather than the `segment`
+    /// being viewed or a scale that grows to fit.
+    fn is_pinned(&self, segment: Option<&history::Segment>) -> bool {
+        self.y_max.is_some() && segment.is_none()
+    }
+
+    /// Rolls a bar `rows` pixels tall stands for, the inverse of how counts are drawn: one roll
+    /// per pixel until the counts outgrow the chart, scaled down after that, and doubled when
+    /// mirrored as each row then covers two. Pinned, the pinned count is always at the top.
+    fn rolls_at_height(&self, rows: u32) -> u64 {
+        let max_allowed = self.column_width as u64 * self.live_height() as u64;
+        let segment = self.viewed_segment();
+        let top = if self.is_pinned(segment) {
+            self.top_count(segment)
+        } else {
+            self.top_count(segment).max(max_allowed)
+        };
+        let rows_per_pixel = if self.mirrored { 2 } else { 1 };
+        let cells = u128::from(rows * rows_per_pixel) * u128::from(self.column_width);
+        (cells * u128::from(top) / u128::from(max_allowed.max(1))) as u64
+    }
+
+    /// Scale `counts` so a count of `top` reaches the top of a column. Unless `pinned`, counts
+    /// are only ever scaled down, leaving one roll per pixel while they fit; pinned, they're
+    /// scaled up too, and any past `top` run off the top of the chart.
+    fn fit_to_chart(&self, counts: &[u64], top: u64, pinned: bool) -> Vec<u64> {
+        let max_allowed = self.column_width as u64 * self.live_height() as u64;
+        if top == max_allowed || (top < max_allowed && !pinned) {
+            return counts.to_vec();
+        }
+        if self.integer_render {
+            return counts
+                .iter()
+                .map(|&count| {
+                    (u128::from(count) * u128::from(max_allowed) / u128::from(top)) as u64
+                })
+                .collect();
+        }
+        counts
+            .iter()
+            .map(|&count| (count as f64 * max_allowed as f64 / top as f64) as u64)
+            .collect()
+    }
+
+    /// Show the next saved snapshot as a ghost, or none after the last one.
+    fn cycle_snapshot(&mut self) {
+        if self.snapshots.is_empty() {
+            self.set_status("No snapshots saved".to_string(), Some(STATUS_TIMEOUT));
+            return;
+        }
+        self.ghost = match self.ghost {
+            None => Some(0),
+            Some(index) if index + 1 < self.snapshots.len() => Some(index + 1),
+            Some(_) => None,
+        };
+        let status = match self.ghost {
+            Some(index) => format!(
+                "Snapshot {} of {}: {}",
+                index + 1,
+                self.snapshots.len(),
+                self.snapshots[index].name
+            ),
+            None => "Snapshot hidden".to_string(),
+        };
+        self.set_status(status, Some(STATUS_TIMEOUT));
+    }
+
+    /// The ghost snapshot's counts, rescaled to `total` so shapes compare directly.
+    fn ghost_counts(&self, total: u64) -> Option<Vec<u64>> {
+        let snapshot = &self.snapshots[self.ghost?];
+        Some(
+            snapshot
+                .shares()
+                .iter()
+                .map(|share| (share * total as f64).round() as u64)
+                .collect(),
+        )
+    }
+
+    fn set_stall_window(&mut self, stall_window: u64) {
+        self.stall_window = stall_window;
+    }
+
+    /// Move the winner and loser highlights only once a new face has led for `delay`.
+    fn set_highlight_debounce(&mut self, delay: Duration) {
+        self.winner_highlight = Debounced::new(delay);
+        self.loser_highlight = Debounced::new(delay);
+    }
+
+    /// Draw with `theme`, keeping any highlight pair picked this session.
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme_highlights = (theme.winner, theme.loser);
+        self.theme = theme;
+        self.apply_highlight_pair();
+    }
+
+    /// Shade the bars from the desktop's accent color, or go back to the theme's.
+    fn set_follow_accent(&mut self, follow_accent: bool) {
+        self.follow_accent = follow_accent;
+        self.shade_bars();
+    }
+
+    /// Shade the bars from `hue`, in degrees, or go back to the accent color or theme's.
+    fn set_bar_hue(&mut self, hue: Option<f64>) {
+        self.bar_hue = hue;
+        self.shade_bars();
+    }
+
+    /// The hue picked for this run from its seed, or at random without one. A hue already
+    /// picked is kept, so reloading the config doesn't change the colors.
+    fn seed_hue(&self) -> f64 {
+        self.bar_hue
+            .unwrap_or_else(|| theme::seed_hue(self.seed.unwrap_or_else(rand::random)))
+    }
+
+    fn set_desktop_dark(&mut self, dark: bool) {
+        self.desktop_dark = dark;
+        self.shade_bars();
+    }
+
+    /// Shade the bars from the chosen hue, or from the accent color as it is now if following
+    /// it. Otherwise the theme's bars are left as they are.
+    fn shade_bars(&mut self) {
+        let hue = if let Some(hue) = self.bar_hue {
+            hue.to_radians()
+        } else if self.follow_accent {
+            theme::hue(platform::accent_color().unwrap_or(theme::DEFAULT_BAR))
+        } else {
+            return;
+        };
+        self.theme.bars = theme::accent_ramp(hue, self.desktop_dark);
+    }
+
+    /// Move on to the next colorblind-safe winner and loser pair, or back to the theme's own
+    /// after the last.
+    fn cycle_highlight_pair(&mut self) {
+        self.highlight_pair = match self.highlight_pair {
+            Some(index) if index + 1 < theme::HIGHLIGHT_PAIRS.len() => Some(index + 1),
+            Some(_) => None,
+            None => Some(0),
+        };
+        self.apply_highlight_pair();
+        let name = self
+            .highlight_pair
+            .map_or("theme", |index| theme::HIGHLIGHT_PAIRS[index].name);
+        self.set_status(format!("Highlights: {name}"), Some(STATUS_TIMEOUT));
+    }
+
+    fn apply_highlight_pair(&mut self) {
+        (self.theme.winner, self.theme.loser) = match self.highlight_pair {
+            Some(index) => {
+                let pair = &theme::HIGHLIGHT_PAIRS[index];
+                (pair.winner, pair.loser)
+            }
+            None => self.theme_highlights,
+        };
+    }
+
+    /// Show `text` in the status strip, optionally only for `duration`.
+    fn set_status(&mut self, text: String, duration: Option<Duration>) {
+        self.status = Some(StatusMessage {
+            text,
+            expires: duration.map(|duration| Instant::now() + duration),
+        });
+    }
+
+    fn set_labels(&mut self, labels: Vec<String>) {
+        self.labels = labels;
+        // Longer labels may need more room in the legend.
+        self.set_size(self.width, self.height);
+    }
+
+    /// Load the die with each face's relative chance of coming up, or make it fair again.
+    fn set_weights(&mut self, weights: Option<Vec<f64>>) {
+        self.configured_weights = weights;
+        self.pending_weights = None;
+        self.update_weights();
+    }
+
+    /// Hold each face's share of the rolls to within its tolerance of what its weight gives it,
+    /// or stop checking.
+    fn set_tolerances(&mut self, tolerances: Option<Vec<f64>>) {
+        self.tolerances = tolerances;
+        self.fits = [Fit::default(); 20];
+        self.update_fits();
+    }
+
+    /// Never roll the faces numbered in `faces`, which must leave at least one face.
+    fn set_excluded(&mut self, faces: &[usize]) {
+        self.excluded = [false; 20];
+        for &face in faces {
+            self.excluded[face - 1] = true;
+        }
+        self.update_weights();
+    }
+
+    /// Work out the weights rolls are drawn with from the configured weights and excluded faces.
+    fn update_weights(&mut self) {
+        let configured = self
+            .configured_weights
+            .clone()
+            .unwrap_or_else(|| vec![1.; self.roll_counts.len()]);
+        let mut weights = configured.clone();
+        for (weight, &excluded) in weights.iter_mut().zip(&self.excluded) {
+            if excluded {
+                *weight = 0.;
+            }
+        }
+        if weights.iter().all(|&weight| weight == 0.) {
+            warn!("every face with any weight is excluded; rolling them anyway");
+            weights = configured;
+        }
+        let loaded = self.configured_weights.is_some() || self.excluded.contains(&true);
+        self.loaded =
+            loaded.then(|| WeightedIndex::new(&weights).expect("weights are checked on load"));
+        self.weights = weights;
+    }
+
+    /// Load the die with `weights` on the next reset.
+    fn defer_weights(&mut self, weights: Option<Vec<f64>>) {
+        self.pending_weights = Some(weights);
+    }
+
+    /// Write a report of how the run was set up and what it rolled to `path`.
+    fn write_report(&mut self, path: &Path) {
+        let excluded = (0..self.roll_counts.len())
+            .filter(|&roll_key| self.excluded[roll_key])
+            .map(|roll_key| self.face_number(roll_key))
+            .collect();
+        let run = report::Run {
+            seed: self.seed,
+            source: match &self.source {
+                Some(source) => source.name(),
+                None if self.continuous => "simulated, bucketing floats",
+                None => "simulated",
+            },
+            weights: self.configured_weights.as_deref(),
+            excluded,
+            stickiness: self.stickiness,
+            labels: &self.labels,
+            first_face: self.first_face,
+            counts: &self.face_totals,
+            checksum: self.roll_hash,
+            unix_seconds: history::unix_seconds(SystemTime::now()),
+        };
+        let saved = if self.failures.take(inject::Point::ExportIo) {
+            Err(inject::io_error())
+        } else {
+            report::save(path, &run)
+        };
+        match saved {
+            Ok(()) => {
+                info!("wrote report to {}", path.display());
+                self.set_status(
+                    format!("Wrote report to {}", path.display()),
+                    Some(STATUS_TIMEOUT),
+                );
+            }
+            Err(err) => {
+                self.set_status(
+                    format!("Could not write report: {err}"),
+                    Some(STATUS_TIMEOUT),
+                );
+                log_error("report::save", err);
+            }
+        }
+    }
+
+    /// Share of the rolls `roll_key` should come up in, going by the weights.
+    fn expected_share(&self, roll_key: usize) -> f64 {
+        self.weights[roll_key] / self.weights.iter().sum::<f64>()
+    }
+
+    /// Check each face's share of the rolls against its tolerance.
+    fn update_fits(&mut self) {
+        let Some(tolerances) = &self.tolerances else {
+            return;
+        };
+        let total = self.face_totals.iter().sum::<u64>();
+        if total == 0 {
+            return;
+        }
+        for (roll_key, &tolerance) in tolerances.iter().enumerate() {
+            let share = self.face_totals[roll_key] as f64 / total as f64;
+            let distance = (share - self.expected_share(roll_key)).abs();
+            self.fits[roll_key] = self.fits[roll_key].update(distance, tolerance);
+        }
+    }
+
+    /// Log a summary of the run and anything that went wrong in it, rotating out older runs'
+    /// logs. `ending` says what ended it.
+    fn write_run_log(&mut self, ending: &str) {
+        let metrics = Metrics::compute(&self.face_totals);
+        let mut warnings = Vec::new();
+        let critical_value = metrics::chi_square_critical_value(self.face_totals.len());
+        if metrics.total > 0 && metrics.chi_square() > critical_value {
+            warnings.push(format!("Bias: {}", summary::verdict(&metrics)));
+        }
+        let stalled: Vec<_> = (0..self.stalls.len())
+            .filter(|&roll_key| self.stalls[roll_key].is_alarming())
+            .map(|roll_key| self.labels[roll_key].as_str())
+            .collect();
+        if !stalled.is_empty() {
+            warnings.push(format!("Stalled faces: {}", stalled.join(", ")));
+        }
+        if self.tolerances.is_some() {
+            let outside: Vec<_> = (0..self.fits.len())
+                .filter(|&roll_key| self.fits[roll_key] == Fit::Outside)
+                .map(|roll_key| self.labels[roll_key].as_str())
+                .collect();
+            if !outside.is_empty() {
+                warnings.push(format!("Out of tolerance: {}", outside.join(", ")));
+            }
+        }
+        if self.saturated {
+            warnings.push("Counts saturated and stopped growing".to_string());
+        }
+        if self.accounting.dropped > 0 {
+            warnings.push(format!("Dropped rolls: {}", self.accounting.dropped));
+        }
+        if self.accounting.invalid > 0 {
+            warnings.push(format!("Invalid rolls: {}", self.accounting.invalid));
+        }
+        if !self.accounting.is_balanced() {
+            warnings.push(format!("Rolls unaccounted for: {}", self.accounting));
+        }
+        let run = runlog::Run {
+            ending,
+            duration: self.launched.elapsed(),
+            labels: &self.labels,
+            counts: &self.face_totals,
+            first_face: self.first_face,
+            warnings,
+            unix_seconds: history::unix_seconds(SystemTime::now()),
+        };
+        let saved = if self.failures.take(inject::Point::ExportIo) {
+            Err(inject::io_error())
+        } else {
+            runlog::save(Path::new(runlog::PATH), &run)
+        };
+        match saved {
+            Ok(()) => info!("logged run to {}", runlog::PATH),
+            Err(err) => log_error("runlog::save", err),
+        }
+    }
+
+    /// Write a bar chart of every face's rolls to `path` as an SVG.
+    fn write_svg(&mut self, path: &Path) {
+        let saved = if self.failures.take(inject::Point::ExportIo) {
+            Err(inject::io_error())
+        } else {
+            svg::save(
+                path,
+                &self.labels,
+                &self.face_totals,
+                self.first_face,
+                &self.theme,
+            )
+        };
+        match saved {
+            Ok(()) => {
+                info!("wrote chart to {}", path.display());
+                self.set_status(
+                    format!("Wrote chart to {}", path.display()),
+                    Some(STATUS_TIMEOUT),
+                );
+            }
+            Err(err) => {
+                self.set_status(
+                    format!("Could not write chart: {err}"),
+                    Some(STATUS_TIMEOUT),
+                );
+                log_error("svg::save", err);
+            }
+        }
+    }
+
+    fn set_title_template(&mut self, template: title::Template) {
+        self.title.set_template(template);
+    }
+
+    fn set_source(&mut self, kind: source::Kind) {
+        info!("taking rolls from {}", kind.name());
+        self.source_kind = kind;
+        self.source = kind.spawn(self.roll_counts.len());
+        // Don't count the wait across a switch as a gap between arrivals.
+        self.last_arrival = None;
+    }
+
+    /// Switch to the next roll source, keeping the counts and noting where the switch happened.
+    /// Rolls the old source had already received are counted before it's stopped.
+    fn cycle_source(&mut self) {
+        if let Some(source) = &mut self.source {
+            source.stop(SOURCE_STOP_TIMEOUT);
+            self.take_source_rolls();
+        }
+        let kind = self.source_kind.next();
+        self.set_source(kind);
+        self.push_note(format!("Switched to {}", kind.name()));
+        self.set_status(
+            format!("Taking rolls from {}", kind.name()),
+            Some(STATUS_TIMEOUT),
+        );
+    }
+
+    /// Chart the chi-square statistics of rounds of `rolls` rolls instead of the faces.
+    fn set_rounds(&mut self, rolls: u64) {
+        self.rounds = Some(Rounds::new(rolls.max(1), self.roll_counts.len()));
+    }
+
+    /// Roll dice with each of `sizes` sides and chart them together instead of the d20.
+    fn set_overlay(&mut self, overlay: Overlay) {
+        self.overlay = Some(overlay);
+    }
+
+    /// Roll `runs` more dice alongside this one, each from a seed derived from this one's, and
+    /// draw their shares as lines over the bars along with the average of them all.
+    fn set_ensemble(&mut self, runs: usize) {
+        let base_seed = self.seed.unwrap_or_else(|| self.rng.gen());
+        self.ensemble = Some(Ensemble::new(runs, self.roll_counts.len(), base_seed));
+    }
+
+    /// Start or stop running a fair die alongside external rolls.
+    fn set_paired(&mut self, paired: bool) {
+        self.simulated = paired.then(|| vec![0; self.roll_counts.len()]);
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Keep every pixel one roll, turning the page whenever a column fills rather than scaling
+    /// the bars down.
+    fn set_pixel_exact(&mut self, pixel_exact: bool) {
+        self.pages = pixel_exact.then(|| Pages::new(self.roll_counts.len()));
+    }
+
+    /// Height of the band along the bottom of the chart that shows the pages turned so far.
+    fn page_band_height(&self) -> u32 {
+        if self.pages.is_some() {
+            self.chart_height / 5
+        } else {
+            0
+        }
+    }
+
+    /// Height of the chart the live bars fill.
+    fn live_height(&self) -> u32 {
+        self.chart_height - self.page_band_height()
+    }
+
+    /// Roll at a rate that climbs along `ramp` instead of a fixed number of rolls per frame.
+    fn set_ramp(&mut self, ramp: Ramp) {
+        self.ramp = Some(ramp);
+    }
+
+    /// The settings a tour changes, as they are now.
+    fn setup(&self) -> demo::Setup {
+        demo::Setup {
+            weights: self.configured_weights.clone(),
+            show_p_value: self.show_p_value,
+            spotlight: self.spotlight,
+            dc: self.show_dc.then_some(self.dc),
+        }
+    }
+
+    /// Switch to `setup` while running, starting the counts over so they reflect only the new
+    /// settings.
+    fn reconfigure(&mut self, setup: &demo::Setup) {
+        self.set_weights(setup.weights.clone());
+        self.show_p_value = setup.show_p_value;
+        self.spotlight = setup.spotlight;
+        self.set_dc(setup.dc);
+        self.start_reset();
+    }
+
+    /// Start looping through the tour from its first step.
+    fn start_demo(&mut self) {
+        let demo = Demo::new(self.setup(), Instant::now());
+        self.reconfigure(&demo.current().setup());
+        self.demo = Some(demo);
+    }
+
+    /// End the tour, putting back the settings from before it started.
+    fn stop_demo(&mut self) {
+        if let Some(demo) = self.demo.take() {
+            self.reconfigure(&demo.restore);
+            self.set_status("Demo stopped".to_string(), Some(STATUS_TIMEOUT));
+        }
+    }
+
+    /// Move the tour on to its next step once the current one has run its course.
+    fn advance_demo(&mut self, now: Instant) {
+        let Some(step) = self.demo.as_mut().and_then(|demo| demo.advance(now)) else {
+            return;
+        };
+        self.reconfigure(&step.setup());
+    }
+
+    /// Stop rolling after `total` rolls, until the next reset.
+    fn set_budget(&mut self, total: u64) {
+        self.budget = Some(Budget { total, spent: 0 });
+    }
+
+    fn toggle_paused(&mut self) {
+        self.paused = !self.paused;
+        let status = if self.paused { "Paused" } else { "Resumed" };
+        self.set_status(status.to_string(), Some(STATUS_TIMEOUT));
+    }
+
+    /// Pause once no face's share strays more than `threshold` from an even share for a while.
+    fn set_convergence(&mut self, threshold: Option<f64>) {
+        self.convergence = threshold.map(convergence::Detector::new);
+    }
+
+    /// Whether the counts have converged since a summary was last saved, clearing it.
+    fn take_summary_pending(&mut self) -> bool {
+        std::mem::take(&mut self.summary_pending)
+    }
+
+    /// Ask for another window, which the event loop opens as it can.
+    fn request_window(&mut self) {
+        self.window_requested = true;
+    }
+
+    /// Whether another window has been asked for since this was last called.
+    fn take_window_requested(&mut self) -> bool {
+        std::mem::take(&mut self.window_requested)
+    }
+
+    /// A copy of what the main window is showing, for another window to start from.
+    fn current_view(&self) -> WindowView {
+        WindowView {
+            classing: self.classing,
+            meter: self
+                .meter
+                .as_ref()
+                .map(|_| meter::Meter::new(self.roll_counts.len())),
+        }
+    }
+
+    /// Trade what the main window shows for `view`, so it can be drawn or changed with the
+    /// main window's methods, and traded back after.
+    fn swap_view(&mut self, view: &mut WindowView) {
+        std::mem::swap(&mut self.classing, &mut view.classing);
+        std::mem::swap(&mut self.meter, &mut view.meter);
+    }
+
+    /// Move another window's `view` on to its next view, leaving the main window's alone.
+    fn cycle_detached_view(&mut self, view: &mut WindowView) {
+        self.swap_view(view);
+        self.cycle_view();
+        self.swap_view(view);
+    }
+
+    /// Bring another window's `view` up to date with the latest rolls.
+    fn tick_view(&self, view: &mut WindowView, now: Instant) {
+        if let Some(meter) = &mut view.meter {
+            meter.tick(&self.slices.window_counts(), now);
+        }
+    }
+
+    /// Draw `view` into another window's `frame` of `width` by `height` pixels from the shared
+    /// counts and statistics, leaving out the main window's cursor, menu, and title bar.
+    fn draw_detached(&mut self, frame: &mut [u8], view: &mut WindowView, width: u32, height: u32) {
+        let size = (self.width, self.height);
+        let cursor_position = self.cursor_position.take();
+        let menu = self.menu.take();
+        let title_bar = std::mem::take(&mut self.title_bar);
+        self.swap_view(view);
+        self.set_size(width, height);
+        self.draw(frame);
+        self.swap_view(view);
+        self.title_bar = title_bar;
+        self.set_size(size.0, size.1);
+        self.cursor_position = cursor_position;
+        self.menu = menu;
+    }
+
+    /// How long the counts took to converge, for the summary.
+    fn convergence_line(&self) -> Option<String> {
+        self.converged.as_ref().map(|converged| {
+            format!(
+                "Converged after {} rolls in {:.1}s",
+                converged.rolls,
+                converged.elapsed.as_secs_f64()
+            )
+        })
+    }
+
+    /// Move on to the next view: the faces, each classing of them, then the meters.
+    fn cycle_view(&mut self) {
+        if self.meter.take().is_none() {
+            self.classing = Classing::next(self.classing);
+            if self.classing.is_none() {
+                self.meter = Some(meter::Meter::new(self.roll_counts.len()));
+            }
+        }
+        let name = match (&self.meter, self.classing) {
+            (Some(_), _) => "Last second",
+            (None, Some(classing)) => classing.name(),
+            (None, None) => "Faces",
+        };
+        self.set_status(name.to_string(), Some(STATUS_TIMEOUT));
+    }
+
+    /// Update the counts at most `hz` times a second, or before every draw if `None`, drawing
+    /// the bars partway between updates if `interpolate` is set.
+    fn set_update_rate(&mut self, hz: Option<u32>, interpolate: bool) {
+        self.update_interval = hz.map(|hz| Duration::from_secs_f64(1. / hz as f64));
+        self.interpolate = interpolate && hz.is_some();
+    }
+
+    fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+
+    /// Whether it's been long enough since the last update for another.
+    fn is_update_due(&self, now: Instant) -> bool {
+        match (self.update_interval, self.last_update) {
+            (Some(interval), Some(last_update)) => {
+                now.saturating_duration_since(last_update) >= interval
+            }
+            _ => true,
+        }
+    }
+
+    /// The live counts to draw: when interpolating, partway from before the last update's rolls
+    /// to after them, by how far we are toward the next update.
+    fn displayed_counts(&self) -> [u64; 20] {
+        if self.integer_render {
+            return self.displayed_counts_integer();
+        }
+        if let Some(started) = self.draining {
+            let remaining = 1. - (started.elapsed().as_secs_f64() / DRAIN_DURATION.as_secs_f64());
+            return self
+                .roll_counts
+                .map(|count| (count as f64 * remaining.max(0.)).round() as u64);
+        }
+        let (true, Some(interval), Some(last_update)) =
+            (self.interpolate, self.update_interval, self.last_update)
+        else {
+            return self.roll_counts;
+        };
+        // Rescaling and resetting shrink the counts at once, which isn't worth animating.
+        let shrunk = self
+            .roll_counts
+            .iter()
+            .zip(&self.previous_counts)
+            .any(|(current, previous)| current < previous);
+        if shrunk {
+            return self.roll_counts;
+        }
+        let progress = self
+            .easing
+            .apply(last_update.elapsed().as_secs_f64() / interval.as_secs_f64());
+        let mut counts = self.previous_counts;
+        for (count, &current) in counts.iter_mut().zip(&self.roll_counts) {
+            *count += ((current - *count) as f64 * progress).round() as u64;
+        }
+        counts
+    }
+
+    /// `displayed_counts` without floating point, rounding each step down.
+    fn displayed_counts_integer(&self) -> [u64; 20] {
+        if let Some(started) = self.draining {
+            let duration = DRAIN_DURATION.as_nanos();
+            let remaining = duration.saturating_sub(started.elapsed().as_nanos());
+            return self
+                .roll_counts
+                .map(|count| (u128::from(count) * remaining / duration) as u64);
+        }
+        let (true, Some(interval), Some(last_update)) =
+            (self.interpolate, self.update_interval, self.last_update)
+        else {
+            return self.roll_counts;
+        };
+        let shrunk = self
+            .roll_counts
+            .iter()
+            .zip(&self.previous_counts)
+            .any(|(current, previous)| current < previous);
+        if shrunk {
+            return self.roll_counts;
+        }
+        let interval = interval.as_nanos().max(1);
+        let elapsed = last_update.elapsed().as_nanos().min(interval);
+        let eased = self.easing.apply_integer(elapsed, interval);
+        let mut counts = self.previous_counts;
+        for (count, &current) in counts.iter_mut().zip(&self.roll_counts) {
+            *count += (u128::from(current - *count) * eased / interval) as u64;
+        }
+        counts
+    }
+
+    /// Write `count` in the notation chosen for labels.
+    fn format_count(&self, count: u64) -> String {
+        notation::format_count(count, self.notation)
+    }
+
+    fn set_notation(&mut self, notation: Notation) {
+        self.notation = notation;
+    }
+
+    fn cycle_notation(&mut self) {
+        self.notation = self.notation.next();
+        self.set_status(
+            format!("Counts in {} notation", self.notation.name()),
+            Some(STATUS_TIMEOUT),
+        );
+    }
+
+    fn speed_multiplier(&self) -> f64 {
+        SPEEDS[self.speed]
+    }
+
+    fn speed_up(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+
+    fn slow_down(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    fn set_cursor_position(&mut self, position: Option<(u32, u32)>) {
+        self.cursor_position = position;
+    }
+
+    /// Repeat the previous face with chance `stickiness` on each simulated roll, as a worn die
+    /// might, instead of always rolling fresh.
+    fn set_stickiness(&mut self, stickiness: f64) {
+        self.stickiness = stickiness;
+    }
+
+    /// Roll a fair die by bucketing a float from 0 up to 1 into equal-width ranges, one per face,
+    /// which exercises a different path through the random number generator.
+    fn set_continuous(&mut self, continuous: bool) {
+        self.continuous = continuous;
+    }
+
+    /// Roll a reproducible sequence from `seed`, including after each reset.
+    fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Drain the bars to nothing, then start over, or start over at once if there's nothing
+    /// to drain.
+    fn start_reset(&mut self) {
+        if self.draining.is_some() {
+            return;
+        }
+        if self.total_rolls == 0 {
+            self.reset();
+        } else {
+            self.draining = Some(Instant::now());
+        }
+    }
+
+    /// Start over once the bars have finished draining.
+    fn finish_draining(&mut self, now: Instant) {
+        let Some(started) = self.draining else {
+            return;
+        };
+        if now.saturating_duration_since(started) >= DRAIN_DURATION {
+            self.draining = None;
+            self.reset();
+        }
+    }
+
+    /// Clear all counts and start over.
+    fn reset(&mut self) {
+        self.draining = None;
+        if let Some(weights) = self.pending_weights.take() {
+            self.set_weights(weights);
+        }
+        self.clear_counts();
+        self.accounting = Accounting::default();
+        self.roll_hash = RollHash::default();
+        if let Some(seed) = self.seed {
+            // Start the same sequence over so a seeded run can be repeated after a reset.
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+        if let Some(rounds) = &mut self.rounds {
+            rounds.clear();
+        }
+        if let Some(overlay) = &mut self.overlay {
+            overlay.clear();
+        }
+        self.rolls_owed = 0;
+        self.next_backlog_warning = 0;
+        if let Some(budget) = &mut self.budget {
+            budget.spent = 0;
+        }
+        if let Some(ramp) = &mut self.ramp {
+            ramp.restart();
+        }
+        if let Some(ensemble) = &mut self.ensemble {
+            ensemble.restart();
+        }
+        if let Some(pages) = &mut self.pages {
+            pages.clear();
+        }
+        self.last_arrival = None;
+        self.arrival_gaps.clear();
+        self.slices.clear();
+        if let Some(bet) = &mut self.bet {
+            *bet = Bet::new(bet.roll_key);
+        }
+        self.bankroll = 0;
+        if let Some(simulated) = &mut self.simulated {
+            simulated.fill(0);
+        }
+        if let Some(convergence) = &mut self.convergence {
+            convergence.clear();
+        }
+        // Only undo the pause converging caused, not one asked for.
+        if self.converged.take().is_some() {
+            self.paused = false;
+        }
+        self.summary_pending = false;
+        self.run_started = Instant::now();
+    }
+
+    /// Forget every roll, leaving the random number generator where it is.
+    fn clear_counts(&mut self) {
+        self.saturated = false;
+        self.biased = false;
+        self.scale = 0;
+        self.deviation_scale = 0;
+        self.roll_counts = [0; 20];
+        self.total_rolls = 0;
+        self.face_totals = [0; 20];
+        self.tallies.clear();
+        self.last_seen = [0; 20];
+        self.last_roll_key = None;
+        self.transitions.clear();
+        self.stalls = [Stall::default(); 20];
+        self.winner_highlight.clear();
+        self.loser_highlight.clear();
+        self.fits = [Fit::default(); 20];
+        if let Some(captures) = &mut self.captures {
+            captures.clear();
+        }
+    }
+
+    /// Show exported `counts` as if they'd been rolled, with their labels. They have to be for
+    /// a d20.
+    fn set_counts(&mut self, counts: &export::Counts) -> Result<(), export::Error> {
+        if counts.counts.len() != self.roll_counts.len() {
+            return Err(export::Error::Mismatch);
+        }
+        self.clear_counts();
+        self.roll_counts.copy_from_slice(&counts.counts);
+        self.face_totals.copy_from_slice(&counts.counts);
+        self.total_rolls = counts
+            .counts
+            .iter()
+            .fold(0u64, |total, &count| total.saturating_add(count));
+        // Nothing says when each face last came up, so treat them all as current.
+        self.last_seen = [self.total_rolls; 20];
+        self.tallies.rebuild(&self.face_totals);
+        self.set_labels(counts.labels.clone());
+        self.update_statistics();
+        Ok(())
+    }
+
+    /// Save every face's total rolls as CSV.
+    fn save_counts(&mut self, path: &Path) {
+        let counts = export::Counts {
+            labels: self.labels.clone(),
+            counts: self.face_totals.to_vec(),
+        };
+        let saved = if self.failures.take(inject::Point::ExportIo) {
+            Err(export::Error::Io(inject::io_error()))
+        } else {
+            export::save(path, &counts)
+        };
+        match saved {
+            Ok(()) => {
+                info!("saved counts to {}", path.display());
+                self.set_status(
+                    format!("Saved counts to {}", path.display()),
+                    Some(STATUS_TIMEOUT),
+                );
+            }
+            Err(err) => {
+                self.set_status(format!("{err}"), Some(STATUS_TIMEOUT));
+                log_error("export::save", err);
+            }
+        }
+    }
+
+    /// Print a text bar chart of every face's rolls to standard output, for pasting into notes.
+    fn print_chart(&mut self) {
+        println!("{}", self.ascii_chart(ascii::terminal_width()));
+        self.set_status(
+            "Printed the chart to standard output".to_string(),
+            Some(STATUS_TIMEOUT),
+        );
+    }
+
+    /// A text bar chart of the counts for a terminal `terminal_width` columns wide, or one for
+    /// each die when comparing dice of several sizes.
+    fn ascii_chart(&self, terminal_width: usize) -> String {
+        let Some(overlay) = &self.overlay else {
+            let sides = self.sides();
+            return ascii::render(
+                &self.face_totals[..sides],
+                &self.labels[..sides],
+                self.first_face,
+                terminal_width,
+            );
+        };
+        overlay
+            .dice
+            .iter()
+            .map(|counts| {
+                let labels = face_numbers(counts.len(), self.first_face);
+                format!(
+                    "d{}\n{}",
+                    counts.len(),
+                    ascii::render(counts, &labels, self.first_face, terminal_width)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Number of faces on the die.
+    fn sides(&self) -> usize {
+        self.roll_counts.len()
+    }
+
+    /// Spread the rolls so far evenly across the faces, keeping the total, so a run can be
+    /// watched drifting away from fair again. Any remainder goes one each to the lowest faces.
+    fn soft_reset(&mut self) {
+        even_out(&mut self.face_totals);
+        even_out(&mut self.roll_counts);
+        self.tallies.rebuild(&self.face_totals);
+        self.update_statistics();
+        self.set_status("Counts evened out".to_string(), Some(STATUS_TIMEOUT));
+    }
+
+    /// If the current round has made all its rolls, record its chi-square and start the next.
+    fn finish_round(&mut self) {
+        let Some(rounds) = &mut self.rounds else {
+            return;
+        };
+        if self.total_rolls < rounds.rolls {
+            return;
+        }
+        rounds.record(Metrics::compute(&self.face_totals).chi_square());
+        self.clear_counts();
+    }
+
+    /// Roll the die once and record the result.
+    fn roll_once(&mut self) {
+        let roll_key = match (self.last_roll_key, &self.loaded) {
+            // Skip the draw when rolls can't stick, so seeded runs are unchanged.
+            (Some(last_roll_key), _)
+                if self.stickiness > 0. && self.rng.gen_bool(self.stickiness) =>
+            {
+                last_roll_key
+            }
+            (_, Some(loaded)) => loaded.sample(&mut self.rng),
+            (_, None) if self.continuous => {
+                let sides = self.roll_counts.len();
+                // Rounding can't carry a float below 1 up to `sides`, but stay on the die
+                // regardless.
+                ((self.rng.gen::<f64>() * sides as f64).floor() as usize).min(sides - 1)
+            }
+            (_, None) => self.rng.gen_range(1..=20) - 1,
+        };
+        self.accounting.generated += 1;
+        self.record_roll(roll_key);
+    }
+
+    /// Record a roll of `roll_key`, however it was made.
+    fn record_roll(&mut self, roll_key: usize) {
+        let live_count = self
+            .roll_counts
+            .get_mut(roll_key)
+            .expect("roll value not found");
+        // Stop at the limit rather than wrapping around to zero, and say so.
+        for count in [
+            live_count,
+            &mut self.total_rolls,
+            &mut self.face_totals[roll_key],
+            &mut self.accounting.tallied,
+        ] {
+            match count.checked_add(1) {
+                Some(next) => *count = next,
+                None => self.saturated = true,
+            }
+        }
+        self.tallies.record(roll_key, self.roll_counts.len());
+        self.roll_hash.record(roll_key);
+        self.last_seen[roll_key] = self.total_rolls;
+        if let Some(last_roll_key) = self.last_roll_key {
+            self.transitions.record(last_roll_key, roll_key);
+        }
+        self.last_roll_key = Some(roll_key);
+        self.slices.record(roll_key);
+        if let Some(captures) = &mut self.captures {
+            captures.record(roll_key);
+        }
+        if let Some(bet) = &mut self.bet {
+            self.bankroll += bet.settle(roll_key, self.roll_counts.len());
+        }
+    }
+
+    /// Record a roll made outside, such as with a physical die, matching it with a simulated
+    /// one if the two are being compared.
+    fn record_external_roll(&mut self, roll_key: usize) {
+        self.accounting.generated += 1;
+        self.record_roll(roll_key);
+        if let Some(simulated) = &mut self.simulated {
+            let simulated_key = self.rng.gen_range(0..simulated.len());
+            simulated[simulated_key] += 1;
+        }
+    }
+
+    /// Number of rolls since `roll_key` last came up (or since the last reset).
+    fn rolls_since_seen(&self, roll_key: usize) -> u64 {
+        self.total_rolls - self.last_seen[roll_key]
+    }
+
+    /// Number of rolls since any face in `column` last came up.
+    fn column_rolls_since_seen(&self, column: usize) -> u64 {
+        self.column_faces(column)
+            .map(|roll_key| self.rolls_since_seen(roll_key))
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Number of bars drawn: one per group, or one per face if faces aren't grouped, less any
+    /// that are hidden.
+    fn column_count(&self) -> usize {
+        self.shown_columns.len()
+    }
+
+    /// The faces making up `column`'s bar.
+    fn column_faces(&self, column: usize) -> RangeInclusive<usize> {
+        self.group_faces(self.shown_columns[column])
+    }
+
+    /// The faces in the group at `index`, or just the face at `index` if faces aren't grouped,
+    /// whether or not it's shown.
+    fn group_faces(&self, index: usize) -> RangeInclusive<usize> {
+        match &self.groups {
+            Some(groups) => groups[index].faces.clone(),
+            None => index..=index,
+        }
+    }
+
+    /// `column`'s name: the face's label, or the labels of a group's first and last faces.
+    fn column_label(&self, column: usize) -> String {
+        let faces = self.column_faces(column);
+        if faces.start() == faces.end() {
+            self.labels[*faces.start()].clone()
+        } else {
+            format!(
+                "{}-{}",
+                self.labels[*faces.start()],
+                self.labels[*faces.end()]
+            )
+        }
+    }
+
+    /// Per-face `counts` summed into one count per column.
+    fn column_counts(&self, counts: &[u64]) -> Vec<u64> {
+        (0..self.column_count())
+            .map(|column| {
+                self.column_faces(column)
+                    .map(|roll_key| counts[roll_key])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// How many more rolls each column has had than the weights would give it, or fewer if
+    /// negative.
+    fn column_deviations(&self) -> Vec<i64> {
+        let expected = metrics::expected_counts(self.total_rolls, &self.weights);
+        self.column_counts(&self.face_totals)
+            .into_iter()
+            .zip(self.column_counts(&expected))
+            .map(|(count, expected)| count as i64 - expected as i64)
+            .collect()
+    }
+
+    /// Make this frame's share of rolls with the built-in random number generator, deferring
+    /// any that don't fit in the frame budget.
+    fn roll_from_rng(&mut self, started: Instant) {
+        let base_rolls = match &mut self.ramp {
+            Some(ramp) => ramp.tick(started),
+            None => self.rolls_per_frame,
+        };
+        let scaled_rolls = base_rolls as f64 * self.speed_multiplier() + self.speed_remainder;
+        let rolls_this_frame = scaled_rolls.floor();
+        self.speed_remainder = scaled_rolls - rolls_this_frame;
+        let mut remaining = self.rolls_owed.saturating_add(rolls_this_frame as u64);
+        if let Some(budget) = &self.budget {
+            // Cut the last batch short so the budget is spent exactly.
+            remaining = remaining.min(budget.total - budget.spent);
+        }
+        let planned = remaining;
+        while remaining > 0 {
+            let chunk = remaining.min(ROLL_CHUNK);
+            for _ in 0..chunk {
+                match &mut self.overlay {
+                    Some(overlay) => overlay.roll(&mut self.rng),
+                    None => {
+                        self.roll_once();
+                        self.finish_round();
+                    }
+                }
+            }
+            if let Some(ensemble) = &mut self.ensemble {
+                ensemble.roll(chunk, self.loaded.as_ref());
+            }
+            remaining -= chunk;
+            if started.elapsed() >= self.frame_budget {
+                break;
+            }
+        }
+        if let Some(budget) = &mut self.budget {
+            budget.spent += planned - remaining;
+        }
+        self.rolls_owed = remaining;
+        if self.rolls_owed == 0 {
+            self.next_backlog_warning = 0;
+        } else if self.rolls_owed >= self.next_backlog_warning.max(rolls_this_frame as u64) {
+            warn!(
+                "can't keep up with {rolls_this_frame} rolls per frame; {} rolls behind",
+                self.rolls_owed
+            );
+            self.next_backlog_warning = self.rolls_owed * 2;
+        }
+    }
+
+    /// Record everything the configured roll source has for us.
+    fn take_source_rolls(&mut self) {
+        let Some(source) = &mut self.source else {
+            return;
+        };
+        let external = source.is_external();
+        let mut rolls = Vec::new();
+        source.poll(&mut rolls);
+        let invalid = source.take_invalid();
+        self.accounting.generated += invalid;
+        self.accounting.invalid += invalid;
+        for roll in rolls {
+            if external {
+                if let Some(last_arrival) = self.last_arrival {
+                    self.arrival_gaps
+                        .record(roll.arrived.saturating_duration_since(last_arrival));
+                }
+                self.last_arrival = Some(roll.arrived);
+                self.record_external_roll(roll.roll_key);
+            } else {
+                self.accounting.generated += 1;
+                self.record_roll(roll.roll_key);
+            }
+        }
+    }
+
+    /// Update the `World` internal state; bounce the box around the screen.
+    fn update(&mut self) {
+        let started = Instant::now();
+        self.last_update = Some(started);
+        self.previous_counts = self.roll_counts;
+        if let Some(StatusMessage {
+            expires: Some(expires),
+            ..
+        }) = self.status
+        {
+            if started >= expires {
+                self.status = None;
+            }
+        }
+        self.slices.advance(started);
+        if let Some(meter) = &mut self.meter {
+            meter.tick(&self.slices.window_counts(), started);
+        }
+        self.advance_demo(started);
+        self.finish_draining(started);
+
+        // While paused or draining, a source's rolls are left waiting until we resume.
+        if !self.paused && self.draining.is_none() {
+            if self.source.is_some() {
+                self.take_source_rolls();
+            } else {
+                self.roll_from_rng(started);
+            }
+        } else if let Some(ramp) = &mut self.ramp {
+            ramp.stop();
+        }
+
+        self.accounting.check();
+        self.update_statistics();
+        self.check_bias(started);
+        if let Some(captures) = &mut self.captures {
+            captures.check(self.total_rolls, self.metrics.winner());
+        }
+        self.check_convergence(started);
+        self.update_title();
+        let sort_due = self
+            .last_sort
+            .is_none_or(|last_sort| started - last_sort >= SORT_INTERVAL);
+        if self.sorted && sort_due {
+            self.sort_columns();
+            self.last_sort = Some(started);
+        }
+
+        let widest_deviation = self
+            .column_deviations()
+            .into_iter()
+            .map(i64::unsigned_abs)
+            .max()
+            .unwrap_or(0);
+        self.deviation_scale = hysteresis_scale(
+            self.deviation_scale,
+            widest_deviation,
+            self.live_height() as u64 / 2,
+        );
+        if self.pages.is_some() {
+            self.turn_page_if_full();
+            return;
+        }
+        // Hidden faces can grow past the top, since they aren't drawn.
+        let max_found = self
+            .column_counts(&self.roll_counts)
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+        let capacity = self.column_width as u64 * self.live_height() as u64;
+        self.scale = hysteresis_scale(self.scale, max_found, capacity);
+    }
+
+    /// Set the live counts aside as a page once any column has more rolls than pixels.
+    fn turn_page_if_full(&mut self) {
+        let capacity = self.column_width as u64 * self.live_height() as u64;
+        let fullest = self
+            .column_counts(&self.roll_counts)
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+        // With no room to draw in, such as before the window has a size, there's nothing to fill.
+        if capacity == 0 || fullest <= capacity {
+            return;
+        }
+        if let Some(pages) = &mut self.pages {
+            pages.turn(&mut self.roll_counts);
+        }
+    }
+
+    /// Bring everything derived from the counts up to date after a batch of rolls.
+    fn update_statistics(&mut self) {
+        self.metrics.recompute(&self.face_totals);
+        if self.hidden.contains(&true) {
+            let shown = self.hidden.map(|hidden| !hidden);
+            self.metrics.rank_among(&self.face_totals, &shown);
+        }
+        let now = Instant::now();
+        self.winner_highlight.update(self.metrics.winner(), now);
+        self.loser_highlight.update(self.metrics.loser(), now);
+        self.update_fits();
+        self.overdue_roll_key = (0..self.roll_counts.len())
+            .filter(|&roll_key| !self.excluded[roll_key])
+            .max_by_key(|&roll_key| {
+                // Prefer the lowest face on ties so the highlight doesn't jump around.
+                (self.rolls_since_seen(roll_key), std::cmp::Reverse(roll_key))
+            });
+        // Excluded faces are meant to stop coming up, so they're never flagged as stalled.
+        for roll_key in (0..self.roll_counts.len()).filter(|&roll_key| !self.excluded[roll_key]) {
+            let rolls_since_seen = self.rolls_since_seen(roll_key);
+            let change =
+                self.stalls[roll_key].update(rolls_since_seen, self.total_rolls, self.stall_window);
+            match change {
+                stall::Change::Stalled => warn!(
+                    "{} hasn't come up in {rolls_since_seen} rolls",
+                    self.labels[roll_key]
+                ),
+                stall::Change::Recovered => info!("{} is coming up again", self.labels[roll_key]),
+                stall::Change::None => (),
+            }
+        }
+    }
+
+    /// Log when the chi-square test starts or stops finding the counts biased, once there are
+    /// enough rolls to trust it, and flash or beep if asked to when it starts.
+    fn check_bias(&mut self, now: Instant) {
+        let sides = self.roll_counts.len();
+        let critical_value = metrics::chi_square_critical_value(sides);
+        let chi_square = self.metrics.chi_square();
+        let biased =
+            self.total_rolls >= MIN_EXPECTED_PER_FACE * sides as u64 && chi_square > critical_value;
+        if biased == self.biased {
+            return;
+        }
+        self.biased = biased;
+        if let Some(captures) = &mut self.captures {
+            captures.verdict_changed(biased, self.total_rolls);
+        }
+        if !biased {
+            info!("counts look fair again at {} rolls", self.total_rolls);
+            return;
+        }
+        warn!(
+            "counts look biased at {} rolls (chi-square {chi_square:.2} above {critical_value:.2})",
+            self.total_rolls
+        );
+        if self.bias_alert {
+            self.flash = Some(now);
+            if self.beep_on_bias {
+                #[cfg(feature = "audio")]
+                platform::beep();
+            }
+        }
+    }
+
+    /// Pause and report how long it took once the counts have settled close to even.
+    fn check_convergence(&mut self, now: Instant) {
+        let Some(convergence) = &mut self.convergence else {
+            return;
+        };
+        // Rounds keep starting over, so they never have time to converge.
+        if self.paused || self.rounds.is_some() || self.total_rolls == 0 {
+            return;
+        }
+        if !convergence.update(self.metrics.max_share_deviation(), now) {
+            return;
+        }
+        self.converged = Some(Converged {
+            rolls: self.total_rolls,
+            elapsed: now - self.run_started,
+            at: now,
+        });
+        self.paused = true;
+        self.summary_pending = true;
+        for line in summary::lines(&self.metrics, self.first_face)
+            .into_iter()
+            .chain(self.convergence_line())
+        {
+            info!("{line}");
+        }
+        self.set_status(
+            format!("Converged after {} rolls", self.total_rolls),
+            Some(STATUS_TIMEOUT),
+        );
+    }
+
+    /// Shannon entropy of the faces rolled so far, in bits: 0 when every roll has been the same
+    /// face, up to `log2(sides)` when the counts are perfectly even.
+    fn entropy(&self) -> f64 {
+        if self.total_rolls == 0 {
+            return 0.;
+        }
+        let total = self.total_rolls as f64;
+        metrics::compensated_sum(
+            self.face_totals
+                .iter()
+                // Faces never rolled add nothing, as `p * log2(p)` tends to 0 with `p`.
+                .filter(|&&count| count > 0)
+                .map(|&count| {
+                    let share = count as f64 / total;
+                    -share * share.log2()
+                }),
+        )
+    }
+
+    /// The most entropy the die's rolls can have, reached when every face comes up equally.
+    fn max_entropy(&self) -> f64 {
+        (self.roll_counts.len() as f64).log2()
+    }
+
+    /// The current statistics as a single line of JSON, for remote clients.
+    fn stats_json(&self) -> String {
+        // Rolls may have been recorded since the last update, so don't rely on `self.metrics`.
+        let metrics = Metrics::compute(&self.face_totals);
+        serde_json::json!({
+            "sides": self.roll_counts.len(),
+            "total_rolls": self.total_rolls,
+            "checksum": self.roll_hash.to_string(),
+            "counts": self.face_totals,
+            "chi_square": metrics.chi_square(),
+            "mean_roll": metrics.mean_face(self.first_face),
+            "entropy": self.entropy(),
+            "notes": self.notes.iter().map(|note| serde_json::json!({
+                "text": note.text,
+                "time": note.unix_seconds,
+                "total_rolls": note.total_rolls,
+            })).collect::<Vec<_>>(),
+            "classes": {
+                "parity": self.tallies.parity,
+                "mod4": self.tallies.mod4,
+                "halves": self.tallies.halves,
+            },
+            "accounting": {
+                "generated": self.accounting.generated,
+                "tallied": self.accounting.tallied,
+                "dropped": self.accounting.dropped,
+                "invalid": self.accounting.invalid,
+            },
+            "saturated": self.saturated,
+            "paused": self.paused,
+            "dc": self.show_dc.then(|| {
+                let (chance, advantage, disadvantage) = self.dc_chances(&metrics);
+                serde_json::json!({
+                    "dc": self.face_number(self.dc_roll_key()),
+                    "chance": chance,
+                    "advantage": advantage,
+                    "disadvantage": disadvantage,
+                })
+            }),
+            "spotlight": self.spotlight.map(|threshold| serde_json::json!({
+                "threshold": threshold,
+                "faces": metrics
+                    .outliers(threshold)
+                    .iter()
+                    .map(|&roll_key| self.face_number(roll_key))
+                    .collect::<Vec<_>>(),
+            })),
+        })
+        .to_string()
+    }
+
+    /// Bring the window title's segments up to date; the title itself is only recomposed
+    /// every so often.
+    fn update_title(&mut self) {
+        self.title
+            .set(Segment::Total, self.format_count(self.total_rolls));
+        let mode = match &self.source {
+            _ if self.paused => "paused".to_string(),
+            Some(source) => source.name().to_string(),
+            None => format!("{}x speed", self.speed_multiplier()),
+        };
+        self.title.set(Segment::Mode, mode);
+        let hover = match self.hovered_column() {
+            Some(column) => format!(
+                "{}: {} rolls",
+                self.column_label(column),
+                self.format_count(
+                    self.column_faces(column)
+                        .map(|roll_key| self.face_totals[roll_key])
+                        .sum()
+                )
+            ),
+            None => String::new(),
+        };
+        self.title.set(Segment::Hover, hover);
+        let stalled = self
+            .stalls
+            .iter()
+            .filter(|stall| stall.is_alarming())
+            .count();
+        let alert = if stalled == 0 {
+            String::new()
+        } else {
+            format!("{stalled} stalled")
+        };
+        self.title.set(Segment::Alert, alert);
+    }
+
+    /// The column whose bar or labels the cursor is over.
+    fn hovered_column(&self) -> Option<usize> {
+        let (cursor_x, cursor_y) = self.cursor_position?;
+        let cutoff = self.offset + self.column_width * self.column_count() as u32;
+        if cursor_y < self.chart_top || cursor_x < self.offset || cursor_x >= cutoff {
+            return None;
+        }
+        Some(((cursor_x - self.offset) / self.column_width) as usize)
+    }
+
+    /// Draw the `World` state to the frame buffer.
+    ///
+    /// Assumes the default texture format: `wgpu::TextureFormat::Rgba8UnormSrgb`
+    fn draw(&self, frame: &mut [u8]) {
+        if let Some(rounds) = &self.rounds {
+            self.draw_rounds(frame, rounds);
+        } else if let Some(overlay) = self.overlay.as_ref().filter(|overlay| overlay.grid) {
+            self.draw_dice_set(frame, overlay);
+        } else if let Some(overlay) = &self.overlay {
+            self.draw_overlay(frame, overlay);
+        } else if let Some(meter) = &self.meter {
+            self.draw_meter(frame, meter);
+        } else if let Some(classing) = self.classing {
+            self.draw_classes(frame, classing);
+        } else if self.show_transitions {
+            self.draw_transitions(frame);
+        } else if self.show_deviations {
+            self.draw_deviations(frame);
+        } else {
+            self.draw_faces(frame);
+        }
+        self.draw_menu(frame);
+        self.draw_flash(frame);
+        self.draw_colorblind_preview(frame);
+        self.apply_opacity(frame);
+    }
+
+    /// Give every pixel the window's opacity. Drawing never reads alpha back, so setting it
+    /// outright, rather than scaling it, keeps it from compounding across frames.
+    fn apply_opacity(&self, frame: &mut [u8]) {
+        let Some(opacity) = self.opacity else {
+            return;
+        };
+        let alpha = (opacity * 255.).round() as u8;
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel[3] = alpha;
+        }
+    }
+
+    /// Replace the frame with three strips stacked top to bottom, each the whole frame squashed
+    /// to a third of its height and recolored as seen with one kind of color blindness.
+    fn draw_colorblind_preview(&self, frame: &mut [u8]) {
+        if !self.show_colorblind_preview || self.integer_render {
+            return;
+        }
+        let original = frame.to_vec();
+        let strips = theme::Deficiency::ALL.len() as u32;
+        let strip_height = self.height / strips;
+        if strip_height == 0 {
+            return;
+        }
+        let row_bytes = self.width as usize * 4;
+        for (strip, deficiency) in theme::Deficiency::ALL.into_iter().enumerate() {
+            // Charts only use a handful of colors, so each is worked out once.
+            let mut simulated = BTreeMap::new();
+            let top = strip as u32 * strip_height;
+            for row in 0..strip_height {
+                let source_row = (row * self.height / strip_height).min(self.height - 1) as usize;
+                let source = &original[source_row * row_bytes..][..row_bytes];
+                let target = &mut frame[(top + row) as usize * row_bytes..][..row_bytes];
+                for (from, to) in source.chunks_exact(4).zip(target.chunks_exact_mut(4)) {
+                    let color = [from[0], from[1], from[2], from[3]];
+                    let seen = *simulated
+                        .entry(color)
+                        .or_insert_with(|| theme::simulate(color, deficiency));
+                    to.copy_from_slice(&seen);
+                }
+            }
+            text::draw_text_box(
+                frame,
+                self.width,
+                0,
+                top as i32,
+                self.label_scale,
+                self.theme.text,
+                self.theme.panel,
+                &[deficiency.name()],
+            );
+        }
+        // Rows left over below the last strip.
+        frame[(strips * strip_height) as usize * row_bytes..]
+            .chunks_exact_mut(4)
+            .for_each(|pixel| pixel.copy_from_slice(&self.theme.background));
+    }
+
+    /// Tint the whole frame with the alarm color, fading out as the flash ends.
+    fn draw_flash(&self, frame: &mut [u8]) {
+        let Some(started) = self.flash.filter(|_| !self.integer_render) else {
+            return;
+        };
+        let remaining = 1. - started.elapsed().as_secs_f64() / FLASH_DURATION.as_secs_f64();
+        if remaining <= 0. {
+            return;
+        }
+        for pixel in frame.chunks_exact_mut(4) {
+            let color = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            pixel.copy_from_slice(&theme::blend(color, self.theme.alarm, 0.5 * remaining));
+        }
+    }
+
+    /// Draw a bar for each face or group of faces, with everything laid over them.
+    fn draw_faces(&self, frame: &mut [u8]) {
+        let columns = self.column_count();
+        let segment = self.viewed_segment();
+        let displayed_counts = self.displayed_counts();
+        let face_counts = match segment {
+            Some(segment) => &segment.counts[..],
+            None => &displayed_counts[..],
+        };
+        let counts = self.fit_to_chart(
+            &self.column_counts(face_counts),
+            self.top_count(segment),
+            self.is_pinned(segment),
+        );
+        let total = counts.iter().sum::<u64>();
+        let segment_metrics = segment.map(|segment| Metrics::compute(&segment.counts));
+        let metrics = segment_metrics.as_ref().unwrap_or(&self.metrics);
+        let expected = self.column_counts(&metrics::expected_counts(total, &self.weights));
+        let half_width = self.column_width / 2;
+        let split = self.show_expected && half_width > 0;
+        // Ghosts and alarms describe the live counts, so they're left off past segments.
+        let ghost_counts = self
+            .ghost_counts(total)
+            .filter(|_| segment.is_none())
+            .map(|ghost_counts| self.column_counts(&ghost_counts));
+        // Scale the simulation like the live counts, so equal totals make equal heights.
+        let simulated_counts =
+            self.simulated
+                .as_ref()
+                .filter(|_| segment.is_none())
+                .map(|simulated| {
+                    let scale = total as f64 / self.total_rolls.max(1) as f64;
+                    let scaled: Vec<u64> = simulated
+                        .iter()
+                        .map(|&count| (count as f64 * scale).round() as u64)
+                        .collect();
+                    self.column_counts(&scaled)
+                });
+        let alarming: Vec<bool> = (0..columns)
+            .map(|column| {
+                segment.is_none()
+                    && self
+                        .column_faces(column)
+                        .any(|roll_key| self.stalls[roll_key].is_alarming())
+            })
+            .collect();
+        let spotlit = self.spotlight.map(|threshold| metrics.outliers(threshold));
+        // Past segments don't change, so only the live leaders need holding steady.
+        let (winner, loser) = match segment {
+            Some(_) => (metrics.winner(), metrics.loser()),
+            None => (self.winner_highlight.shown(), self.loser_highlight.shown()),
+        };
+        let colors: Vec<[u8; 4]> = (0..columns)
+            .map(|column| {
+                let faces = self.column_faces(column);
+                // Only single faces are singled out as the winner or loser.
+                let face = (faces.start() == faces.end()).then_some(*faces.start());
+                // Against a target, bars show how well they meet it in place of who's leading.
+                let fit = faces.clone().map(|roll_key| self.fits[roll_key]).max();
+                let color = if let (Some(_), None, Some(fit)) = (&self.tolerances, segment, fit) {
+                    match fit {
+                        Fit::Inside => self.theme.winner,
+                        Fit::Near => self.theme.overdue,
+                        Fit::Outside => self.theme.loser,
+                    }
+                } else if face.is_some() && face == winner {
+                    self.theme.winner
+                } else if face.is_some() && face == loser {
+                    self.theme.loser
+                } else {
+                    self.theme.bars[*faces.end()]
+                };
+                match &spotlit {
+                    Some(spotlit) if !faces.clone().any(|face| spotlit.contains(&face)) => {
+                        theme::blend(self.theme.background, color, self.theme.dim)
+                    }
+                    _ => color,
+                }
+            })
+            .collect();
+        let ghost_fill = theme::blend(self.theme.background, self.theme.ghost, 0.35);
+        // Each column's tolerance band around its expected height, in the same units as its
+        // bar. Like ghosts, bands describe the live counts only.
+        let bands: Option<Vec<(u64, u64)>> = self
+            .tolerances
+            .as_ref()
+            .filter(|_| segment.is_none())
+            .map(|tolerances| {
+                (0..columns)
+                    .map(|column| {
+                        let (low, high) =
+                            self.column_faces(column)
+                                .fold((0., 0.), |(low, high), roll_key| {
+                                    let (face_low, face_high) = tolerance::band(
+                                        self.expected_share(roll_key),
+                                        tolerances[roll_key],
+                                    );
+                                    (low + face_low, high + face_high)
+                                });
+                        let to_count = |share: f64| (share * total as f64).round() as u64;
+                        (to_count(low), to_count(high))
+                    })
+                    .collect()
+            });
+        let band_fill = theme::blend(self.theme.background, self.theme.expected, 0.35);
+        let excluded: Vec<bool> = (0..columns)
+            .map(|column| {
+                self.column_faces(column)
+                    .all(|roll_key| self.excluded[roll_key])
+            })
+            .collect();
+        let hatch = theme::blend(self.theme.background, self.theme.text, 0.15);
+        // Pages turned so far fill the band beneath the live bars, scaled to the fullest.
+        let band_height = self.page_band_height();
+        let page_fills: Vec<u64> = match (&self.pages, segment) {
+            (Some(pages), None) => {
+                let archived = self.column_counts(&pages.counts);
+                let fullest = archived.iter().copied().max().unwrap_or(0).max(1);
+                archived
+                    .iter()
+                    .map(|&count| count * band_height as u64 / fullest)
+                    .collect()
+            }
+            _ => vec![0; columns],
+        };
+        let page_colors: Vec<[u8; 4]> = colors
+            .iter()
+            .map(|&color| theme::blend(self.theme.background, color, 0.5))
+            .collect();
+        let midline = self.live_height() / 2;
+
+        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
+            let total_x = i as u32 % self.width;
+            let screen_row = i as u32 / self.width;
+            if screen_row < self.chart_top || screen_row >= self.chart_top + self.chart_height {
+                pixel.copy_from_slice(&self.theme.background);
+                continue;
+            }
+            let row = screen_row - self.chart_top;
+            let y = self.chart_height - 1 - row;
+
+            let cutoff = self.offset + self.column_width * columns as u32;
+            let column = if total_x >= self.offset && total_x < cutoff {
+                Some(((total_x - self.offset) / self.column_width) as usize)
+            } else {
+                None
+            };
+            if y < band_height {
+                let rgba = match column {
+                    Some(column) if (y as u64) < page_fills[column] => page_colors[column],
+                    _ => self.theme.background,
+                };
+                pixel.copy_from_slice(&rgba);
+                continue;
+            }
+            let y = y - band_height;
+            // Mirrored, each bar runs half as far each way from the midline, so every row
+            // away from it covers two rows' worth of rolls.
+            let (fill_y, rows_per_pixel) = if self.mirrored {
+                let distance = if y >= midline {
+                    y - midline
+                } else {
+                    midline - 1 - y
+                };
+                (2 * distance, 2)
+            } else {
+                (y, 1)
+            };
+            let mut in_expected_half = false;
+            let mut in_ghost = false;
+            let mut in_band = false;
+            let mut on_ghost_edge = false;
+            let mut on_alarm_outline = false;
+            let mut on_simulated_outline = false;
+            let mut hatched = false;
+            let highlighted = if let Some(column) = column {
+                let mut roll_x = total_x - self.offset - column as u32 * self.column_width;
+                if excluded[column] {
+                    // Diagonal stripes, each as thick as a label's strokes.
+                    hatched = ((roll_x + y) / self.label_scale).is_multiple_of(4);
+                }
+                if alarming[column] {
+                    let thickness = self.label_scale;
+                    on_alarm_outline = roll_x < thickness
+                        || roll_x + thickness >= self.column_width
+                        || row < thickness
+                        || y < thickness;
+                }
+                if let Some(simulated_counts) = &simulated_counts {
+                    let simulated = simulated_counts[column];
+                    if simulated > 0 {
+                        let thickness = self.label_scale;
+                        let top = (simulated - 1) / self.column_width as u64;
+                        on_simulated_outline = fill_y as u64 <= top
+                            && (roll_x < thickness
+                                || roll_x + thickness >= self.column_width
+                                || fill_y as u64 + thickness as u64 > top);
+                    }
+                }
+                let mut count = counts[column];
+                if split {
+                    // Stretch each half across the whole column so both share the same scale.
+                    if roll_x < half_width {
+                        roll_x = roll_x * self.column_width / half_width;
+                    } else {
+                        roll_x = (roll_x - half_width) * self.column_width
+                            / (self.column_width - half_width);
+                        count = expected[column];
+                        in_expected_half = true;
+                    }
+                }
+                let value = (fill_y * self.column_width + roll_x + 1) as u64;
+                if let Some(bands) = &bands {
+                    let (low, high) = bands[column];
+                    in_band = low < value && value <= high;
+                }
+                if let (Some(ghost_counts), false) = (&ghost_counts, in_expected_half) {
+                    // The ghost's top edge is drawn over the bars so it's visible either way.
+                    let ghost_count = ghost_counts[column];
+                    in_ghost = value <= ghost_count;
+                    let top = (ghost_count.max(1) - 1) / self.column_width as u64;
+                    on_ghost_edge = ghost_count > 0
+                        && (fill_y / rows_per_pixel) as u64 == top / u64::from(rows_per_pixel);
+                }
+                // Only the bottom few pixels are floored, leaving taller bars' heights as they
+                // are.
+                let floored = !in_expected_half && fill_y / rows_per_pixel < self.min_bar_height;
+                value <= count || floored
+            } else {
+                false
+            };
+
+            let rgba = if on_alarm_outline {
+                self.theme.alarm
+            } else if on_ghost_edge {
+                self.theme.ghost
+            } else if on_simulated_outline {
+                self.theme.simulated
+            } else if highlighted {
+                let color = if in_expected_half {
+                    self.theme.expected
+                } else {
+                    colors[column.unwrap()]
+                };
+                if in_band {
+                    theme::blend(color, self.theme.expected, 0.35)
+                } else {
+                    color
+                }
+            } else if in_ghost {
+                ghost_fill
+            } else if in_band {
+                band_fill
+            } else if hatched {
+                hatch
+            } else if self.trail > 0 {
+                // Fading all the way back to the background keeps old bars from building up.
+                let previous = [pixel[0], pixel[1], pixel[2], pixel[3]];
+                theme::fade(previous, self.theme.background, self.trail)
+            } else {
+                self.theme.background
+            };
+
+            pixel.copy_from_slice(&rgba);
+        }
+
+        self.draw_clipped(frame, &counts);
+        self.draw_big_total(frame);
+        self.draw_smoothed(frame, &self.column_counts(face_counts), total);
+        self.draw_fit(frame, metrics, total);
+        self.draw_ensemble(frame, total);
+        self.draw_crosshair(frame);
+        self.draw_labels(frame);
+        self.draw_stats_panel(frame);
+        self.draw_legend(frame);
+        self.draw_heat_strip(frame);
+        self.draw_tooltip(frame);
+        self.draw_panels(frame, self.panels());
+        self.draw_dc(frame, metrics);
+        self.draw_budget(frame);
+        self.draw_convergence_border(frame);
+        self.draw_title_bar(frame);
+    }
+
+    /// Show the total rolls as large as fits, centered on the chart and faint enough to read
+    /// past, in the background between the bars.
+    fn draw_big_total(&self, frame: &mut [u8]) {
+        if !self.show_big_total || self.integer_render || self.tier == Tier::BarsOnly {
+            return;
+        }
+        let total = self.format_count(self.total_rolls);
+        let chart_width = self.chart_width();
+        let scale = (chart_width * 4 / 5 / text::text_width(&total, 1).max(1))
+            .min(self.chart_height / 2 / text::GLYPH_HEIGHT);
+        if scale == 0 {
+            return;
+        }
+        let x = (chart_width - text::text_width(&total, scale)) / 2;
+        let y = self.chart_top + (self.chart_height - text::text_height(scale)) / 2;
+        text::draw_text_behind(
+            frame,
+            self.width,
+            x as i32,
+            y as i32,
+            scale,
+            theme::blend(self.theme.background, self.theme.text, 0.15),
+            self.theme.background,
+            &total,
+        );
+    }
+
+    /// Draw the right-click menu, if it's open, with its highlighted item picked out.
+    fn draw_menu(&self, frame: &mut [u8]) {
+        let Some(menu) = &self.menu else {
+            return;
+        };
+        let lines = menu::lines(self.paused);
+        text::draw_text_box(
+            frame,
+            self.width,
+            menu.x,
+            menu.y,
+            self.label_scale,
+            self.theme.text,
+            self.theme.panel,
+            &lines,
+        );
+        let (width, _) = text::text_box_size(self.label_scale, &lines);
+        let line_height = text::text_height(self.label_scale) + 2 * self.label_scale;
+        let row_y = menu.y + (self.label_scale + menu.selected as u32 * line_height) as i32;
+        text::fill_rect(
+            frame,
+            self.width,
+            menu.x,
+            row_y,
+            width,
+            line_height,
+            self.theme.text,
+        );
+        text::draw_text(
+            frame,
+            self.width,
+            menu.x + 2 * self.label_scale as i32,
+            row_y + self.label_scale as i32,
+            self.label_scale,
+            self.theme.panel,
+            lines[menu.selected],
+        );
+    }
+
+    /// Draw a curve through the bars smoothed from each column's true count, scaled to the
+    /// `drawn_total` rolls the bars are drawn with. Left off with too few bars to smooth.
+    fn draw_smoothed(&self, frame: &mut [u8], column_counts: &[u64], drawn_total: u64) {
+        // The curve follows the tops of upright bars, which mirrored bars don't have.
+        if !self.show_smoothed
+            || self.mirrored
+            || self.integer_render
+            || column_counts.len() < smooth::MIN_BUCKETS
+        {
+            return;
+        }
+        let shares = smooth::smooth(column_counts, smooth::bandwidth(column_counts.len()));
+        let thickness = self.label_scale.max(2);
+        self.draw_curve(frame, &shares, drawn_total, thickness, self.theme.text);
+    }
+
+    /// Draw the normal distribution fitted to `metrics` as a thin line through the bars, scaled
+    /// like `draw_smoothed`, so it can be told apart from the expected counts' half bars.
+    fn draw_fit(&self, frame: &mut [u8], metrics: &Metrics, drawn_total: u64) {
+        if !self.show_fit || self.mirrored || self.integer_render || metrics.total == 0 {
+            return;
+        }
+        let face_shares = metrics::normal_shares(
+            self.roll_counts.len(),
+            metrics.mean_roll(),
+            metrics.variance(),
+        );
+        let shares: Vec<f64> = (0..self.column_count())
+            .map(|column| {
+                self.column_faces(column)
+                    .map(|roll_key| face_shares[roll_key])
+                    .sum()
+            })
+            .collect();
+        self.draw_curve(frame, &shares, drawn_total, 1, self.theme.overdue);
+    }
+
+    /// Draw each ensemble run's share of each column as a faint line, and the average of them
+    /// all as a bold one, scaled like `draw_smoothed`.
+    fn draw_ensemble(&self, frame: &mut [u8], drawn_total: u64) {
+        let Some(ensemble) = self
+            .ensemble
+            .as_ref()
+            .filter(|_| !self.mirrored && !self.integer_render)
+        else {
+            return;
+        };
+        let columns = self.column_count();
+        let mut average = vec![0.; columns];
+        let mut runs = 0;
+        let faint = theme::blend(self.theme.background, self.theme.simulated, 0.35);
+        for counts in ensemble.runs() {
+            let column_counts = self.column_counts(counts);
+            let total = column_counts.iter().sum::<u64>().max(1) as f64;
+            let shares: Vec<f64> = column_counts
+                .iter()
+                .map(|&count| count as f64 / total)
+                .collect();
+            for (sum, share) in average.iter_mut().zip(&shares) {
+                *sum += share;
+            }
+            runs += 1;
+            self.draw_curve(frame, &shares, drawn_total, 1, faint);
+        }
+        for share in &mut average {
+            *share /= runs.max(1) as f64;
+        }
+        let thickness = self.label_scale.max(2);
+        self.draw_curve(
+            frame,
+            &average,
+            drawn_total,
+            thickness,
+            self.theme.simulated,
+        );
+    }
+
+    /// Draw a line through the middles of the columns at each's share of `drawn_total`, the
+    /// rolls the bars are drawn with.
+    fn draw_curve(
+        &self,
+        frame: &mut [u8],
+        shares: &[f64],
+        drawn_total: u64,
+        thickness: u32,
+        color: [u8; 4],
+    ) {
+        let bottom = (self.chart_top + self.chart_height - self.page_band_height()) as f64;
+        let y_of = |share: f64| {
+            // Bars fill a column one pixel per roll, a row at a time.
+            bottom - share * drawn_total as f64 / self.column_width.max(1) as f64
+        };
+        let half_column = self.column_width as f64 / 2.;
+        // Step a pixel at a time between the middles of neighboring columns, so steep stretches
+        // stay joined.
+        for (column, pair) in shares.windows(2).enumerate() {
+            let left = self.offset as f64 + column as f64 * self.column_width as f64 + half_column;
+            let (from, to) = (y_of(pair[0]), y_of(pair[1]));
+            let steps = (self.column_width as f64).max((to - from).abs()).ceil() as u32;
+            for step in 0..=steps {
+                let progress = step as f64 / steps.max(1) as f64;
+                let x = left + progress * self.column_width as f64;
+                let y = from + progress * (to - from);
+                text::fill_rect(
+                    frame,
+                    self.width,
+                    x.round() as i32 - thickness as i32 / 2,
+                    (y.round() as i32 - thickness as i32 / 2).max(self.chart_top as i32),
+                    thickness,
+                    thickness,
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Draw the histogram of each round's chi-square statistic in place of the faces, marking
+    /// how many rounds the chi-square distribution says each bucket should have.
+    fn draw_rounds(&self, frame: &mut [u8], rounds: &Rounds) {
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&self.theme.background);
+        }
+
+        let bar_width = self.width / rounds.buckets.len() as u32;
+        let left = (self.width - bar_width * rounds.buckets.len() as u32) as i32 / 2;
+        let bottom = (self.chart_top + self.chart_height) as i32;
+        let expected: Vec<f64> = (0..rounds.buckets.len())
+            .map(|bucket| rounds.expected(bucket))
+            .collect();
+        let tallest = rounds
+            .buckets
+            .iter()
+            .map(|&count| count as f64)
+            .chain(expected.iter().copied())
+            .fold(1., f64::max);
+        let height = |count: f64| (count / tallest * self.chart_height as f64).round() as u32;
+        let marker_height = self.label_scale.max(2);
+        for (bucket, &count) in rounds.buckets.iter().enumerate() {
+            let x = left + (bucket as u32 * bar_width) as i32;
+            let bar_height = height(count as f64);
+            text::fill_rect(
+                frame,
+                self.width,
+                x,
+                bottom - bar_height as i32,
+                bar_width.saturating_sub(1),
+                bar_height,
+                self.theme.bars[self.theme.bars.len() - 1],
+            );
+            let expected_y = bottom - height(expected[bucket]) as i32;
+            text::fill_rect(
+                frame,
+                self.width,
+                x,
+                expected_y - marker_height as i32 / 2,
+                bar_width.saturating_sub(1),
+                marker_height,
+                self.theme.expected,
+            );
+        }
+
+        let axis_y = bottom + 2 * self.label_scale as i32;
+        text::draw_text(
+            frame,
+            self.width,
+            left,
+            axis_y,
+            self.label_scale,
+            self.theme.text,
+            "0",
+        );
+        let end = format!("{}+", rounds.range() as u64 - 1);
+        text::draw_text(
+            frame,
+            self.width,
+            self.width as i32 - left - text::text_width(&end, self.label_scale) as i32,
+            axis_y,
+            self.label_scale,
+            self.theme.text,
+            &end,
+        );
+
+        let summary = format!(
+            "{} rounds of {} rolls, {} degrees of freedom",
+            rounds.completed, rounds.rolls, rounds.degrees_of_freedom
+        );
+        let lines = [
+            "Chi-square of each round",
+            summary.as_str(),
+            "Markers: chi-square distribution",
+        ];
+        let (box_width, _) = text::text_box_size(self.label_scale, &lines);
+        text::draw_text_box(
+            frame,
+            self.width,
+            (self.width as i32 - box_width as i32) / 2,
+            self.chart_top as i32,
+            self.label_scale,
+            self.theme.text,
+            self.theme.panel,
+            &lines,
+        );
+        self.draw_panels(frame, self.panels());
+        self.draw_title_bar(frame);
+    }
+
+    /// Draw a bar for each class of faces in place of the faces, marking how many rolls the
+    /// weights say each class should have.
+    fn draw_classes(&self, frame: &mut [u8], classing: Classing) {
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&self.theme.background);
+        }
+
+        let counts = self.tallies.get(classing);
+        let total = counts.iter().sum::<u64>() as f64;
+        let expected: Vec<f64> = classing
+            .expected_shares(&self.weights)
+            .iter()
+            .map(|share| share * total)
+            .collect();
+        let chart_width = self.chart_width();
+        let bar_width = chart_width / counts.len() as u32;
+        let left = (chart_width - bar_width * counts.len() as u32) as i32 / 2;
+        let bottom = (self.chart_top + self.chart_height) as i32;
+        let tallest = counts
+            .iter()
+            .map(|&count| count as f64)
+            .chain(expected.iter().copied())
+            .fold(1., f64::max);
+        let height = |count: f64| (count / tallest * self.chart_height as f64).round() as u32;
+        let marker_height = self.label_scale.max(2);
+        let labels = classing.labels();
+        for (class, &count) in counts.iter().enumerate() {
+            let x = left + (class as u32 * bar_width) as i32;
+            let bar_height = height(count as f64);
+            text::fill_rect(
+                frame,
+                self.width,
+                x,
+                bottom - bar_height as i32,
+                bar_width.saturating_sub(1),
+                bar_height,
+                self.theme.bars[self.theme.bars.len() - 1],
+            );
+            let expected_y = bottom - height(expected[class]) as i32;
+            text::fill_rect(
+                frame,
+                self.width,
+                x,
+                expected_y - marker_height as i32 / 2,
+                bar_width.saturating_sub(1),
+                marker_height,
+                self.theme.expected,
+            );
+            let label = format!("{}: {}", labels[class], self.format_count(count));
+            let label_width = text::text_width(&label, self.label_scale) as i32;
+            text::draw_text(
+                frame,
+                self.width,
+                x + (bar_width as i32 - label_width) / 2,
+                bottom + 2 * self.label_scale as i32,
+                self.label_scale,
+                self.theme.text,
+                &label,
+            );
+        }
+
+        let lines = [classing.name(), "Markers: expected from the weights"];
+        let (box_width, _) = text::text_box_size(self.label_scale, &lines);
+        text::draw_text_box(
+            frame,
+            self.width,
+            (chart_width as i32 - box_width as i32) / 2,
+            self.chart_top as i32,
+            self.label_scale,
+            self.theme.text,
+            self.theme.panel,
+            &lines,
+        );
+        self.draw_panels(frame, self.panels());
+        self.draw_title_bar(frame);
+    }
+
+    /// Draw each face's share of the last second's rolls, with a tick at its held peak and a
+    /// line across at a fair share.
+    fn draw_meter(&self, frame: &mut [u8], meter: &meter::Meter) {
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&self.theme.background);
+        }
+
+        let shares = meter.shares();
+        let chart_width = self.chart_width();
+        let bar_width = chart_width / shares.len() as u32;
+        let left = (chart_width - bar_width * shares.len() as u32) as i32 / 2;
+        let bottom = (self.chart_top + self.chart_height) as i32;
+        let full_scale = meter.full_scale();
+        let height = |share: f64| (share / full_scale * self.chart_height as f64).round() as u32;
+        let marker_height = self.label_scale.max(2);
+        for (roll_key, (&share, peak)) in shares.iter().zip(meter.peaks()).enumerate() {
+            let x = left + (roll_key as u32 * bar_width) as i32;
+            let bar_height = height(share);
+            text::fill_rect(
+                frame,
+                self.width,
+                x,
+                bottom - bar_height as i32,
+                bar_width.saturating_sub(1),
+                bar_height,
+                self.theme.bars[roll_key],
+            );
+            if peak > 0. {
+                text::fill_rect(
+                    frame,
+                    self.width,
+                    x,
+                    bottom - height(peak) as i32,
+                    bar_width.saturating_sub(1),
+                    marker_height,
+                    self.theme.text,
+                );
+            }
+            let label = &self.labels[roll_key];
+            let label_width = text::text_width(label, self.label_scale) as i32;
+            text::draw_text(
+                frame,
+                self.width,
+                x + (bar_width as i32 - label_width) / 2,
+                bottom + 2 * self.label_scale as i32,
+                self.label_scale,
+                self.theme.text,
+                label,
+            );
+        }
+        let fair_y = bottom - height(1. / shares.len() as f64) as i32;
+        text::fill_rect(
+            frame,
+            self.width,
+            left,
+            fair_y,
+            bar_width * shares.len() as u32,
+            1,
+            self.theme.expected,
+        );
+
+        let lines = ["Last second", "Ticks: recent peaks; line: a fair share"];
+        let (box_width, _) = text::text_box_size(self.label_scale, &lines);
+        text::draw_text_box(
+            frame,
+            self.width,
+            (chart_width as i32 - box_width as i32) / 2,
+            self.chart_top as i32,
+            self.label_scale,
+            self.theme.text,
+            self.theme.panel,
+            &lines,
+        );
+        self.draw_panels(frame, self.panels());
+        self.draw_title_bar(frame);
+    }
+
+    /// Draw each column's count less what's expected as a bar up or down from a midline, scaled
+    /// to the widest deviation instead of the counts, which dwarf it.
+    fn draw_deviations(&self, frame: &mut [u8]) {
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&self.theme.background);
+        }
+
+        let half_height = self.chart_height / 2;
+        let midline = (self.chart_top + half_height) as i32;
+        let scale = self.deviation_scale.max(1) as f64;
+        for (column, deviation) in self.column_deviations().into_iter().enumerate() {
+            let height = (deviation.unsigned_abs() as f64 / scale * half_height as f64)
+                .round()
+                .min(half_height as f64) as u32;
+            let (y, color) = if deviation >= 0 {
+                (midline - height as i32, self.theme.winner)
+            } else {
+                (midline, self.theme.loser)
+            };
+            text::fill_rect(
+                frame,
+                self.width,
+                (self.offset + column as u32 * self.column_width) as i32,
+                y,
+                self.column_width.saturating_sub(1),
+                height,
+                color,
+            );
+        }
+        text::fill_rect(
+            frame,
+            self.width,
+            self.offset as i32,
+            midline,
+            self.column_width * self.column_count() as u32,
+            1,
+            self.theme.expected,
+        );
+
+        let scale_line = format!(
+            "Top and bottom: {}",
+            self.format_count(self.deviation_scale)
+        );
+        let lines = ["Rolls above or below expected", scale_line.as_str()];
+        let (box_width, _) = text::text_box_size(self.label_scale, &lines);
+        text::draw_text_box(
+            frame,
+            self.width,
+            (self.chart_width() as i32 - box_width as i32) / 2,
+            self.chart_top as i32,
+            self.label_scale,
+            self.theme.text,
+            self.theme.panel,
+            &lines,
+        );
+        self.draw_labels(frame);
+        self.draw_panels(frame, self.panels());
+        self.draw_title_bar(frame);
+    }
+
+    /// Draw a grid with a row for each face and a column for the face rolled straight after it,
+    /// shaded by how much more or less often that happened than if rolls ignored the one before.
+    fn draw_transitions(&self, frame: &mut [u8]) {
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&self.theme.background);
+        }
+
+        let total_line = format!(
+            "Pairs of rolls: {}",
+            self.format_count(self.transitions.total())
+        );
+        let lines = [
+            "Rows: a face rolled; columns: the face rolled next",
+            "Winner's color: more often than chance; loser's: less",
+            total_line.as_str(),
+        ];
+        let (box_width, box_height) = text::text_box_size(self.label_scale, &lines);
+        let chart_width = self.chart_width();
+        let sides = self.transitions.sides() as u32;
+        let grid_height = self.chart_height.saturating_sub(box_height);
+        let cell = chart_width.min(grid_height) / sides;
+        let left = (chart_width - cell * sides) / 2;
+        let top = self.chart_top + box_height + (grid_height - cell * sides) / 2;
+        for from in 0..self.transitions.sides() {
+            for to in 0..self.transitions.sides() {
+                let color = match self.transitions.ratio(from, to) {
+                    Some(ratio) => {
+                        let off = ((ratio - 1.) / TRANSITION_FULL_SCALE).clamp(-1., 1.);
+                        if off >= 0. {
+                            theme::blend(self.theme.expected, self.theme.winner, off)
+                        } else {
+                            theme::blend(self.theme.expected, self.theme.loser, -off)
+                        }
+                    }
+                    None => self.theme.panel,
+                };
+                // A gap between cells keeps neighbors of the same shade apart.
+                text::fill_rect(
+                    frame,
+                    self.width,
+                    (left + to as u32 * cell) as i32,
+                    (top + from as u32 * cell) as i32,
+                    cell.saturating_sub(1),
+                    cell.saturating_sub(1),
+                    color,
+                );
+            }
+        }
+
+        text::draw_text_box(
+            frame,
+            self.width,
+            (chart_width as i32 - box_width as i32) / 2,
+            self.chart_top as i32,
+            self.label_scale,
+            self.theme.text,
+            self.theme.panel,
+            &lines,
+        );
+        self.draw_panels(frame, self.panels());
+        self.draw_title_bar(frame);
+    }
+
+    /// Draw each die's share of its rolls as a stepped line stretched across the whole chart,
+    /// so dice of different sizes line up, over a faint line at the `1/sides` a fair one hugs.
+    fn draw_overlay(&self, frame: &mut [u8], overlay: &Overlay) {
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&self.theme.background);
+        }
+
+        let palette = [
+            self.theme.bars[self.theme.bars.len() - 1],
+            self.theme.winner,
+            self.theme.loser,
+            self.theme.expected,
+            self.theme.overdue,
+            self.theme.simulated,
+        ];
+        let shares: Vec<Vec<f64>> = (0..overlay.dice.len())
+            .map(|index| overlay.shares(index))
+            .collect();
+        let smallest = overlay.dice.iter().map(Vec::len).min().unwrap_or(1);
+        // Leave headroom above the largest fair share so early wobbles stay on the chart.
+        let top_share = shares
+            .iter()
+            .flatten()
+            .copied()
+            .fold(1.5 / smallest as f64, f64::max);
+        let bottom = (self.chart_top + self.chart_height) as i32;
+        let y_of =
+            |share: f64| bottom - (share / top_share * self.chart_height as f64).round() as i32;
+        let thickness = self.label_scale.max(2);
+
+        for (index, shares) in shares.iter().enumerate() {
+            let color = palette[index % palette.len()];
+            text::fill_rect(
+                frame,
+                self.width,
+                0,
+                y_of(1. / shares.len() as f64),
+                self.width,
+                thickness / 2,
+                theme::blend(self.theme.background, color, 0.4),
+            );
+        }
+        for (index, shares) in shares.iter().enumerate() {
+            let color = palette[index % palette.len()];
+            let sides = shares.len() as u64;
+            let mut previous_y: Option<i32> = None;
+            for (face, &share) in shares.iter().enumerate() {
+                let left = (face as u64 * self.width as u64 / sides) as i32;
+                let right = ((face as u64 + 1) * self.width as u64 / sides) as i32;
+                let y = y_of(share) - thickness as i32 / 2;
+                text::fill_rect(
+                    frame,
+                    self.width,
+                    left,
+                    y,
+                    (right - left) as u32,
+                    thickness,
+                    color,
+                );
+                if let Some(previous_y) = previous_y {
+                    // Join the steps so each die reads as a single line.
+                    let top = previous_y.min(y);
+                    text::fill_rect(
+                        frame,
+                        self.width,
+                        left - thickness as i32 / 2,
+                        top,
+                        thickness,
+                        previous_y.abs_diff(y) + thickness,
+                        color,
+                    );
+                }
+                previous_y = Some(y);
+            }
+        }
+
+        // Beneath the status strip, one box per die so each can take its line's color.
+        let mut panels = self.panels();
+        let heading = format!("{} rolls of each die", self.format_count(overlay.rolls));
+        panels.push(self.text_panel(Anchor::TopLeft, 3, vec![heading], self.theme.text));
+        for (index, counts) in overlay.dice.iter().enumerate() {
+            let line = format!(
+                "d{}: fair at {:.2}%",
+                counts.len(),
+                100. / counts.len() as f64
+            );
+            let color = palette[index % palette.len()];
+            panels.push(self.text_panel(Anchor::TopLeft, 3, vec![line], color));
+        }
+        self.draw_panels(frame, panels);
+        self.draw_title_bar(frame);
+    }
+
+    /// Draw each die's counts as a small histogram of its own, in a grid filling the chart, with a
+    /// line across each at the count a fair die would give every face.
+    fn draw_dice_set(&self, frame: &mut [u8], overlay: &Overlay) {
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&self.theme.background);
+        }
+
+        let cells = overlay.dice.len().max(1) as u32;
+        let grid_columns = (cells as f64).sqrt().ceil() as u32;
+        let grid_rows = cells.div_ceil(grid_columns);
+        let cell_width = self.chart_width() / grid_columns;
+        let cell_height = self.chart_height / grid_rows;
+        let padding = 2 * self.label_scale;
+        let heading_height = text::text_height(self.label_scale) + 2 * padding;
+        for (index, counts) in overlay.dice.iter().enumerate() {
+            let cell_x = index as u32 % grid_columns * cell_width;
+            let cell_y = self.chart_top + index as u32 / grid_columns * cell_height;
+            let heading = format!("d{}", counts.len());
+            text::draw_text(
+                frame,
+                self.width,
+                (cell_x + padding) as i32,
+                (cell_y + padding) as i32,
+                self.label_scale,
+                self.theme.text,
+                &heading,
+            );
+
+            let plot_height = cell_height.saturating_sub(heading_height + padding);
+            let plot_width = cell_width.saturating_sub(2 * padding);
+            let bottom = (cell_y + heading_height + plot_height) as i32;
+            let fair_count = overlay.rolls as f64 / counts.len() as f64;
+            // Leave headroom above a fair count so early wobbles stay in the cell.
+            let tallest = counts
+                .iter()
+                .map(|&count| count as f64)
+                .fold(1.5 * fair_count, f64::max)
+                .max(1.);
+            let height = |count: f64| (count / tallest * plot_height as f64).round() as u32;
+            let bar_width = plot_width / counts.len() as u32;
+            for (roll_key, &count) in counts.iter().enumerate() {
+                let bar_height = height(count as f64);
+                // Spread each die's faces over the same ramp of colors the d20 uses.
+                let color = ((roll_key + 1) * self.theme.bars.len() / counts.len()).max(1) - 1;
+                text::fill_rect(
+                    frame,
+                    self.width,
+                    (cell_x + padding + roll_key as u32 * bar_width) as i32,
+                    bottom - bar_height as i32,
+                    bar_width.saturating_sub(1),
+                    bar_height,
+                    self.theme.bars[color],
+                );
+            }
+            text::fill_rect(
+                frame,
+                self.width,
+                (cell_x + padding) as i32,
+                bottom - height(fair_count) as i32,
+                bar_width * counts.len() as u32,
+                1,
+                self.theme.expected,
+            );
+        }
+
+        let mut panels = self.panels();
+        let heading = format!("{} rolls of each die", self.format_count(overlay.rolls));
+        panels.push(self.text_panel(Anchor::TopRight, 3, vec![heading], self.theme.text));
+        self.draw_panels(frame, panels);
+        self.draw_title_bar(frame);
+    }
+
+    /// Draw each column's label beneath it, followed by how many rolls it has been since any of
+    /// its faces last came up, highlighting the column with the most overdue face.
+    fn draw_labels(&self, frame: &mut [u8]) {
+        if self.tier >= Tier::NoLabels {
+            return;
+        }
+        let line_height = text::text_height(self.label_scale) + 2 * self.label_scale;
+        let below_chart = self.chart_top + self.chart_height + 2 * self.label_scale;
+        let name_y = if self.mirrored {
+            // Along the midline, on a strip of its own so the names stand out from the bars.
+            let midline_y = self.chart_top + self.live_height() - self.live_height() / 2;
+            let strip_top = midline_y.saturating_sub(line_height / 2);
+            text::fill_rect(
+                frame,
+                self.width,
+                self.offset as i32,
+                strip_top as i32,
+                self.column_width * self.column_count() as u32,
+                line_height,
+                self.theme.panel,
+            );
+            strip_top + self.label_scale
+        } else {
+            below_chart
+        };
+        let staleness_y = below_chart + if self.mirrored { 0 } else { line_height };
+        for column in 0..self.column_count() {
+            let label = self.column_label(column);
+            let name = text::truncate_to_width(&label, self.label_scale, self.column_width);
+            self.draw_column_label(frame, column, name_y, self.theme.text, &name);
+
+            let staleness = self.format_count(self.column_rolls_since_seen(column));
+            let overdue = self
+                .overdue_roll_key
+                .is_some_and(|roll_key| self.column_faces(column).contains(&roll_key));
+            let color = if overdue {
+                self.theme.overdue
+            } else {
+                self.theme.text
+            };
+            self.draw_column_label(frame, column, staleness_y, color, &staleness);
+        }
+    }
+
+    /// Draw the running statistics down the panel on the right of the chart, if it's shown.
+    fn draw_stats_panel(&self, frame: &mut [u8]) {
+        let panel_width = self.stats_panel_width();
+        if panel_width == 0 {
+            return;
+        }
+        let x = self.chart_width();
+        text::fill_rect(
+            frame,
+            self.width,
+            x as i32,
+            self.chart_top as i32,
+            panel_width,
+            self.height - self.chart_top,
+            self.theme.panel,
+        );
+
+        let metrics = &self.metrics;
+        let elapsed = self.run_started.elapsed().as_secs();
+        let mut lines = vec![format!("Total: {}", self.format_count(metrics.total))];
+        if metrics.total > 0 {
+            lines.push(format!("Mean: {:.3}", metrics.mean_face(self.first_face)));
+            lines.push(format!("Variance: {:.3}", metrics.variance()));
+            lines.push(format!("Chi-square: {:.2}", metrics.chi_square()));
+            lines.push(format!(
+                "Entropy: {:.3} of {:.3} bits",
+                self.entropy(),
+                self.max_entropy()
+            ));
+            lines.push(format!("Verdict: {}", summary::verdict(metrics)));
+        }
+        lines.push(format!(
+            "Dropped: {}, invalid: {}",
+            self.accounting.dropped, self.accounting.invalid
+        ));
+        if self.saturated {
+            lines.push("Counts stopped at their limit".to_string());
+        }
+        lines.push(format!(
+            "Elapsed: {}:{:02}:{:02}",
+            elapsed / 3600,
+            elapsed / 60 % 60,
+            elapsed % 60
+        ));
+
+        let scale = self.label_scale;
+        let padding = 2 * scale;
+        let line_height = text::text_height(scale) + 2 * scale;
+        for (index, line) in lines.iter().enumerate() {
+            let line =
+                text::truncate_to_width(line, scale, panel_width.saturating_sub(2 * padding));
+            text::draw_text(
+                frame,
+                self.width,
+                (x + padding) as i32,
+                (self.chart_top + padding + index as u32 * line_height) as i32,
+                scale,
+                self.theme.text,
+                &line,
+            );
+        }
+    }
+
+    /// Draw a swatch of each face's bar color beside its label, in a grid beneath the labels if
+    /// the legend has room there, or else laid translucently over the bottom of the chart.
+    fn draw_legend(&self, frame: &mut [u8]) {
+        if !self.show_legend || self.tier == Tier::BarsOnly {
+            return;
+        }
+        let layout = self.legend_layout();
+        let scale = self.label_scale;
+        let padding = 2 * scale;
+        let top = if self.legend_height > 0 {
+            let top = self.height - self.heat_strip_height - self.legend_height;
+            text::fill_rect(
+                frame,
+                self.width,
+                0,
+                top as i32,
+                self.width,
+                layout.height,
+                self.theme.panel,
+            );
+            top
+        } else {
+            let top = (self.chart_top + self.chart_height).saturating_sub(layout.height);
+            let rows = top as usize..(top + layout.height).min(self.height) as usize;
+            let row_bytes = self.width as usize * 4;
+            for pixel in frame[rows.start * row_bytes..rows.end * row_bytes].chunks_exact_mut(4) {
+                let previous = [pixel[0], pixel[1], pixel[2], pixel[3]];
+                pixel.copy_from_slice(&theme::blend(previous, self.theme.panel, 0.75));
+            }
+            top
+        };
+
+        let swatch = text::text_height(scale);
+        for (roll_key, label) in self.labels.iter().enumerate() {
+            let column = roll_key as u32 % layout.columns;
+            let row = roll_key as u32 / layout.columns;
+            let x = (padding + column * layout.entry_width) as i32;
+            let y = (top + padding + row * layout.entry_height) as i32;
+            text::fill_rect(
+                frame,
+                self.width,
+                x,
+                y,
+                swatch,
+                swatch,
+                self.theme.bars[roll_key],
+            );
+            let label = text::truncate_to_width(label, scale, layout.label_width);
+            text::draw_text(
+                frame,
+                self.width,
+                x + (swatch + padding) as i32,
+                y,
+                scale,
+                self.theme.text,
+                &label,
+            );
+        }
+    }
+
+    /// Draw the heat strip along the bottom of the window, one pixel column per slice of time
+    /// with the newest on the right, each in the color of the face rolled most during it.
+    fn draw_heat_strip(&self, frame: &mut [u8]) {
+        if self.heat_strip_height == 0 {
+            return;
+        }
+        let y = (self.height - self.heat_strip_height) as i32;
+        for x in 0..self.width {
+            let age = (self.width - 1 - x) as usize;
+            let color = match self.slices.get(age).and_then(|slice| slice.top) {
+                Some(top) => self.theme.bars[top],
+                None => self.theme.panel,
+            };
+            text::fill_rect(
+                frame,
+                self.width,
+                x as i32,
+                y,
+                1,
+                self.heat_strip_height,
+                color,
+            );
+        }
+        for note in &self.notes {
+            let age = (note.at.elapsed().as_secs_f64() / heat::SLICE_LENGTH.as_secs_f64()) as u32;
+            let Some(x) = self.width.saturating_sub(1).checked_sub(age) else {
+                continue;
+            };
+            text::fill_rect(
+                frame,
+                self.width,
+                x as i32,
+                y,
+                self.label_scale,
+                self.heat_strip_height,
+                self.theme.text,
+            );
+        }
+    }
+
+    /// Draw `label` centered beneath `column`.
+    fn draw_column_label(
+        &self,
+        frame: &mut [u8],
+        column: usize,
+        y: u32,
+        color: [u8; 4],
+        label: &str,
+    ) {
+        let label_width = text::text_width(label, self.label_scale);
+        let column_x = self.offset + column as u32 * self.column_width;
+        let label_x = column_x as i32 + (self.column_width as i32 - label_width as i32) / 2;
+        text::draw_text(
+            frame,
+            self.width,
+            label_x,
+            y as i32,
+            self.label_scale,
+            color,
+            label,
+        );
+    }
+
+    /// When hovering over a label, explain what it means (and what it doesn't).
+    fn draw_tooltip(&self, frame: &mut [u8]) {
+        let Some((cursor_x, cursor_y)) =
+            self.cursor_position.filter(|_| self.tier < Tier::BarsOnly)
+        else {
+            return;
+        };
+        let labels_top = self.chart_top + self.chart_height;
+        if cursor_y < labels_top || self.heat_strip_hit().is_some() {
+            return;
+        }
+        let Some(column) = self.hovered_column() else {
+            return;
+        };
+
+        let summary = format!(
+            "{} last rolled {} rolls ago",
+            self.column_label(column),
+            self.format_count(self.column_rolls_since_seen(column))
+        );
+        let chance = format!(
+            "the next roll is still {} in {}",
+            self.column_faces(column).count(),
+            self.roll_counts.len()
+        );
+        let lines = [summary.as_str(), "Dice have no memory:", chance.as_str()];
+
+        let (box_width, box_height) = text::text_box_size(self.label_scale, &lines);
+        let box_x = (cursor_x as i32)
+            .min(self.chart_width() as i32 - box_width as i32)
+            .max(0);
+        let box_y = labels_top as i32 - box_height as i32;
+        text::draw_text_box(
+            frame,
+            self.width,
+            box_x,
+            box_y,
+            self.label_scale,
+            self.theme.text,
+            self.theme.panel,
+            &lines,
+        );
+    }
+
+    /// Mark the top of each column whose fitted `counts` run past the top of the chart, and the
+    /// bottom too when mirrored, since the rest of the bar can't be seen.
+    fn draw_clipped(&self, frame: &mut [u8], counts: &[u64]) {
+        let max_allowed = self.column_width as u64 * self.live_height() as u64;
+        let thickness = 2 * self.label_scale;
+        for (column, &count) in counts.iter().enumerate() {
+            if count <= max_allowed {
+                continue;
+            }
+            let x = (self.offset + column as u32 * self.column_width) as i32;
+            let mut edges = vec![self.chart_top];
+            if self.mirrored {
+                edges.push(self.chart_top + self.live_height() - thickness);
+            }
+            for y in edges {
+                text::fill_rect(
+                    frame,
+                    self.width,
+                    x,
+                    y as i32,
+                    self.column_width,
+                    thickness,
+                    self.theme.alarm,
+                );
+            }
+        }
+    }
+
+    /// Draw a faint line across the chart at the cursor, with the count a bar reaching it would
+    /// have beside the cursor, on whichever side has room.
+    fn draw_crosshair(&self, frame: &mut [u8]) {
+        let Some((cursor_x, cursor_y)) =
+            self.cursor_position.filter(|_| self.tier < Tier::BarsOnly)
+        else {
+            return;
+        };
+        let chart_width = self.chart_width();
+        let chart_bottom = self.chart_top + self.chart_height;
+        if self.menu.is_some() || cursor_x >= chart_width || cursor_y < self.chart_top {
+            return;
+        }
+        // Rows above the pages set aside along the bottom, counted up from the bottom or out
+        // from the midline.
+        let Some(y) = (chart_bottom - 1)
+            .checked_sub(cursor_y)
+            .and_then(|y| y.checked_sub(self.page_band_height()))
+        else {
+            return;
+        };
+        let rows = if self.mirrored {
+            let midline = self.live_height() / 2;
+            y.abs_diff(midline)
+        } else {
+            y + 1
+        };
+
+        text::fill_rect(
+            frame,
+            self.width,
+            0,
+            cursor_y as i32,
+            chart_width,
+            1,
+            theme::blend(self.theme.background, self.theme.text, 0.3),
+        );
+        let readout = format!("~{}", self.format_count(self.rolls_at_height(rows)));
+        let readout_width = text::text_width(&readout, self.label_scale);
+        let gap = 3 * self.label_scale;
+        let x = if cursor_x + gap + readout_width <= chart_width {
+            cursor_x + gap
+        } else {
+            cursor_x.saturating_sub(gap + readout_width)
+        };
+        let text_height = text::text_height(self.label_scale);
+        let y = if cursor_y >= self.chart_top + gap + text_height {
+            cursor_y - gap - text_height
+        } else {
+            cursor_y + gap
+        };
+        text::draw_text(
+            frame,
+            self.width,
+            x as i32,
+            y as i32,
+            self.label_scale,
+            self.theme.text,
+            &readout,
+        );
+    }
+
+    /// How the current bet is going, in the bottom-left corner.
+    fn bet_panel(&self) -> Option<Panel<'_>> {
+        let bet = self.bet.as_ref()?;
+        let lines = bet.lines(
+            &self.labels[bet.roll_key],
+            self.roll_counts.len(),
+            self.bankroll,
+        );
+        Some(self.text_panel(Anchor::BottomLeft, 2, lines, self.theme.text))
+    }
+
+    /// What the filled and outlined bars mean when comparing against a fair simulation, and how
+    /// likely the two are to come from the same distribution, beneath the status strip.
+    fn paired_legend_panel(&self) -> Option<Panel<'_>> {
+        let simulated = self.simulated.as_ref()?;
+        let (chi_square, degrees_of_freedom) =
+            metrics::two_sample_chi_square(&self.face_totals, simulated);
+        let p_value = metrics::chi_square_p_value(chi_square, degrees_of_freedom);
+        let lines = vec![
+            format!(
+                "Filled: your die, {} rolls",
+                self.format_count(self.total_rolls)
+            ),
+            format!(
+                "Outlined: fair die, {} rolls",
+                self.format_count(simulated.iter().sum())
+            ),
+            format!("Two-sample chi-square {chi_square:.2}, p-value {p_value:.4}"),
+        ];
+        Some(self.text_panel(Anchor::TopLeft, 3, lines, self.theme.text))
+    }
+
+    /// Draw a divider on the left edge of the DC's bar, with the chances of meeting it beside
+    /// the divider.
+    fn draw_dc(&self, frame: &mut [u8], metrics: &Metrics) {
+        if !self.show_dc || self.tier == Tier::BarsOnly {
+            return;
+        }
+        let column = (0..self.column_count())
+            .find(|&column| self.column_faces(column).contains(&self.dc_roll_key()))
+            .unwrap_or(0);
+        let x = (self.offset + column as u32 * self.column_width) as i32;
+        let thickness = self.label_scale;
+        text::fill_rect(
+            frame,
+            self.width,
+            x - thickness as i32 / 2,
+            self.chart_top as i32,
+            thickness,
+            self.chart_height,
+            self.theme.text,
+        );
+
+        let lines = self.dc_lines(metrics);
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let (_, status_height) = text::text_box_size(self.label_scale, &[""]);
+        let (box_width, _) = text::text_box_size(self.label_scale, &lines);
+        let box_x = (x + thickness as i32).min(self.chart_width() as i32 - box_width as i32);
+        text::draw_text_box(
+            frame,
+            self.width,
+            box_x.max(0),
+            (self.chart_top + 2 * status_height) as i32,
+            self.label_scale,
+            self.theme.text,
+            self.theme.panel,
+            &lines,
+        );
+    }
+
+    /// The snapshot name prompt or current status message, if any, the roll source if it isn't
+    /// the built-in generator, and how many faces are hidden, in the top-left corner.
+    fn status_panel(&self) -> Option<Panel<'_>> {
+        let line = if let Some(name) = &self.snapshot_name {
+            Some(format!("Snapshot name: {name}_"))
+        } else if let Some(entry) = &self.bet_entry {
+            Some(format!(
+                "Bet on face ({}-{}): {entry}_",
+                self.first_face,
+                self.last_face()
+            ))
+        } else if let Some(entry) = &self.note_entry {
+            Some(format!("Note: {entry}_"))
+        } else {
+            self.status.as_ref().map(|status| status.text.clone())
+        };
+        let source = self
+            .source
+            .as_ref()
+            .map(|source| format!("Source: {}", source.name()));
+        let hue = self
+            .bar_hue
+            .map(|hue| format!("hue {}°", hue.round() as u32 % 360));
+        let lines: Vec<String> = line
+            .into_iter()
+            .chain(source)
+            .chain(hue)
+            .chain(self.hidden_line())
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        Some(self.text_panel(Anchor::TopLeft, 4, lines, self.theme.text))
+    }
+
+    /// What the tour is showing, above everything else in the top-left corner.
+    fn demo_panel(&self) -> Option<Panel<'_>> {
+        let caption = self.demo.as_ref()?.current().caption;
+        Some(self.text_panel(
+            Anchor::TopLeft,
+            5,
+            vec![caption.to_string()],
+            self.theme.text,
+        ))
+    }
+
+    /// Draw the chart off screen with the final statistics laid over it, leaving out anything
+    /// that only makes sense interactively.
+    fn render_summary(&mut self) -> Vec<u8> {
+        // Set the interactive parts aside rather than clearing them, since the window may stay
+        // open afterward.
+        let cursor_position = self.cursor_position.take();
+        let status = self.status.take();
+        let snapshot_name = self.snapshot_name.take();
+        let bet_entry = self.bet_entry.take();
+        let note_entry = self.note_entry.take();
+        let menu = self.menu.take();
+        // Start from the background so trails have nothing to fade from.
+        let mut frame = self
+            .theme
+            .background
+            .repeat(self.width as usize * self.height as usize);
+        self.draw(&mut frame);
+        self.cursor_position = cursor_position;
+        self.status = status;
+        self.snapshot_name = snapshot_name;
+        self.bet_entry = bet_entry;
+        self.note_entry = note_entry;
+        self.menu = menu;
+
+        // Once the budget is spent, the chart already carries the statistics.
+        if !self.budget.as_ref().is_some_and(Budget::is_spent) {
+            self.draw_summary(&mut frame);
+        }
+        frame
+    }
+
+    /// Draw the final statistics centered across the top of the chart.
+    fn draw_summary(&self, frame: &mut [u8]) {
+        let mut lines = summary::lines(&self.metrics, self.first_face);
+        lines.extend(self.convergence_line());
+        if let Some(budget) = &self.budget {
+            lines.push(format!(
+                "Budget: {} of {} rolls",
+                budget.spent, budget.total
+            ));
+        }
+        if self.show_dc {
+            lines.extend(self.dc_lines(&self.metrics));
+        }
+        lines.push(format!("Roll checksum: {}", self.roll_hash));
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let scale = self.label_scale;
+        let (box_width, _) = text::text_box_size(scale, &lines);
+        text::draw_text_box(
+            frame,
+            self.width,
+            (self.chart_width() as i32 - box_width as i32) / 2,
+            self.chart_top as i32,
+            scale,
+            self.theme.text,
+            self.theme.panel,
+            &lines,
+        );
+    }
+
+    /// Draw how much of the roll budget is left as a bar across the top of the chart, and the
+    /// summary once it's all spent.
+    fn draw_budget(&self, frame: &mut [u8]) {
+        let Some(budget) = self.budget.as_ref().filter(|_| self.tier < Tier::BarsOnly) else {
+            return;
+        };
+        let height = 2 * self.label_scale;
+        text::fill_rect(
+            frame,
+            self.width,
+            0,
+            self.chart_top as i32,
+            self.width,
+            height,
+            self.theme.panel,
+        );
+        let left = budget.total - budget.spent;
+        text::fill_rect(
+            frame,
+            self.width,
+            0,
+            self.chart_top as i32,
+            (self.width as u64 * left / budget.total.max(1)) as u32,
+            height,
+            self.theme.expected,
+        );
+        if budget.is_spent() {
+            self.draw_summary(frame);
+        }
+    }
+
+    /// The current simulation speed in the top-right corner, along with the ramp's rate and
+    /// the detection countdown if they're shown.
+    fn speed_panel(&self) -> Panel<'_> {
+        let lines = std::iter::once(format!("{}x speed", self.speed_multiplier()))
+            .chain(self.ramp.as_ref().map(ramp_line))
+            .chain(self.show_detection.then(|| self.detection_line()))
+            .collect();
+        self.text_panel(Anchor::TopRight, 4, lines, self.theme.text)
+    }
+
+    /// The normal distribution fitted to the counts beside the one the weights give, and how far
+    /// the counts' cumulative shares stray from the weights', in the top-right corner.
+    fn fit_panel(&self) -> Option<Panel<'_>> {
+        if !self.show_fit || self.metrics.total == 0 {
+            return None;
+        }
+        let (mean, variance) = metrics::weighted_moments(&self.weights);
+        let mean = mean + self.first_face as f64 - 1.;
+        let lines = vec![
+            format!(
+                "Fitted: mean {:.3}, sd {:.3}",
+                self.metrics.mean_face(self.first_face),
+                self.metrics.variance().sqrt()
+            ),
+            format!("Theory: mean {mean:.3}, sd {:.3}", variance.sqrt()),
+            format!(
+                "KS distance {:.4}",
+                metrics::ks_distance(&self.face_totals, &self.weights)
+            ),
+        ];
+        Some(self.text_panel(Anchor::TopRight, 3, lines, self.theme.text))
+    }
+
+    /// How many more rolls the configured bias should take to show up in the chi-square test.
+    fn detection_line(&self) -> String {
+        match metrics::rolls_to_detect(&self.weights) {
+            None => "Fair weights: no bias to detect".to_string(),
+            Some(rolls) => match (rolls.ceil() as u64).checked_sub(self.total_rolls) {
+                Some(remaining) if remaining > 0 => {
+                    format!("Bias should be detectable in ~{remaining} more rolls")
+                }
+                _ => "Bias should be detectable by now".to_string(),
+            },
+        }
+    }
+
+    /// The chi-square test's p-value beneath the speed, as a number and as a bar that shrinks
+    /// toward nothing as the counts look less and less like a fair die's.
+    fn p_value_panel(&self) -> Option<Panel<'_>> {
+        if !self.show_p_value {
+            return None;
+        }
+        let p_value = self.metrics.p_value();
+        let line = format!("p-value {p_value:.4}");
+        let scale = self.label_scale;
+        let padding = 2 * scale;
+        let (text_width, text_height) = text::text_box_size(scale, &[line.as_str()]);
+        let bar_height = 2 * scale;
+        let request = Request {
+            anchor: Anchor::TopRight,
+            width: text_width,
+            height: text_height + bar_height + padding,
+            priority: 3,
+        };
+        let draw = move |frame: &mut [u8], rect: Rect| {
+            text::fill_rect(
+                frame,
+                self.width,
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                self.theme.panel,
+            );
+            text::draw_text_box(
+                frame,
+                self.width,
+                rect.x,
+                rect.y,
+                scale,
+                self.theme.text,
+                self.theme.panel,
+                &[line.as_str()],
+            );
+            let track_width = text_width - 2 * padding;
+            let bar_y = rect.y + text_height as i32;
+            text::fill_rect(
+                frame,
+                self.width,
+                rect.x + padding as i32,
+                bar_y,
+                track_width,
+                bar_height,
+                self.theme.background,
+            );
+            // Below 5%, the usual cutoff for calling a die unfair.
+            let color = if p_value < 0.05 {
+                self.theme.loser
+            } else {
+                self.theme.winner
+            };
+            text::fill_rect(
+                frame,
+                self.width,
+                rect.x + padding as i32,
+                bar_y,
+                (track_width as f64 * p_value).round() as u32,
+                bar_height,
+                color,
+            );
+        };
+        Some(Panel {
+            request,
+            draw: Box::new(draw),
+        })
+    }
+
+    /// A small histogram of the time between externally sourced rolls, in the bottom-right
+    /// corner.
+    fn arrival_gaps_panel(&self) -> Option<Panel<'_>> {
+        let histogram = &self.arrival_gaps;
+        let external = self
+            .source
+            .as_ref()
+            .is_some_and(|source| source.is_external());
+        if !external {
+            return None;
+        }
+        let p50 = histogram.quantile(0.5)?;
+        let p95 = histogram.quantile(0.95)?;
+
+        let scale = self.label_scale;
+        let padding = 2 * scale;
+        let line_height = text::text_height(scale) + 2 * scale;
+        let bar_width = scale.max(2);
+        let chart_width = bar_width * histogram.buckets().len() as u32;
+        let chart_height = (self.chart_height / 6).max(line_height);
+        let quantiles = format!(
+            "p50 {} p95 {}",
+            histogram::format_duration(p50),
+            histogram::format_duration(p95)
+        );
+        let (text_width, text_height) =
+            text::text_box_size(scale, &["Time between rolls", quantiles.as_str()]);
+        let request = Request {
+            anchor: Anchor::BottomRight,
+            width: text_width.max(chart_width + 2 * padding),
+            height: text_height + chart_height + line_height + padding,
+            priority: 2,
+        };
+        let draw = move |frame: &mut [u8], rect: Rect| {
+            text::fill_rect(
+                frame,
+                self.width,
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                self.theme.panel,
+            );
+            text::draw_text_box(
+                frame,
+                self.width,
+                rect.x,
+                rect.y,
+                scale,
+                self.theme.text,
+                self.theme.panel,
+                &["Time between rolls", quantiles.as_str()],
+            );
+
+            let chart_x = rect.x + padding as i32;
+            let chart_bottom = rect.y + (text_height + chart_height) as i32;
+            let tallest = histogram
+                .buckets()
+                .iter()
+                .copied()
+                .max()
+                .unwrap_or(0)
+                .max(1);
+            for (index, &count) in histogram.buckets().iter().enumerate() {
+                let bar_height = (count * chart_height as u64 / tallest) as u32;
+                text::fill_rect(
+                    frame,
+                    self.width,
+                    chart_x + (index as u32 * bar_width) as i32,
+                    chart_bottom - bar_height as i32,
+                    bar_width - 1,
+                    bar_height,
+                    self.theme.bars[self.theme.bars.len() - 1],
+                );
+            }
+            let axis_y = chart_bottom + scale as i32;
+            text::draw_text(
+                frame,
+                self.width,
+                chart_x,
+                axis_y,
+                scale,
+                self.theme.text,
+                "1ms",
+            );
+            text::draw_text(
+                frame,
+                self.width,
+                chart_x + chart_width as i32 - text::text_width("60s", scale) as i32,
+                axis_y,
+                scale,
+                self.theme.text,
+                "60s",
+            );
+        };
+        Some(Panel {
+            request,
+            draw: Box::new(draw),
+        })
+    }
+
+    /// A box of `lines` in `color` for the compositor to place at `anchor`.
+    fn text_panel(
+        &self,
+        anchor: Anchor,
+        priority: u8,
+        lines: Vec<String>,
+        color: [u8; 4],
+    ) -> Panel<'_> {
+        let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let (width, height) = text::text_box_size(self.label_scale, &refs);
+        let draw = move |frame: &mut [u8], rect: Rect| {
+            let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+            text::draw_text_box(
+                frame,
+                self.width,
+                rect.x,
+                rect.y,
+                self.label_scale,
+                color,
+                self.theme.panel,
+                &lines,
+            );
+        };
+        Panel {
+            request: Request {
+                anchor,
+                width,
+                height,
+                priority,
+            },
+            draw: Box::new(draw),
+        }
+    }
+
+    /// How many pages have been turned, in the bottom-left corner over the band showing them.
+    fn pages_panel(&self) -> Option<Panel<'_>> {
+        let pages = self.pages.as_ref().filter(|pages| pages.pages > 0)?;
+        let line = format!("×{}", pages.pages);
+        Some(self.text_panel(Anchor::BottomLeft, 3, vec![line], self.theme.text))
+    }
+
+    /// The most recent notes, with the time of day and rolls so far when each was made, in the
+    /// bottom-left corner.
+    fn notes_panel(&self) -> Option<Panel<'_>> {
+        if self.notes.is_empty() {
+            return None;
+        }
+        let first = self.notes.len().saturating_sub(NOTES_SHOWN);
+        let lines = self.notes[first..]
+            .iter()
+            .map(|note| {
+                let seconds = note.unix_seconds % 86400;
+                format!(
+                    "{:02}:{:02}:{:02} at {} rolls: {}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60,
+                    self.format_count(note.total_rolls),
+                    note.text
+                )
+            })
+            .collect();
+        Some(self.text_panel(Anchor::BottomLeft, 1, lines, self.theme.text))
+    }
+
+    /// The overlays that can appear in any view, each wanting a corner of the chart.
+    fn panels(&self) -> Vec<Panel<'_>> {
+        [
+            self.demo_panel(),
+            self.status_panel().filter(|_| self.tier < Tier::NoStatus),
+            self.pages_panel(),
+            self.notes_panel(),
+            self.paired_legend_panel(),
+            Some(self.speed_panel()),
+            self.p_value_panel(),
+            self.bet_panel(),
+            self.arrival_gaps_panel(),
+            self.fit_panel(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Lay `panels` out over the chart so none covers another, then draw them, lowest priority
+    /// first.
+    fn draw_panels(&self, frame: &mut [u8], mut panels: Vec<Panel<'_>>) {
+        if self.tier == Tier::BarsOnly {
+            return;
+        }
+        panels.sort_by_key(|panel| panel.request.priority);
+        let area = Rect {
+            x: 0,
+            y: self.chart_top as i32,
+            width: self.chart_width(),
+            height: self.chart_height,
+        };
+        let requests: Vec<Request> = panels.iter().map(|panel| panel.request).collect();
+        for (panel, rect) in panels.iter().zip(compositor::layout(area, &requests)) {
+            if let Some(rect) = rect {
+                (panel.draw)(frame, rect);
+            }
+        }
+    }
+
+    /// Outline the chart in the winner's color once the counts have converged, blinking at first
+    /// to catch the eye.
+    fn draw_convergence_border(&self, frame: &mut [u8]) {
+        let Some(converged) = &self.converged else {
+            return;
+        };
+        let since = converged.at.elapsed();
+        if since < CONVERGENCE_BLINK && since.as_millis() / 250 % 2 == 1 {
+            return;
+        }
+        let thickness = 2 * self.label_scale;
+        let top = self.chart_top as i32;
+        let height = self.height.saturating_sub(self.chart_top);
+        let color = self.theme.winner;
+        text::fill_rect(frame, self.width, 0, top, self.width, thickness, color);
+        let bottom = (self.height - thickness.min(self.height)) as i32;
+        text::fill_rect(frame, self.width, 0, bottom, self.width, thickness, color);
+        text::fill_rect(frame, self.width, 0, top, thickness, height, color);
+        let right = (self.width - thickness.min(self.width)) as i32;
+        text::fill_rect(frame, self.width, right, top, thickness, height, color);
+    }
+
+    /// Draw the client-side title bar with the window title and a close button.
+    fn draw_title_bar(&self, frame: &mut [u8]) {
+        if !self.title_bar {
+            return;
+        }
+        let height = self.chart_top;
+        let padding = 2 * self.label_scale as i32;
+        text::fill_rect(
+            frame,
+            self.width,
+            0,
+            0,
+            self.width,
+            height,
+            self.theme.panel,
+        );
+        text::draw_text(
+            frame,
+            self.width,
+            padding,
+            padding,
+            self.label_scale,
+            self.theme.text,
+            self.title.title(),
+        );
+        let close_x = self.width as i32 - height as i32;
+        text::fill_rect(
+            frame,
+            self.width,
+            close_x,
+            0,
+            height,
+            height,
+            self.theme.alarm,
+        );
+        let glyph_x =
+            close_x + (height as i32 - text::text_width("x", self.label_scale) as i32) / 2;
+        text::draw_text(
+            frame,
+            self.width,
+            glyph_x,
+            padding,
+            self.label_scale,
+            self.theme.text,
+            "x",
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_since_seen_counts_from_the_last_roll() {
+        let mut world = World::new(0, 0);
+        world.record_roll(3);
+        for _ in 0..9 {
+            world.record_roll(0);
+        }
+        assert_eq!(world.rolls_since_seen(3), 9);
+        assert_eq!(world.rolls_since_seen(0), 0);
+        // Never rolled, so it's been every roll.
+        assert_eq!(world.rolls_since_seen(5), 10);
+    }
+
+    #[test]
+    fn recorded_frames_match_what_was_drawn() {
+        let mut world = World::new(200, 100);
+        for (roll_key, count) in [(0, 3), (4, 10), (19, 6)] {
+            for _ in 0..count {
+                world.record_roll(roll_key);
+            }
+        }
+        world.update_statistics();
+        let mut frame = vec![0; 200 * 100 * 4];
+        world.draw(&mut frame);
+        // Only the tallest bar, face 5's, is in the winner's color.
+        let winner_rows = |column: usize| {
+            let x = column * world.column_width as usize + world.column_width as usize / 2;
+            (0..100)
+                .filter(|y| frame[(y * 200 + x) * 4..][..4] == world.theme.winner)
+                .count()
+        };
+        assert!(winner_rows(4) > 0);
+        assert_eq!(winner_rows(0), 0);
+        assert_eq!(winner_rows(19), 0);
+
+        let dir = std::env::temp_dir().join(format!("d20-record-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut recorder = record::Recorder::spawn(&dir).unwrap();
+        recorder.capture(&frame, world.width, world.height);
+        recorder.finish();
+
+        let file = std::fs::File::open(dir.join("frame-000000.png")).unwrap();
+        let mut reader = png::Decoder::new(std::io::BufReader::new(file))
+            .read_info()
+            .unwrap();
+        let mut recorded = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut recorded).unwrap();
+        assert_eq!((info.width, info.height), (200, 100));
+        assert!(
+            recorded == frame,
+            "recorded a different frame from the one drawn"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn switching_sources_keeps_the_old_ones_rolls() {
+        /// A source holding rolls that arrived but haven't been polled yet.
+        struct Pending(Vec<usize>);
+
+        impl RollSource for Pending {
+            fn name(&self) -> &'static str {
+                "pending"
+            }
+
+            fn is_external(&self) -> bool {
+                false
+            }
+
+            fn poll(&mut self, rolls: &mut Vec<source::Roll>) {
+                let arrived = Instant::now();
+                rolls.extend(
+                    self.0
+                        .drain(..)
+                        .map(|roll_key| source::Roll { roll_key, arrived }),
+                );
+            }
+
+            fn take_invalid(&mut self) -> u64 {
+                2
+            }
+        }
+
+        let mut world = World::new(0, 0);
+        world.source_kind = source::Kind::Stdin;
+        world.source = Some(Box::new(Pending(vec![0, 4, 4, 19])));
+        world.cycle_source();
+        assert!(world.source_kind == source::Kind::Rng);
+        assert!(world.source.is_none());
+        assert_eq!(world.total_rolls, 4);
+        assert_eq!(world.face_totals[0], 1);
+        assert_eq!(world.face_totals[4], 2);
+        assert_eq!(world.face_totals[19], 1);
+        assert_eq!(world.accounting.generated, 6);
+        assert_eq!(world.accounting.invalid, 2);
+        assert!(world.accounting.is_balanced());
+    }
+
+    #[test]
+    fn sticky_rolls_repeat_as_often_as_set() {
+        const ROLLS: u32 = 200_000;
+        for stickiness in [0., 0.25, 0.9] {
+            let mut world = World::new(0, 0);
+            world.set_seed(11);
+            world.set_stickiness(stickiness);
+            world.roll_once();
+            let mut repeats = 0;
+            for _ in 0..ROLLS {
+                let last_roll_key = world.last_roll_key;
+                world.roll_once();
+                if world.last_roll_key == last_roll_key {
+                    repeats += 1;
+                }
+            }
+            // Sticking, or rolling fresh and happening to match.
+            let expected = stickiness + (1. - stickiness) / 20.;
+            let measured = f64::from(repeats) / f64::from(ROLLS);
+            assert!(
+                (measured - expected).abs() < 0.005,
+                "{measured} repeats at {stickiness}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn entropy_of_uniform_and_degenerate_rolls() {
+        let mut world = World::new(0, 0);
+        assert_eq!(world.entropy(), 0.);
+        for _ in 0..5 {
+            world.record_roll(12);
+        }
+        assert_eq!(world.entropy(), 0.);
+        for roll_key in (0..20).filter(|&roll_key| roll_key != 12) {
+            for _ in 0..5 {
+                world.record_roll(roll_key);
+            }
+        }
+        assert!((world.entropy() - world.max_entropy()).abs() < 1e-12);
+        assert!((world.max_entropy() - 20_f64.log2()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn entropy_of_a_two_faced_split() {
+        let mut world = World::new(0, 0);
+        world.record_roll(0);
+        world.record_roll(1);
+        assert!((world.entropy() - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn excluded_faces_never_come_up() {
+        let mut world = World::new(0, 0);
+        world.set_seed(5);
+        world.set_excluded(&[1, 7, 20]);
+        world.set_rate(1_000, Duration::from_secs(60));
+        for _ in 0..20 {
+            world.roll_from_rng(Instant::now());
+        }
+        assert_eq!(world.total_rolls, 20_000);
+        for (roll_key, &count) in world.face_totals.iter().enumerate() {
+            if [0, 6, 19].contains(&roll_key) {
+                assert_eq!(count, 0, "face {} was rolled", roll_key + 1);
+            } else {
+                // The rest share the rolls between them.
+                let fair = 20_000 / 17;
+                assert!(count > fair * 9 / 10, "face {} is short", roll_key + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn continuous_rolls_are_uniform() {
+        let mut world = World::new(0, 0);
+        world.set_continuous(true);
+        world.set_rate(10_000, Duration::from_secs(60));
+        for seed in 0..5 {
+            world.set_seed(seed);
+            world.clear_counts();
+            for _ in 0..10 {
+                world.roll_from_rng(Instant::now());
+            }
+            assert_eq!(world.total_rolls, 100_000);
+            let metrics = Metrics::compute(&world.face_totals);
+            // Strict enough that a fair die only fails one seed in a thousand.
+            assert!(
+                metrics.p_value() > 0.001,
+                "p-value of {} with seed {seed}",
+                metrics.p_value()
+            );
+            assert!(metrics.min > 100_000 / 20 * 95 / 100);
+            assert!(metrics.max < 100_000 / 20 * 105 / 100);
+        }
+    }
+
+    #[test]
+    fn hidden_faces_pass_on_the_lead() {
+        let mut world = World::new(0, 0);
+        for roll_key in 0..20 {
+            let count = match roll_key {
+                2 => 10,
+                7 => 7,
+                11 => 1,
+                14 => 2,
+                _ => 4,
+            };
+            for _ in 0..count {
+                world.record_roll(roll_key);
+            }
+        }
+        world.update_statistics();
+        assert_eq!(world.metrics.winner(), Some(2));
+        assert_eq!(world.metrics.loser(), Some(11));
+
+        // With the true leader and trailer hidden, the runners-up take their places.
+        world.set_hidden(&[3, 12]);
+        world.update_statistics();
+        assert_eq!(world.metrics.winner(), Some(7));
+        assert_eq!(world.metrics.loser(), Some(14));
+        // Everything else still counts the hidden faces.
+        assert_eq!(world.metrics.total, world.total_rolls);
+        assert_eq!(world.metrics.max, 10);
+
+        world.set_hidden(&[]);
+        world.update_statistics();
+        assert_eq!(world.metrics.winner(), Some(2));
+        assert_eq!(world.metrics.loser(), Some(11));
+    }
+
+    #[test]
+    fn zero_indexed_faces_are_numbered_from_zero() {
+        let mut world = World::new(0, 0);
+        world.set_first_face(0);
+        world.set_labels(default_labels(0));
+        world.set_excluded(&[20]);
+        for roll_key in 0..19 {
+            for _ in 0..if roll_key == 0 { 150 } else { 50 } {
+                world.record_roll(roll_key);
+            }
+        }
+        world.update_statistics();
+
+        // The config's DC counts from 1, so its 20th face is shown as 19.
+        world.set_dc(Some(20));
+        let lines = world.dc_lines(&world.metrics);
+        assert_eq!(lines[0], "DC 19");
+        assert!(lines[1].starts_with("Roll 19+: "));
+
+        world.toggle_spotlight();
+        let stats: serde_json::Value = serde_json::from_str(&world.stats_json()).unwrap();
+        assert_eq!(stats["dc"]["dc"], 19);
+        assert_eq!(stats["spotlight"]["faces"], serde_json::json!([0, 19]));
+
+        let dir = std::env::temp_dir().join(format!("d20-zero-indexed-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(report::DEFAULT_PATH);
+        world.write_report(&path);
+        let report = std::fs::read_to_string(&path).unwrap();
+        assert!(report.contains("  Excluded faces: 19\n"), "{report}");
+        assert!(report.contains("\n   0 (0): 150 "), "{report}");
+        assert!(report.contains("\n  19 (19): 0 "), "{report}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn soft_reset_evens_out_the_counts() {
+        let mut world = World::new(0, 0);
+        world.set_seed(8);
+        world.set_excluded(&[3]);
+        world.set_rate(1_003, Duration::from_secs(60));
+        world.roll_from_rng(Instant::now());
+        world.soft_reset();
+        assert_eq!(world.total_rolls, 1_003);
+        assert_eq!(world.face_totals.iter().sum::<u64>(), 1_003);
+        assert_eq!(world.roll_counts.iter().sum::<u64>(), 1_003);
+        // As even as whole rolls allow, with the three left over on the lowest faces.
+        for counts in [&world.face_totals[..], &world.roll_counts[..]] {
+            assert_eq!(counts[..3], [51; 3]);
+            assert_eq!(counts[3..], [50; 17]);
+        }
+        let metrics = Metrics::compute(&world.face_totals);
+        assert!(metrics.chi_square() < 0.1);
+        assert!(metrics.p_value() > 0.99);
+    }
+
+    #[test]
+    fn even_out_keeps_the_total() {
+        let mut counts = [0, 0, 7];
+        even_out(&mut counts);
+        assert_eq!(counts, [3, 2, 2]);
+        let mut counts = [0; 4];
+        even_out(&mut counts);
+        assert_eq!(counts, [0; 4]);
+        let mut counts = [u64::MAX / 2, u64::MAX / 2];
+        even_out(&mut counts);
+        assert_eq!(counts, [u64::MAX / 2; 2]);
+    }
+
+    #[test]
+    fn counts_saturate_instead_of_wrapping() {
+        let mut world = World::new(0, 0);
+        world.face_totals[4] = u64::MAX;
+        assert!(!world.saturated);
+        world.record_roll(4);
+        assert_eq!(world.face_totals[4], u64::MAX);
+        assert!(world.saturated);
+        assert_eq!(world.total_rolls, 1);
+    }
+
+    /// Check that a bar of the count read at each height is drawn reaching that height.
+    fn assert_crosshair_inverts_fit(world: &World) {
+        let rows_per_pixel = if world.mirrored { 2 } else { 1 };
+        for rows in [1, 10, world.live_height() / rows_per_pixel] {
+            let rolls = world.rolls_at_height(rows);
+            let fitted = world.fit_to_chart(&[rolls], world.top_count(None), world.is_pinned(None));
+            let cells = u64::from(rows * rows_per_pixel * world.column_width);
+            assert!(
+                fitted[0].abs_diff(cells) <= 1,
+                "{rows} rows read as {rolls} rolls, drawn as {} cells rather than {cells}",
+                fitted[0]
+            );
+        }
+    }
+
+    #[test]
+    fn crosshair_reads_back_every_scaling() {
+        let mut world = World::new(800, 600);
+        assert!(world.column_width > 0 && world.live_height() > 0);
+        let max_allowed = u64::from(world.column_width) * u64::from(world.live_height());
+        // One roll per pixel.
+        assert_eq!(
+            world.rolls_at_height(10),
+            10 * u64::from(world.column_width)
+        );
+        assert_crosshair_inverts_fit(&world);
+        // Scaled down once the counts outgrow the chart.
+        world.scale = max_allowed * 7;
+        assert_crosshair_inverts_fit(&world);
+        world.mirrored = true;
+        assert_crosshair_inverts_fit(&world);
+        // Pinned below the counts' size, scaling them up.
+        world.mirrored = false;
+        world.y_max = Some(max_allowed / 3);
+        assert_crosshair_inverts_fit(&world);
+    }
+
+    #[test]
+    fn scale_holds_within_its_band() {
+        // Set with headroom for a tallest bar of 1,000, so it holds from 800 up to 1,100.
+        let scale = 1_100;
+        for max_found in [800, 900, 1_000, 1_099, 1_100] {
+            assert_eq!(hysteresis_scale(scale, max_found, 0), scale, "{max_found}");
+        }
+    }
+
+    #[test]
+    fn scale_resets_outside_its_band() {
+        let scale = 1_100;
+        // Outgrown: fits the new tallest bar with headroom again.
+        assert_eq!(hysteresis_scale(scale, 1_101, 0), 1_211);
+        assert_eq!(hysteresis_scale(scale, 5_000, 0), 5_500);
+        // Shrunk well below: closes in on the tallest bar.
+        assert_eq!(hysteresis_scale(scale, 799, 0), 878);
+        assert_eq!(hysteresis_scale(scale, 100, 0), 110);
+        // Either way, never below a column's capacity.
+        assert_eq!(hysteresis_scale(scale, 100, 400), 400);
+        assert_eq!(hysteresis_scale(scale, 900, 2_000), 2_000);
+        assert_eq!(hysteresis_scale(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn integer_heights_stay_within_a_pixel_of_float() {
+        let mut world = World::new(800, 600);
+        let column_width = u64::from(world.column_width);
+        let max_allowed = column_width * u64::from(world.live_height());
+        // Pinned below the chart's size scales up; the rest scale down.
+        for top in [
+            max_allowed / 3,
+            max_allowed + 1,
+            max_allowed * 3 - 1,
+            1 << 40,
+            (1 << 60) + 7,
+        ] {
+            // Counts either side of where a bar gains a row, and huge counts floats can't hold
+            // exactly.
+            let counts: Vec<u64> = [1, 2, 3, 7, 10]
+                .iter()
+                .flat_map(|&tenths| {
+                    let count = top / 10 * tenths;
+                    [count.saturating_sub(1), count, count + 1]
+                })
+                .chain([top - 1, top])
+                .collect();
+            for pinned in [false, true] {
+                world.integer_render = false;
+                let float = world.fit_to_chart(&counts, top, pinned);
+                world.integer_render = true;
+                let integer = world.fit_to_chart(&counts, top, pinned);
+                for ((count, float), integer) in counts.iter().zip(float).zip(integer) {
+                    let (float_rows, integer_rows) = (float / column_width, integer / column_width);
+                    assert!(
+                        float_rows.abs_diff(integer_rows) <= 1,
+                        "{count} of {top} drew {float_rows} rows with floats, {integer_rows} without"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn resizing_across_every_tier_draws() {
+        let mut world = World::new(800, 600);
+        for roll_key in 0..20 {
+            world.record_roll(roll_key);
+        }
+        world.update_statistics();
+        // Shrink through every tier down to a sliver, then grow back.
+        for (width, height) in [
+            (800, 600),
+            (200, 150),
+            (120, 90),
+            (60, 40),
+            (2, 2),
+            (800, 600),
+        ] {
+            world.set_size(width, height);
+            let mut frame = vec![0; (width * height * 4) as usize];
+            world.draw(&mut frame);
+        }
+    }
+
+    #[test]
+    fn checksum_ignores_how_rolls_are_batched() {
+        let checksum = |rolls_per_frame: u64| {
+            let mut world = World::new(0, 0);
+            world.set_seed(3);
+            world.set_rate(rolls_per_frame, Duration::from_secs(60));
+            for _ in 0..1_000 / rolls_per_frame {
+                world.roll_from_rng(Instant::now());
+            }
+            assert_eq!(world.total_rolls, 1_000);
+            world.roll_hash.to_string()
+        };
+        assert_eq!(checksum(8), checksum(1_000));
+        assert_eq!(checksum(1), checksum(1_000));
+    }
+
+    #[test]
+    fn ascii_chart_follows_the_die_size() {
+        let mut world = World::new(0, 0);
+        world.set_seed(6);
+        world.set_overlay(Overlay::new(&[6]));
+        let overlay = world.overlay.as_mut().unwrap();
+        for _ in 0..600 {
+            overlay.roll(&mut world.rng);
+        }
+        let chart = world.ascii_chart(80);
+        assert!(chart.starts_with("d6\n"));
+        let bars = chart.lines().skip(1).take_while(|line| !line.is_empty());
+        assert_eq!(bars.count(), 6);
+        assert!(chart.contains("rolls         600\n"));
+    }
+
+    #[test]
+    fn embedders_count_pushed_rolls_and_draw_them() {
+        let mut world = World::new(200, 100);
+        world.push_rolls(&[0, 19, 19, 20, 255]);
+        assert_eq!(world.total_rolls, 3);
+        assert_eq!(world.roll_counts[19], 2);
+
+        world.set_view(View::Expected);
+        let mut frame = vec![0; 320 * 240 * 4];
+        world.draw_into(&mut frame, 320, 240);
+        assert!(frame
+            .chunks_exact(4)
+            .any(|pixel| pixel != world.theme.background));
+    }
+}
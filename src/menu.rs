@@ -0,0 +1,95 @@
+//! A small menu of the most common actions, opened by right-clicking, for anyone who hasn't
+//! learned the shortcuts.
+
+use crate::text;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    Pause,
+    Reset,
+    WriteReport,
+    SaveCounts,
+    SaveSummary,
+    SwitchView,
+    Quit,
+}
+
+impl Item {
+    pub fn label(self, paused: bool) -> &'static str {
+        match self {
+            Self::Pause if paused => "Resume",
+            Self::Pause => "Pause",
+            Self::Reset => "Reset",
+            Self::WriteReport => "Write report",
+            Self::SaveCounts => "Save CSV",
+            Self::SaveSummary => "Save summary image",
+            Self::SwitchView => "Switch view",
+            Self::Quit => "Quit",
+        }
+    }
+}
+
+pub const ITEMS: [Item; 7] = [
+    Item::Pause,
+    Item::Reset,
+    Item::WriteReport,
+    Item::SaveCounts,
+    Item::SaveSummary,
+    Item::SwitchView,
+    Item::Quit,
+];
+
+pub struct Menu {
+    /// Top-left corner, kept within the frame.
+    pub x: i32,
+    pub y: i32,
+    /// Index into `ITEMS` of the highlighted item.
+    pub selected: usize,
+}
+
+impl Menu {
+    /// A menu opening at (`x`, `y`), or as near as it can while staying within a frame of
+    /// `frame_width` by `frame_height` pixels.
+    pub fn open(
+        x: u32,
+        y: u32,
+        frame_width: u32,
+        frame_height: u32,
+        scale: u32,
+        paused: bool,
+    ) -> Self {
+        let (width, height) = text::text_box_size(scale, &lines(paused));
+        Self {
+            x: x.min(frame_width.saturating_sub(width)) as i32,
+            y: y.min(frame_height.saturating_sub(height)) as i32,
+            selected: 0,
+        }
+    }
+
+    pub fn item(&self) -> Item {
+        ITEMS[self.selected]
+    }
+
+    /// Highlight the item `step` places down, wrapping around at either end.
+    pub fn move_selection(&mut self, step: isize) {
+        self.selected = (self.selected as isize + step).rem_euclid(ITEMS.len() as isize) as usize;
+    }
+
+    /// Index into `ITEMS` of the item at (`x`, `y`), if it's over one.
+    pub fn item_at(&self, x: u32, y: u32, scale: u32, paused: bool) -> Option<usize> {
+        let (width, height) = text::text_box_size(scale, &lines(paused));
+        let (x, y) = (x as i32 - self.x, y as i32 - self.y);
+        if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+            return None;
+        }
+        // Each item's row takes in half the spacing above and below it.
+        let row_top = scale as i32;
+        let line_height = (text::text_height(scale) + 2 * scale) as i32;
+        Some(((y - row_top).max(0) / line_height).min(ITEMS.len() as i32 - 1) as usize)
+    }
+}
+
+/// Every item's label, top to bottom.
+pub fn lines(paused: bool) -> Vec<&'static str> {
+    ITEMS.iter().map(|item| item.label(paused)).collect()
+}
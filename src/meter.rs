@@ -0,0 +1,82 @@
+//! A view like a row of VU meters: each face's share of only the last second's rolls, with a
+//! tick holding its recent peak that then sinks slowly.
+
+use std::time::{Duration, Instant};
+
+/// How long a peak holds before it starts to sink.
+const HOLD: Duration = Duration::from_secs(1);
+
+/// How fast a peak sinks once it's held, in fair shares per second.
+const FALL: f64 = 0.5;
+
+#[derive(Clone, Copy)]
+struct Peak {
+    share: f64,
+    /// When the peak was last pushed up.
+    set: Instant,
+}
+
+pub struct Meter {
+    /// Each face's share of the rolls in the window, or 0 if there were none.
+    shares: Vec<f64>,
+    peaks: Vec<Option<Peak>>,
+    last_tick: Option<Instant>,
+}
+
+impl Meter {
+    pub fn new(sides: usize) -> Self {
+        Self {
+            shares: vec![0.; sides],
+            peaks: vec![None; sides],
+            last_tick: None,
+        }
+    }
+
+    /// Take the latest windowed `counts` and let the peaks sink for the time since the last
+    /// tick, or rise to meet the new shares.
+    pub fn tick(&mut self, counts: &[u64], now: Instant) {
+        let elapsed = self.last_tick.replace(now).map_or(0., |last_tick| {
+            now.saturating_duration_since(last_tick).as_secs_f64()
+        });
+        let total = counts.iter().sum::<u64>() as f64;
+        let fall = FALL / self.shares.len() as f64 * elapsed;
+        for ((share, peak), &count) in self.shares.iter_mut().zip(&mut self.peaks).zip(counts) {
+            *share = if total == 0. {
+                0.
+            } else {
+                count as f64 / total
+            };
+            match peak {
+                Some(held) if held.share > *share => {
+                    if now.saturating_duration_since(held.set) > HOLD {
+                        held.share = (held.share - fall).max(*share);
+                    }
+                }
+                _ => {
+                    *peak = Some(Peak {
+                        share: *share,
+                        set: now,
+                    })
+                }
+            }
+        }
+    }
+
+    pub fn shares(&self) -> &[f64] {
+        &self.shares
+    }
+
+    /// Each face's held peak share.
+    pub fn peaks(&self) -> impl Iterator<Item = f64> + '_ {
+        self.peaks
+            .iter()
+            .map(|peak| peak.map_or(0., |peak| peak.share))
+    }
+
+    /// The share drawn at the top of the chart: twice a fair share, or more if a peak needs the
+    /// room. Windowed shares jump around too much to scale to the tallest bar.
+    pub fn full_scale(&self) -> f64 {
+        let fair_share = 1. / self.shares.len() as f64;
+        self.peaks().fold(2. * fair_share, f64::max)
+    }
+}
@@ -0,0 +1,684 @@
+/// Per-face statistics derived from the roll counts, shared by every way of presenting them.
+#[derive(Default)]
+pub struct Metrics {
+    /// Every face's count added up, saturating at `u64::MAX`.
+    pub total: u64,
+    /// Every face's count added up without saturating, for working out shares.
+    exact_total: u128,
+    pub min: u64,
+    pub max: u64,
+    /// Every face holding the highest count, lowest first, if any face has been rolled.
+    pub winners: Vec<usize>,
+    /// Every face holding the lowest count, lowest first, if any face has been rolled.
+    pub losers: Vec<usize>,
+    /// Each face's fraction of all rolls.
+    pub shares: Vec<f64>,
+    /// How many standard deviations each face's count is from what a fair die would give.
+    pub z_scores: Vec<f64>,
+}
+
+impl Metrics {
+    pub fn compute(counts: &[u64]) -> Self {
+        let mut metrics = Self::default();
+        metrics.recompute(counts);
+        metrics
+    }
+
+    /// Recompute from `counts` in place, reusing the previous computation's allocations.
+    pub fn recompute(&mut self, counts: &[u64]) {
+        self.winners.clear();
+        self.losers.clear();
+        self.shares.clear();
+        self.z_scores.clear();
+
+        // Twenty faces near 2^60 rolls each are already more than a u64 holds.
+        self.exact_total = counts.iter().map(|&count| u128::from(count)).sum();
+        self.total = u64::try_from(self.exact_total).unwrap_or(u64::MAX);
+        self.max = counts.iter().copied().max().unwrap_or(0);
+        self.min = counts.iter().copied().min().unwrap_or(0);
+        if self.total == 0 {
+            self.min = 0;
+            self.shares.resize(counts.len(), 0.);
+            self.z_scores.resize(counts.len(), 0.);
+            return;
+        }
+
+        let total = self.exact_total as f64;
+        let sides = counts.len() as i128;
+        let chance = 1. / counts.len() as f64;
+        let deviation = (total * chance * (1. - chance)).sqrt();
+        // Multiply by reciprocals worked out once rather than dividing for every face.
+        let per_roll = 1. / total;
+        let per_excess = if deviation == 0. {
+            0.
+        } else {
+            1. / (sides as f64 * deviation)
+        };
+        // Converting from an i128 to a float is slow, so stick to i64 when every difference
+        // from the expected count fits in one, as it does short of trillions of rolls.
+        let narrow_total = i64::try_from(self.exact_total)
+            .ok()
+            .filter(|_| i128::from(self.max) * sides <= i128::from(i64::MAX));
+        for (roll_key, &count) in counts.iter().enumerate() {
+            if count == self.max {
+                self.winners.push(roll_key);
+            }
+            if count == self.min {
+                self.losers.push(roll_key);
+            }
+            self.shares.push(count as f64 * per_roll);
+            // Take the difference from the expected count in integers, since subtracting two
+            // huge floats would leave little but rounding error.
+            let excess = match narrow_total {
+                Some(total) => (count as i64 * sides as i64 - total) as f64,
+                None => (i128::from(count) * sides - self.exact_total as i128) as f64,
+            };
+            self.z_scores.push(excess * per_excess);
+        }
+    }
+
+    /// Pick the winners and losers from only the faces marked in `shown`, such as when the rest
+    /// are hidden from the chart. Everything else still covers every face.
+    pub fn rank_among(&mut self, counts: &[u64], shown: &[bool]) {
+        self.winners.clear();
+        self.losers.clear();
+        if self.total == 0 {
+            return;
+        }
+        let shown_counts = || {
+            counts
+                .iter()
+                .enumerate()
+                .filter(|&(roll_key, _)| shown[roll_key])
+        };
+        let (Some(max), Some(min)) = (
+            shown_counts().map(|(_, &count)| count).max(),
+            shown_counts().map(|(_, &count)| count).min(),
+        ) else {
+            return;
+        };
+        for (roll_key, &count) in shown_counts() {
+            if count == max {
+                self.winners.push(roll_key);
+            }
+            if count == min {
+                self.losers.push(roll_key);
+            }
+        }
+    }
+
+    /// The first face holding the highest count, if any face has been rolled.
+    pub fn winner(&self) -> Option<usize> {
+        self.winners.first().copied()
+    }
+
+    /// The first face holding the lowest count, if any face has been rolled.
+    pub fn loser(&self) -> Option<usize> {
+        self.losers.first().copied()
+    }
+
+    /// Every face whose count is more than `threshold` standard deviations from a fair die's,
+    /// lowest first.
+    pub fn outliers(&self, threshold: f64) -> Vec<usize> {
+        self.z_scores
+            .iter()
+            .enumerate()
+            .filter(|(_, z_score)| z_score.abs() > threshold)
+            .map(|(roll_key, _)| roll_key)
+            .collect()
+    }
+
+    /// How full `count`'s bar is relative to the tallest bar, from 0 to 1.
+    pub fn bar_fraction(&self, count: u64) -> f64 {
+        if self.max == 0 {
+            0.
+        } else {
+            count as f64 / self.max as f64
+        }
+    }
+
+    /// Average face value, with faces numbered from 1.
+    pub fn mean_roll(&self) -> f64 {
+        compensated_sum(
+            self.shares
+                .iter()
+                .enumerate()
+                .map(|(roll_key, share)| (roll_key + 1) as f64 * share),
+        )
+    }
+
+    /// Average face value, with faces numbered from `first_face`.
+    pub fn mean_face(&self, first_face: usize) -> f64 {
+        self.mean_roll() + first_face as f64 - 1.
+    }
+
+    /// Variance of the face value, with faces numbered from 1.
+    pub fn variance(&self) -> f64 {
+        let mean = self.mean_roll();
+        compensated_sum(
+            self.shares
+                .iter()
+                .enumerate()
+                .map(|(roll_key, share)| share * ((roll_key + 1) as f64 - mean).powi(2)),
+        )
+    }
+
+    /// Fraction of rolls that came up `roll_key` or higher.
+    pub fn share_at_least(&self, roll_key: usize) -> f64 {
+        compensated_sum(self.shares.iter().skip(roll_key).copied())
+    }
+
+    /// The furthest any face's share strays from the even share every face would get from a
+    /// fair die.
+    pub fn max_share_deviation(&self) -> f64 {
+        let expected = 1. / self.shares.len() as f64;
+        self.shares
+            .iter()
+            .map(|share| (share - expected).abs())
+            .fold(0., f64::max)
+    }
+
+    /// Chance of a fair die straying at least this far from even counts, from 1 for a perfect
+    /// match down toward 0 as the evidence of bias grows.
+    pub fn p_value(&self) -> f64 {
+        if self.total == 0 {
+            return 1.;
+        }
+        chi_square_p_value(self.chi_square(), self.shares.len().saturating_sub(1))
+    }
+
+    /// Pearson's chi-square statistic for how far the counts stray from every face being
+    /// equally likely.
+    pub fn chi_square(&self) -> f64 {
+        let expected = 1. / self.shares.len() as f64;
+        compensated_sum(
+            self.shares
+                .iter()
+                .map(|share| (share - expected).powi(2) / expected),
+        ) * self.exact_total as f64
+    }
+}
+
+/// Add up `values` with Neumaier's compensated summation, carrying the low-order bits each
+/// addition would round away, so sums over shares of huge totals stay steady.
+pub fn compensated_sum(values: impl IntoIterator<Item = f64>) -> f64 {
+    let mut sum = 0.;
+    let mut compensation = 0.;
+    for value in values {
+        let next = sum + value;
+        compensation += if f64::abs(sum) >= f64::abs(value) {
+            (sum - next) + value
+        } else {
+            (value - next) + sum
+        };
+        sum = next;
+    }
+    sum + compensation
+}
+
+/// Chi-square value that a fair die exceeds only 5% of the time with `sides` faces, using the
+/// Wilson–Hilferty approximation.
+pub fn chi_square_critical_value(sides: usize) -> f64 {
+    const Z_95: f64 = 1.644_854;
+    let degrees_of_freedom = sides.saturating_sub(1).max(1) as f64;
+    let spread = 2. / (9. * degrees_of_freedom);
+    degrees_of_freedom * (1. - spread + Z_95 * spread.sqrt()).powi(3)
+}
+
+/// How many of `total` rolls each face should have come up, given each face's relative chance
+/// in `weights`. Equal weights give every face the same share.
+pub fn expected_counts(total: u64, weights: &[f64]) -> Vec<u64> {
+    let weight_sum: f64 = weights.iter().sum();
+    weights
+        .iter()
+        .map(|weight| {
+            if weight_sum == 0. {
+                0
+            } else {
+                (total as f64 * weight / weight_sum).round() as u64
+            }
+        })
+        .collect()
+}
+
+/// Rolls a die loaded with `weights` needs before the chi-square test catches it at the 5%
+/// level 80% of the time, or `None` if the weights are even and there's nothing to catch.
+///
+/// The statistic then follows a noncentral chi-square distribution whose noncentrality grows
+/// with the rolls in proportion to Cohen's effect size; it's approximated as normal to solve for
+/// the noncentrality in closed form.
+pub fn rolls_to_detect(weights: &[f64]) -> Option<f64> {
+    const Z_80: f64 = 0.841_621;
+    let weight_sum: f64 = weights.iter().sum();
+    let fair_share = 1. / weights.len() as f64;
+    let effect_size: f64 = weights
+        .iter()
+        .map(|weight| (weight / weight_sum - fair_share).powi(2) / fair_share)
+        .sum();
+    if effect_size <= 1e-12 {
+        return None;
+    }
+    let degrees_of_freedom = weights.len().saturating_sub(1).max(1) as f64;
+    let critical_value = chi_square_critical_value(weights.len());
+    // Solve `df + λ - Z_80 * sqrt(2 * (df + 2λ)) = critical_value` for λ by way of the square
+    // root, which makes it a quadratic.
+    let root = 2. * (Z_80 + (Z_80 * Z_80 + critical_value - degrees_of_freedom / 2.).sqrt());
+    let noncentrality = (root * root - 2. * degrees_of_freedom) / 4.;
+    Some(noncentrality / effect_size)
+}
+
+/// Mean and variance of the face value, with faces numbered from 1, for a die whose faces come
+/// up with relative chances `weights`.
+pub fn weighted_moments(weights: &[f64]) -> (f64, f64) {
+    let weight_sum: f64 = weights.iter().sum();
+    if weight_sum <= 0. {
+        return (0., 0.);
+    }
+    let face = |roll_key: usize| (roll_key + 1) as f64;
+    let mean = compensated_sum(
+        weights
+            .iter()
+            .enumerate()
+            .map(|(roll_key, weight)| face(roll_key) * weight / weight_sum),
+    );
+    let variance = compensated_sum(
+        weights
+            .iter()
+            .enumerate()
+            .map(|(roll_key, weight)| (face(roll_key) - mean).powi(2) * weight / weight_sum),
+    );
+    (mean, variance)
+}
+
+/// Each face's share under a normal distribution with `mean` and `variance`, taking each face
+/// to cover the half a face either side of it. The tails beyond the first and last faces are
+/// folded into them, so the shares add up to 1.
+pub fn normal_shares(sides: usize, mean: f64, variance: f64) -> Vec<f64> {
+    let deviation = variance.sqrt();
+    let below = |edge: f64| {
+        if deviation > 0. {
+            normal_cdf((edge - mean) / deviation)
+        } else if edge >= mean {
+            1.
+        } else {
+            0.
+        }
+    };
+    (0..sides)
+        .map(|roll_key| {
+            let face = (roll_key + 1) as f64;
+            let upper = if roll_key + 1 == sides {
+                1.
+            } else {
+                below(face + 0.5)
+            };
+            let lower = if roll_key == 0 { 0. } else { below(face - 0.5) };
+            upper - lower
+        })
+        .collect()
+}
+
+/// The Kolmogorov–Smirnov statistic between `counts` and a die whose faces come up with relative
+/// chances `weights`: the furthest the observed share of rolls at or below any face strays from
+/// the expected share.
+pub fn ks_distance(counts: &[u64], weights: &[f64]) -> f64 {
+    let total = counts.iter().sum::<u64>() as f64;
+    let weight_sum: f64 = weights.iter().sum();
+    if total == 0. || weight_sum <= 0. {
+        return 0.;
+    }
+    let mut observed = 0.;
+    let mut expected = 0.;
+    let mut distance: f64 = 0.;
+    for (&count, weight) in counts.iter().zip(weights) {
+        observed += count as f64 / total;
+        expected += weight / weight_sum;
+        distance = distance.max((observed - expected).abs());
+    }
+    distance
+}
+
+/// Chance of a standard normal variable falling at or below `z`.
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1. + erf(z / std::f64::consts::SQRT_2))
+}
+
+/// The error function, by Abramowitz and Stegun's formula 7.1.26, good to about 1.5e-7.
+fn erf(x: f64) -> f64 {
+    const P: f64 = 0.327_591_1;
+    const A: [f64; 5] = [
+        0.254_829_592,
+        -0.284_496_736,
+        1.421_413_741,
+        -1.453_152_027,
+        1.061_405_429,
+    ];
+    let t = 1. / (1. + P * x.abs());
+    let polynomial = A.iter().rev().fold(0., |sum, a| sum * t + a) * t;
+    let magnitude = 1. - polynomial * (-x * x).exp();
+    magnitude.copysign(x)
+}
+
+/// Chance of a chi-square statistic at least as large as `chi_square` arising by luck, with
+/// `degrees_of_freedom` degrees of freedom.
+pub fn chi_square_p_value(chi_square: f64, degrees_of_freedom: usize) -> f64 {
+    upper_regularized_gamma(degrees_of_freedom.max(1) as f64 / 2., chi_square / 2.)
+}
+
+/// Chi-square statistic for whether two sets of counts come from the same distribution, and
+/// its degrees of freedom. Faces neither set has seen are left out.
+pub fn two_sample_chi_square(first: &[u64], second: &[u64]) -> (f64, usize) {
+    let first_total = first.iter().sum::<u64>() as f64;
+    let second_total = second.iter().sum::<u64>() as f64;
+    if first_total == 0. || second_total == 0. {
+        return (0., 0);
+    }
+    let first_weight = (second_total / first_total).sqrt();
+    let second_weight = (first_total / second_total).sqrt();
+    let mut chi_square = 0.;
+    let mut faces_seen: usize = 0;
+    for (&first, &second) in first.iter().zip(second) {
+        if first + second == 0 {
+            continue;
+        }
+        faces_seen += 1;
+        chi_square += (first_weight * first as f64 - second_weight * second as f64).powi(2)
+            / (first + second) as f64;
+    }
+    (chi_square, faces_seen.saturating_sub(1))
+}
+
+/// Q(a, x), the upper regularized incomplete gamma function, which gives the chi-square
+/// distribution's upper tail. Uses the series below `a + 1` and a continued fraction above it,
+/// as in Numerical Recipes.
+fn upper_regularized_gamma(a: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-12;
+    const MAX_ITERATIONS: usize = 500;
+    if x <= 0. {
+        return 1.;
+    }
+    let log_prefix = a * x.ln() - x - ln_gamma(a);
+    if x < a + 1. {
+        let mut term = 1. / a;
+        let mut sum = term;
+        let mut n = a;
+        for _ in 0..MAX_ITERATIONS {
+            n += 1.;
+            term *= x / n;
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        (1. - sum * log_prefix.exp()).clamp(0., 1.)
+    } else {
+        // Lentz's method.
+        let tiny = 1e-300;
+        let mut b = x + 1. - a;
+        let mut c = 1. / tiny;
+        let mut d = 1. / b;
+        let mut fraction = d;
+        for i in 1..=MAX_ITERATIONS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1. / d;
+            let delta = d * c;
+            fraction *= delta;
+            if (delta - 1.).abs() < EPSILON {
+                break;
+            }
+        }
+        (fraction * log_prefix.exp()).clamp(0., 1.)
+    }
+}
+
+/// Natural log of the gamma function, by the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_78,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000_000_000_190_015;
+    for (i, coefficient) in COEFFICIENTS.iter().enumerate() {
+        series += coefficient / (x + 1. + i as f64);
+    }
+    -tmp + (2.506_628_274_631_000_7 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn all_zero_counts() {
+        let metrics = Metrics::compute(&[0; 20]);
+        assert_eq!(metrics.total, 0);
+        assert_eq!((metrics.min, metrics.max), (0, 0));
+        assert!(metrics.winners.is_empty());
+        assert!(metrics.losers.is_empty());
+        assert_eq!(metrics.shares, [0.; 20]);
+        assert_eq!(metrics.z_scores, [0.; 20]);
+        assert_eq!(metrics.winner(), None);
+        assert_eq!(metrics.bar_fraction(0), 0.);
+    }
+
+    #[test]
+    fn single_face_dominant() {
+        let mut counts = [0; 20];
+        counts[6] = 1_000;
+        let metrics = Metrics::compute(&counts);
+        assert_eq!(metrics.total, 1_000);
+        assert_eq!((metrics.min, metrics.max), (0, 1_000));
+        assert_eq!(metrics.winners, [6]);
+        assert_eq!(metrics.losers.len(), 19);
+        assert!(!metrics.losers.contains(&6));
+        assert_eq!(metrics.shares[6], 1.);
+        assert!(metrics.z_scores[6] > 20.);
+        // Every other face falls short by far more than chance allows, too.
+        assert!(metrics.z_scores[0] < -3.);
+        assert_eq!(metrics.outliers(3.).len(), 20);
+        assert_eq!(metrics.mean_roll(), 7.);
+    }
+
+    #[test]
+    fn even_counts_have_no_outliers() {
+        let metrics = Metrics::compute(&[50; 6]);
+        assert_eq!(metrics.winners, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(metrics.losers, metrics.winners);
+        assert!(metrics.z_scores.iter().all(|&z_score| z_score == 0.));
+        assert!((metrics.mean_roll() - 3.5).abs() < 1e-12);
+        assert!((metrics.mean_face(0) - 2.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn expected_counts_follow_the_weights() {
+        assert_eq!(expected_counts(600, &[1.; 6]), [100; 6]);
+        assert_eq!(
+            expected_counts(600, &[1., 1., 1., 1., 1., 5.]),
+            [60, 60, 60, 60, 60, 300]
+        );
+        // Weights only matter relative to each other.
+        assert_eq!(
+            expected_counts(600, &[0.5, 0.5, 0.5, 0.5, 0.5, 2.5]),
+            expected_counts(600, &[1., 1., 1., 1., 1., 5.])
+        );
+        assert_eq!(expected_counts(10, &[0., 1.]), [0, 10]);
+        assert_eq!(expected_counts(10, &[0.; 4]), [0; 4]);
+    }
+
+    #[test]
+    fn shares_stay_monotonic_near_2_to_the_60() {
+        const BASE: u64 = 1 << 60;
+        // More than a u64 holds in total, with faces a single roll apart.
+        let counts: Vec<u64> = (0..20).map(|roll_key| BASE + roll_key).collect();
+        let metrics = Metrics::compute(&counts);
+        assert_eq!(metrics.total, u64::MAX);
+        assert!((compensated_sum(metrics.shares.iter().copied()) - 1.).abs() < 1e-15);
+        for pair in metrics.shares.windows(2) {
+            assert!(pair[0] <= pair[1]);
+        }
+        for pair in metrics.z_scores.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        // Faces a roll or two off even are a vanishing fraction of a standard deviation.
+        assert!(metrics.z_scores.iter().all(|z_score| z_score.abs() < 1e-7));
+        assert_eq!(metrics.winners, [19]);
+        assert_eq!(metrics.losers, [0]);
+    }
+
+    #[test]
+    fn z_scores_are_steady_as_huge_counts_grow() {
+        const BASE: u64 = 1 << 60;
+        let mut counts = vec![BASE; 20];
+        let mut previous = Metrics::compute(&counts).z_scores[0];
+        assert_eq!(previous, 0.);
+        for extra in 1..=5 {
+            counts[0] = BASE + extra * 1_000_000_000;
+            let z_score = Metrics::compute(&counts).z_scores[0];
+            assert!(z_score > previous, "{extra} billion extra rolls");
+            previous = z_score;
+        }
+    }
+
+    #[test]
+    fn compensated_sum_keeps_small_values() {
+        let values = [1e16, 1., -1e16, 1.];
+        assert_eq!(values.iter().sum::<f64>(), 1.);
+        assert_eq!(compensated_sum(values), 2.);
+    }
+
+    #[test]
+    fn chi_square_of_a_lopsided_coin() {
+        let metrics = Metrics::compute(&[60, 40]);
+        assert!((metrics.chi_square() - 4.).abs() < 1e-9);
+        assert!((metrics.p_value() - 0.0455).abs() < 1e-3);
+    }
+
+    #[test]
+    fn p_value_is_one_for_even_or_no_counts() {
+        assert_eq!(Metrics::compute(&[0; 20]).p_value(), 1.);
+        assert!((Metrics::compute(&[25; 20]).p_value() - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn p_value_matches_chi_square_tables() {
+        // (statistic, degrees of freedom, upper-tail chance)
+        for (chi_square, degrees_of_freedom, expected) in [
+            (3.841, 1, 0.05),
+            (6.635, 1, 0.01),
+            (11.070, 5, 0.05),
+            (30.144, 19, 0.05),
+            (36.191, 19, 0.01),
+        ] {
+            let p_value = chi_square_p_value(chi_square, degrees_of_freedom);
+            assert!(
+                (p_value - expected).abs() < 1e-4,
+                "{chi_square} with {degrees_of_freedom} degrees of freedom gave {p_value}"
+            );
+        }
+        assert_eq!(chi_square_p_value(0., 19), 1.);
+        assert!(chi_square_p_value(1_000., 19) < 1e-12);
+    }
+
+    #[test]
+    fn critical_value_matches_chi_square_tables() {
+        assert!((chi_square_critical_value(6) - 11.070).abs() < 0.05);
+        assert!((chi_square_critical_value(20) - 30.144).abs() < 0.05);
+    }
+
+    #[test]
+    fn moments_of_a_fair_d6() {
+        let (mean, variance) = weighted_moments(&[1.; 6]);
+        assert!((mean - 3.5).abs() < 1e-12);
+        assert!((variance - 35. / 12.).abs() < 1e-12);
+        assert_eq!(weighted_moments(&[0.; 6]), (0., 0.));
+    }
+
+    #[test]
+    fn normal_cdf_matches_known_values() {
+        assert!((normal_cdf(0.) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.) - 0.841_345).abs() < 1e-6);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+        for z in [0.3, 1.2, 2.5] {
+            assert!((normal_cdf(-z) + normal_cdf(z) - 1.).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn normal_shares_add_up_to_one() {
+        let shares = normal_shares(11, 6., 35. / 6.);
+        assert!((shares.iter().sum::<f64>() - 1.).abs() < 1e-12);
+        // Symmetric about the middle face.
+        for roll_key in 0..5 {
+            assert!((shares[roll_key] - shares[10 - roll_key]).abs() < 1e-7);
+        }
+        assert_eq!(normal_shares(3, 2., 0.), [0., 1., 0.]);
+    }
+
+    #[test]
+    fn ks_distance_of_known_cases() {
+        assert_eq!(ks_distance(&[10, 10, 10, 10], &[1.; 4]), 0.);
+        assert!((ks_distance(&[40, 0, 0, 0], &[1.; 4]) - 0.75).abs() < 1e-12);
+        assert!((ks_distance(&[0, 0, 0, 40], &[1.; 4]) - 0.75).abs() < 1e-12);
+        assert_eq!(ks_distance(&[0; 4], &[1.; 4]), 0.);
+    }
+
+    #[test]
+    fn recompute_stays_cheap() {
+        // Release builds take a few microseconds; the bound leaves room for debug builds.
+        const ITERATIONS: u32 = 200;
+        for sides in [20, 1_000] {
+            let counts: Vec<u64> = (0..sides).map(|roll_key| 1_000 + roll_key % 7).collect();
+            let mut metrics = Metrics::compute(&counts);
+            let started = Instant::now();
+            for _ in 0..ITERATIONS {
+                metrics.recompute(&counts);
+            }
+            let per_frame = started.elapsed() / ITERATIONS;
+            assert!(
+                per_frame.as_micros() < 500,
+                "{sides} faces took {per_frame:?}"
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "a benchmark; run it with `cargo test --release -- --ignored --nocapture`"]
+    fn per_frame_cost_of_20_and_1000_faces() {
+        const ITERATIONS: u32 = 100_000;
+        let per_frame = |sides: u64| {
+            let counts: Vec<u64> = (0..sides).map(|roll_key| 1_000 + roll_key % 7).collect();
+            let mut metrics = Metrics::compute(&counts);
+            // Warm up before timing.
+            for _ in 0..ITERATIONS / 10 {
+                metrics.recompute(std::hint::black_box(&counts));
+            }
+            let started = Instant::now();
+            for _ in 0..ITERATIONS {
+                metrics.recompute(std::hint::black_box(&counts));
+            }
+            started.elapsed() / ITERATIONS
+        };
+        let small = per_frame(20);
+        let large = per_frame(1_000);
+        println!("per frame: {small:?} for 20 faces, {large:?} for 1,000 faces");
+        assert!(small.as_nanos() < 1_000, "20 faces took {small:?}");
+        assert!(large.as_nanos() < 10_000, "1,000 faces took {large:?}");
+        // Fifty times the faces shouldn't cost much more than fifty times as much.
+        assert!(large < small * 100, "{large:?} against {small:?}");
+    }
+}
@@ -0,0 +1,116 @@
+//! Writing counts compactly, since plain numbers get unwieldy after billions of rolls.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Metric prefixes for each power of a thousand, starting from none.
+const PREFIXES: [&str; 7] = ["", "k", "M", "G", "T", "P", "E"];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Notation {
+    /// Every digit, such as 1234567.
+    #[default]
+    Plain,
+    /// A metric prefix, such as 1.2M.
+    Si,
+    /// Scientific notation, such as 1.2e6.
+    Sci,
+}
+
+impl Notation {
+    /// The notation after this one, wrapping around to the first.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Plain => Self::Si,
+            Self::Si => Self::Sci,
+            Self::Sci => Self::Plain,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::Si => "SI",
+            Self::Sci => "scientific",
+        }
+    }
+}
+
+/// Write `count` in `notation`, to about three significant figures unless it's plain. Counts
+/// below a thousand are always written out, as they're already short.
+pub fn format_count(count: u64, notation: Notation) -> String {
+    if count < 1000 {
+        return count.to_string();
+    }
+    match notation {
+        Notation::Plain => count.to_string(),
+        Notation::Si => {
+            let mut value = count as f64;
+            let mut prefix = 0;
+            // Move up a prefix before rounding could turn 999.5k into "1000k".
+            while value >= 999.5 && prefix + 1 < PREFIXES.len() {
+                value /= 1000.;
+                prefix += 1;
+            }
+            if value < 99.95 {
+                format!("{value:.1}{}", PREFIXES[prefix])
+            } else {
+                format!("{value:.0}{}", PREFIXES[prefix])
+            }
+        }
+        Notation::Sci => format!("{:.1e}", count as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_counts_are_written_out() {
+        for notation in [Notation::Plain, Notation::Si, Notation::Sci] {
+            assert_eq!(format_count(0, notation), "0");
+            assert_eq!(format_count(999, notation), "999");
+        }
+    }
+
+    #[test]
+    fn plain_keeps_every_digit() {
+        assert_eq!(format_count(1_234_567, Notation::Plain), "1234567");
+        assert_eq!(
+            format_count(u64::MAX, Notation::Plain),
+            "18446744073709551615"
+        );
+    }
+
+    #[test]
+    fn si_across_magnitudes() {
+        for (count, expected) in [
+            (1_000, "1.0k"),
+            (1_234, "1.2k"),
+            (99_949, "99.9k"),
+            (99_950, "100k"),
+            (999_499, "999k"),
+            (999_500, "1.0M"),
+            (1_234_567, "1.2M"),
+            (4_500_000_000, "4.5G"),
+            (1 << 60, "1.2E"),
+            (u64::MAX, "18.4E"),
+        ] {
+            assert_eq!(format_count(count, Notation::Si), expected, "{count}");
+        }
+    }
+
+    #[test]
+    fn sci_across_magnitudes() {
+        for (count, expected) in [
+            (1_000, "1.0e3"),
+            (1_234_567, "1.2e6"),
+            (4_500_000_000, "4.5e9"),
+            (u64::MAX, "1.8e19"),
+        ] {
+            assert_eq!(format_count(count, Notation::Sci), expected, "{count}");
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Dice of several sizes rolled side by side and compared by each face's share of its rolls,
+//! which for a fair die is `1/sides` whatever the size.
+
+use rand::Rng;
+
+/// The dice in a standard roleplaying set, smallest first.
+pub const STANDARD_SET: [usize; 6] = [4, 6, 8, 10, 12, 20];
+
+pub struct Overlay {
+    /// Counts for each face of each die, in the order the sizes were given.
+    pub dice: Vec<Vec<u64>>,
+    /// Rolls made of each die.
+    pub rolls: u64,
+    /// Whether to give each die its own histogram in a grid instead of overlaying them.
+    pub grid: bool,
+}
+
+impl Overlay {
+    pub fn new(sizes: &[usize]) -> Self {
+        Self {
+            dice: sizes.iter().map(|&sides| vec![0; sides.max(1)]).collect(),
+            rolls: 0,
+            grid: false,
+        }
+    }
+
+    /// A standard set of dice, each in its own cell of a grid.
+    pub fn set() -> Self {
+        Self {
+            grid: true,
+            ..Self::new(&STANDARD_SET)
+        }
+    }
+
+    /// Roll every die once.
+    pub fn roll(&mut self, rng: &mut impl Rng) {
+        for counts in &mut self.dice {
+            let roll_key = rng.gen_range(0..counts.len());
+            counts[roll_key] += 1;
+        }
+        self.rolls += 1;
+    }
+
+    pub fn clear(&mut self) {
+        for counts in &mut self.dice {
+            counts.fill(0);
+        }
+        self.rolls = 0;
+    }
+
+    /// Each face of the die at `index`'s fraction of its rolls.
+    pub fn shares(&self, index: usize) -> Vec<f64> {
+        let total = self.rolls.max(1) as f64;
+        self.dice[index]
+            .iter()
+            .map(|&count| count as f64 / total)
+            .collect()
+    }
+}
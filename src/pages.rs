@@ -0,0 +1,31 @@
+//! Full charts set aside in pixel-exact mode, so every pixel of the live chart can stay one
+//! roll however long the run goes.
+
+pub struct Pages {
+    /// Rolls of each face set aside with every page so far.
+    pub counts: Vec<u64>,
+    /// Pages set aside so far.
+    pub pages: u64,
+}
+
+impl Pages {
+    pub fn new(sides: usize) -> Self {
+        Self {
+            counts: vec![0; sides],
+            pages: 0,
+        }
+    }
+
+    /// Set the live `counts` aside as another page, leaving them empty to fill again.
+    pub fn turn(&mut self, counts: &mut [u64]) {
+        for (archived, count) in self.counts.iter_mut().zip(counts) {
+            *archived += std::mem::take(count);
+        }
+        self.pages += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.pages = 0;
+    }
+}
@@ -0,0 +1,150 @@
+//! Platform-specific window system setup.
+
+#[cfg(feature = "app")]
+use crate::cli::WmBackend;
+#[cfg(feature = "app")]
+use winit::event_loop::{EventLoop, EventLoopBuilder};
+
+/// Force the window system backend, where there's a choice of one.
+#[cfg(all(
+    feature = "app",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )
+))]
+pub fn select_wm_backend<T>(builder: &mut EventLoopBuilder<T>, backend: WmBackend) {
+    use winit::platform::wayland::EventLoopBuilderExtWayland as _;
+    use winit::platform::x11::EventLoopBuilderExtX11 as _;
+
+    match backend {
+        WmBackend::Auto => (),
+        WmBackend::Wayland => {
+            builder.with_wayland();
+        }
+        WmBackend::X11 => {
+            builder.with_x11();
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "app",
+    not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))
+))]
+pub fn select_wm_backend<T>(_builder: &mut EventLoopBuilder<T>, backend: WmBackend) {
+    if backend != WmBackend::Auto {
+        log::warn!("--wm-backend only applies on Linux and the BSDs; ignoring it");
+    }
+}
+
+/// Log which window system backend the event loop ended up using.
+#[cfg(all(
+    feature = "app",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )
+))]
+pub fn log_wm_backend<T>(event_loop: &EventLoop<T>) {
+    use winit::platform::wayland::EventLoopWindowTargetExtWayland as _;
+
+    if event_loop.is_wayland() {
+        log::info!("using the Wayland backend");
+    } else {
+        log::info!("using the X11 backend");
+    }
+}
+
+#[cfg(all(
+    feature = "app",
+    not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))
+))]
+pub fn log_wm_backend<T>(_event_loop: &EventLoop<T>) {
+    log::info!("using the native window system backend");
+}
+
+/// The desktop's accent color, if it has one we know how to ask for.
+#[cfg(target_os = "windows")]
+pub fn accent_color() -> Option<[u8; 4]> {
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Microsoft\Windows\DWM",
+            "/v",
+            "AccentColor",
+        ])
+        .output()
+        .ok()?;
+    // The value is a DWORD laid out as 0xAABBGGRR.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let hex = stdout
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("0x"))?;
+    let [red, green, blue, _] = u32::from_str_radix(hex, 16).ok()?.to_le_bytes();
+    Some([red, green, blue, 0xff])
+}
+
+/// The desktop's accent color, if it has one we know how to ask for.
+#[cfg(target_os = "macos")]
+pub fn accent_color() -> Option<[u8; 4]> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleAccentColor"])
+        .output()
+        .ok()?;
+    // The key is missing while the default, multicolor, is picked, which shows as blue.
+    let index = if output.status.success() {
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .ok()?
+    } else {
+        4
+    };
+    let color = match index {
+        -1 => [0x8c, 0x8c, 0x8c, 0xff],
+        0 => [0xff, 0x52, 0x57, 0xff],
+        1 => [0xf7, 0x82, 0x1b, 0xff],
+        2 => [0xff, 0xc6, 0x00, 0xff],
+        3 => [0x62, 0xba, 0x46, 0xff],
+        4 => [0x00, 0x7a, 0xff, 0xff],
+        5 => [0xa5, 0x50, 0xa7, 0xff],
+        6 => [0xf7, 0x4f, 0x9e, 0xff],
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// The desktop's accent color, if it has one we know how to ask for.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn accent_color() -> Option<[u8; 4]> {
+    None
+}
+
+/// Ring the terminal's bell.
+#[cfg(feature = "audio")]
+pub fn beep() {
+    use std::io::Write as _;
+    let mut stderr = std::io::stderr();
+    if let Err(err) = stderr.write_all(b"\x07").and_then(|()| stderr.flush()) {
+        log::warn!("could not beep: {err}");
+    }
+}
@@ -0,0 +1,113 @@
+//! Speeding the simulation up over time, from a few rolls a second to millions, like a
+//! time-lapse.
+
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+pub struct Ramp {
+    /// Rolls per second at the start.
+    from: f64,
+    /// Rolls per second at the end, held from then on.
+    to: f64,
+    duration: Duration,
+    /// How far into the ramp we are, not counting time spent paused.
+    elapsed: Duration,
+    /// When rolls were last made, or `None` if the clock is stopped.
+    last_tick: Option<Instant>,
+    /// Fraction of a roll carried over between frames, so slow rates still roll.
+    remainder: f64,
+}
+
+impl Ramp {
+    /// Parse a ramp such as `10..1000000 over 60s`, in rolls per second. The duration can be
+    /// given in `ms`, `s`, or `m`.
+    pub fn parse(spec: &str) -> Result<Self, InvalidRamp> {
+        let invalid = || InvalidRamp(spec.to_string());
+        let (rates, duration) = spec.split_once(" over ").ok_or_else(invalid)?;
+        let (from, to) = rates.split_once("..").ok_or_else(invalid)?;
+        let from: f64 = from.trim().parse().map_err(|_| invalid())?;
+        let to: f64 = to.trim().parse().map_err(|_| invalid())?;
+        let duration = parse_duration(duration.trim()).ok_or_else(invalid)?;
+        // Rates are interpolated on a log scale, which has no room for zero.
+        if !(from > 0. && to > 0. && from.is_finite() && to.is_finite()) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            from,
+            to,
+            duration,
+            elapsed: Duration::ZERO,
+            last_tick: None,
+            remainder: 0.,
+        })
+    }
+
+    /// Rolls per second at this point in the ramp, moving from the start rate to the end rate
+    /// by the same factor every second.
+    pub fn rate(&self) -> f64 {
+        let progress = if self.duration.is_zero() {
+            1.
+        } else {
+            (self.elapsed.as_secs_f64() / self.duration.as_secs_f64()).min(1.)
+        };
+        self.from * (self.to / self.from).powf(progress)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Move the ramp along to `now` and return how many rolls are due since it last did. The
+    /// first tick after starting or stopping only starts the clock.
+    pub fn tick(&mut self, now: Instant) -> u64 {
+        let Some(last_tick) = self.last_tick.replace(now) else {
+            return 0;
+        };
+        let delta = now.saturating_duration_since(last_tick);
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+        let rolls = self.rate() * delta.as_secs_f64() + self.remainder;
+        self.remainder = rolls.fract();
+        rolls as u64
+    }
+
+    /// Stop the clock, such as while paused, so the ramp picks up where it left off.
+    pub fn stop(&mut self) {
+        self.last_tick = None;
+    }
+
+    /// Go back to the start rate.
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.last_tick = None;
+        self.remainder = 0.;
+    }
+}
+
+fn parse_duration(duration: &str) -> Option<Duration> {
+    let (number, unit_seconds) = if let Some(number) = duration.strip_suffix("ms") {
+        (number, 0.001)
+    } else if let Some(number) = duration.strip_suffix('s') {
+        (number, 1.)
+    } else if let Some(number) = duration.strip_suffix('m') {
+        (number, 60.)
+    } else {
+        return None;
+    };
+    let number: f64 = number.trim().parse().ok()?;
+    Duration::try_from_secs_f64(number * unit_seconds).ok()
+}
+
+#[derive(Debug)]
+pub struct InvalidRamp(pub String);
+
+impl std::fmt::Display for InvalidRamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid ramp {:?}; expected rolls per second and a duration, such as \"10..1000000 over 60s\"",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidRamp {}
@@ -0,0 +1,199 @@
+//! Saving rendered frames as PNGs: every one, numbered, for turning a run into a video; one
+//! every so often for a time-lapse; or single stills as notable moments happen.
+//!
+//! Frames are copied out only once `World::draw` has finished with them, then encoded on a
+//! separate thread so recording doesn't hold up rendering.
+
+use crate::summary;
+use log::{error, info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Frames that can be waiting to be encoded before capturing has to wait for the encoder.
+const QUEUE_LENGTH: usize = 8;
+
+/// Time-lapse frames that can be waiting to be encoded; any more are skipped instead.
+const TIMELAPSE_QUEUE_LENGTH: usize = 2;
+
+/// Stills that can be waiting to be encoded before capturing has to wait for the encoder.
+const STILLS_QUEUE_LENGTH: usize = 4;
+
+struct Capture {
+    path: PathBuf,
+    width: u32,
+    height: u32,
+    frame: Vec<u8>,
+}
+
+pub struct Recorder {
+    send: SyncSender<Capture>,
+    encoder: JoinHandle<()>,
+    dir: PathBuf,
+    next_index: u64,
+}
+
+impl Recorder {
+    /// Start saving frames into `dir`, creating it if needed.
+    pub fn spawn(dir: &Path) -> Result<Self, std::io::Error> {
+        std::fs::create_dir_all(dir)?;
+        let (send, encoder) = spawn_encoder(QUEUE_LENGTH);
+        Ok(Self {
+            send,
+            encoder,
+            dir: dir.to_path_buf(),
+            next_index: 0,
+        })
+    }
+
+    /// Queue a copy of a fully drawn `frame` of `width` by `height` pixels to be saved. Waits if
+    /// the encoder has fallen behind, so no frame is dropped.
+    pub fn capture(&mut self, frame: &[u8], width: u32, height: u32) {
+        let capture = Capture {
+            path: frame_path(&self.dir, self.next_index),
+            width,
+            height,
+            frame: frame.to_vec(),
+        };
+        self.next_index += 1;
+        // The encoder only stops if its thread panicked, which it has already reported.
+        let _ = self.send.send(capture);
+    }
+
+    /// Wait for every frame still queued to be written.
+    pub fn finish(self) {
+        drop(self.send);
+        // A panic on the encoder's thread has already been reported.
+        let _ = self.encoder.join();
+    }
+}
+
+/// Saves a frame every so often, skipping any the encoder isn't ready for so a slow disk can't
+/// hold up rendering. Frames are numbered one after another, however many are skipped.
+pub struct Timelapse {
+    send: SyncSender<Capture>,
+    encoder: JoinHandle<()>,
+    dir: PathBuf,
+    interval: Duration,
+    next_index: u64,
+    /// When the next frame is to be saved, or `None` to save the next one drawn.
+    due: Option<Instant>,
+    /// Frames that came due while the encoder was still busy.
+    skipped: u64,
+}
+
+impl Timelapse {
+    /// Start saving a frame into `dir` every `interval`, creating it if needed. Fails at once
+    /// if nothing can be written there, rather than after the first frame.
+    pub fn spawn(dir: &Path, interval: Duration) -> Result<Self, std::io::Error> {
+        std::fs::create_dir_all(dir)?;
+        check_writable(dir)?;
+        let (send, encoder) = spawn_encoder(TIMELAPSE_QUEUE_LENGTH);
+        Ok(Self {
+            send,
+            encoder,
+            dir: dir.to_path_buf(),
+            interval,
+            next_index: 0,
+            due: None,
+            skipped: 0,
+        })
+    }
+
+    /// Queue a copy of a fully drawn `frame` of `width` by `height` pixels to be saved if one
+    /// is due by `now`, or count it as skipped if the encoder is behind.
+    pub fn capture(&mut self, frame: &[u8], width: u32, height: u32, now: Instant) {
+        if self.due.is_some_and(|due| now < due) {
+            return;
+        }
+        self.due = Some(now + self.interval);
+        let capture = Capture {
+            path: frame_path(&self.dir, self.next_index),
+            width,
+            height,
+            frame: frame.to_vec(),
+        };
+        match self.send.try_send(capture) {
+            Ok(()) => self.next_index += 1,
+            Err(TrySendError::Full(_)) => self.skipped += 1,
+            // The encoder only stops if its thread panicked, which it has already reported.
+            Err(TrySendError::Disconnected(_)) => (),
+        }
+    }
+
+    /// Save `frame` as the last one, waiting for it and any still queued to be written, then
+    /// report how many were skipped.
+    pub fn finish(self, frame: &[u8], width: u32, height: u32) {
+        let capture = Capture {
+            path: frame_path(&self.dir, self.next_index),
+            width,
+            height,
+            frame: frame.to_vec(),
+        };
+        let saved = self.next_index + u64::from(self.send.send(capture).is_ok());
+        drop(self.send);
+        if self.encoder.join().is_err() {
+            return;
+        }
+        if self.skipped > 0 {
+            warn!(
+                "saved {saved} time-lapse frames, skipping {} the disk couldn't keep up with",
+                self.skipped
+            );
+        } else {
+            info!("saved {saved} time-lapse frames");
+        }
+    }
+}
+
+/// Saves single frames to paths chosen as they're captured.
+pub struct Stills {
+    send: SyncSender<Capture>,
+}
+
+impl Stills {
+    pub fn spawn() -> Self {
+        let (send, _) = spawn_encoder(STILLS_QUEUE_LENGTH);
+        Self { send }
+    }
+
+    /// Queue a copy of a fully drawn `frame` of `width` by `height` pixels to be saved to
+    /// `path`. Waits if the encoder has fallen behind, which stills are too rare to do often.
+    pub fn capture(&mut self, path: PathBuf, frame: &[u8], width: u32, height: u32) {
+        info!("capturing {}", path.display());
+        let capture = Capture {
+            path,
+            width,
+            height,
+            frame: frame.to_vec(),
+        };
+        // The encoder only stops if its thread panicked, which it has already reported.
+        let _ = self.send.send(capture);
+    }
+}
+
+/// Start a thread saving each frame sent to it, with room for `queue_length` to wait.
+fn spawn_encoder(queue_length: usize) -> (SyncSender<Capture>, JoinHandle<()>) {
+    let (send, receive) = mpsc::sync_channel::<Capture>(queue_length);
+    let encoder = thread::spawn(move || {
+        for capture in receive {
+            let saved = summary::save(&capture.path, capture.width, capture.height, &capture.frame);
+            if let Err(err) = saved {
+                error!("could not record {}: {err}", capture.path.display());
+            }
+        }
+    });
+    (send, encoder)
+}
+
+/// Write and remove a file in `dir`, to find out whether frames can be saved there.
+fn check_writable(dir: &Path) -> Result<(), std::io::Error> {
+    let path = dir.join(".d20-write-check");
+    std::fs::write(&path, [])?;
+    std::fs::remove_file(&path)
+}
+
+fn frame_path(dir: &Path, index: u64) -> PathBuf {
+    dir.join(format!("frame-{index:06}.png"))
+}
@@ -0,0 +1,204 @@
+//! A line-based interface for driving the visualizer from scripts, over TCP or, with the
+//! `control-socket` feature, a Unix socket.
+//!
+//! Each line sent is one of:
+//!
+//! - a face number, such as `17`, to record that roll
+//! - `roll` followed by face numbers, such as `roll 17 3 20`, to record several at once
+//! - `reset`, `pause`, or `resume`
+//! - `rate` followed by rolls per frame, such as `rate 5000`
+//! - `seed` followed by a seed, such as `seed 42`, to roll a reproducible sequence from then on
+//! - `stats`, which replies with the current statistics as a single line of JSON
+//!
+//! Every line gets a reply: `OK`, the statistics, or `ERR` followed by what was wrong.
+
+use log::{info, warn};
+use std::io::{BufRead as _, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+/// Longest to wait for the event loop to answer a `stats` request.
+const STATS_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub enum Command {
+    /// Zero-based face indexes to record.
+    Rolls(Vec<usize>),
+    Reset,
+    Pause,
+    Resume,
+    /// Rolls to make each frame.
+    Rate(u64),
+    /// Seed to roll from, and reset to.
+    Seed(u64),
+    /// Send the statistics, as a line of JSON, to the enclosed sender.
+    Stats(Sender<String>),
+}
+
+/// Start accepting clients on `address`, one at a time, passing their commands to `send` until
+/// it returns `false`.
+pub fn listen(
+    address: SocketAddr,
+    sides: usize,
+    send: impl Fn(Command) -> bool + Send + 'static,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    info!("listening for rolls on {address}");
+    std::thread::spawn(move || {
+        let describe = |stream: &std::net::TcpStream| {
+            stream
+                .peer_addr()
+                .map_or_else(|_| "unknown".to_string(), |peer| peer.to_string())
+        };
+        accept(listener.incoming(), describe, sides, send);
+    });
+    Ok(())
+}
+
+/// Start accepting clients on a Unix socket at `path`, one at a time, passing their commands to
+/// `send` until it returns `false`. A socket left at `path` by an earlier run is replaced, but
+/// anything else there is left alone.
+#[cfg(all(unix, feature = "control-socket"))]
+pub fn listen_unix(
+    path: &std::path::Path,
+    sides: usize,
+    send: impl Fn(Command) -> bool + Send + 'static,
+) -> std::io::Result<()> {
+    use std::os::unix::fs::FileTypeExt as _;
+    use std::os::unix::net::UnixListener;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+        // Binding fails on anything else, saying what's in the way.
+        Ok(_) => (),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => (),
+        Err(err) => return Err(err),
+    }
+    let listener = UnixListener::bind(path)?;
+    info!("listening for commands on {}", path.display());
+    let name = path.display().to_string();
+    std::thread::spawn(move || {
+        accept(listener.incoming(), |_| name.clone(), sides, send);
+    });
+    Ok(())
+}
+
+/// Serve each client from `incoming` in turn, named in the log by `describe`, until the event
+/// loop is gone.
+fn accept<S>(
+    incoming: impl Iterator<Item = std::io::Result<S>>,
+    describe: impl Fn(&S) -> String,
+    sides: usize,
+    send: impl Fn(Command) -> bool,
+) where
+    for<'a> &'a S: Read + Write,
+{
+    for stream in incoming {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                warn!("could not accept a remote client: {err}");
+                continue;
+            }
+        };
+        let peer = describe(&stream);
+        info!("remote client {peer} connected");
+        match serve(&stream, &stream, sides, &send) {
+            Ok(true) => info!("remote client {peer} disconnected"),
+            Ok(false) => return,
+            Err(err) => warn!("lost remote client {peer}: {err}"),
+        }
+    }
+}
+
+/// Answer the lines read from one client until it disconnects, returning `false` if the event
+/// loop is gone.
+fn serve(
+    reader: impl Read,
+    mut writer: impl Write,
+    sides: usize,
+    send: &impl Fn(Command) -> bool,
+) -> std::io::Result<bool> {
+    for line in BufReader::new(reader).split(b'\n') {
+        // Anything that isn't UTF-8 fails to parse, so it gets an error like any other bad line.
+        let reply = match parse(&String::from_utf8_lossy(&line?), sides) {
+            Ok(Line::Stats) => {
+                let (sender, receiver) = mpsc::channel();
+                if !send(Command::Stats(sender)) {
+                    return Ok(false);
+                }
+                receiver
+                    .recv_timeout(STATS_TIMEOUT)
+                    .unwrap_or_else(|_| "ERR no statistics available".to_string())
+            }
+            Ok(Line::Command(command)) => {
+                if !send(command) {
+                    return Ok(false);
+                }
+                "OK".to_string()
+            }
+            Err(reason) => format!("ERR {reason}"),
+        };
+        writeln!(writer, "{reply}")?;
+    }
+    Ok(true)
+}
+
+/// What a client asked for in one line.
+enum Line {
+    Command(Command),
+    /// Statistics, which need a channel to come back on.
+    Stats,
+}
+
+/// Parse one line from a client, explaining what's wrong with it if it can't be understood.
+fn parse(line: &str, sides: usize) -> Result<Line, String> {
+    let mut words = line.split_whitespace();
+    let Some(first) = words.next() else {
+        return Err("empty line".to_string());
+    };
+    if first == "roll" {
+        let rolls = words
+            .map(|word| parse_face(word, sides))
+            .collect::<Result<Vec<_>, _>>()?;
+        if rolls.is_empty() {
+            return Err("expected at least one face after roll".to_string());
+        }
+        return Ok(Line::Command(Command::Rolls(rolls)));
+    }
+    if first == "rate" || first == "seed" {
+        let Some(word) = words.next() else {
+            return Err(format!("expected a number after {first}"));
+        };
+        let number = word
+            .parse::<u64>()
+            .map_err(|_| format!("expected a number after {first}, found {word:?}"))?;
+        if let Some(extra) = words.next() {
+            return Err(format!("unexpected {extra:?} after {first} {word}"));
+        }
+        return Ok(Line::Command(if first == "rate" {
+            Command::Rate(number)
+        } else {
+            Command::Seed(number)
+        }));
+    }
+    let line = match first {
+        "reset" => Line::Command(Command::Reset),
+        "pause" => Line::Command(Command::Pause),
+        "resume" => Line::Command(Command::Resume),
+        "stats" => Line::Stats,
+        word => Line::Command(Command::Rolls(vec![parse_face(word, sides)?])),
+    };
+    match words.next() {
+        Some(extra) => Err(format!("unexpected {extra:?} after {first:?}")),
+        None => Ok(line),
+    }
+}
+
+/// Parse a face number from 1 to `sides` into its zero-based index.
+fn parse_face(word: &str, sides: usize) -> Result<usize, String> {
+    match word.parse::<usize>() {
+        Ok(face @ 1..) if face <= sides => Ok(face - 1),
+        _ => Err(format!("expected a face from 1 to {sides}, found {word:?}")),
+    }
+}
@@ -0,0 +1,102 @@
+//! A plain-text account of a run with everything needed to reproduce and interpret it, for
+//! attaching to a bug report about a suspicious random number generator.
+
+use crate::checksum::RollHash;
+use crate::history;
+use crate::metrics::Metrics;
+use crate::summary;
+use std::path::Path;
+
+/// Where reports are written when no path is given.
+pub const DEFAULT_PATH: &str = "d20-report.txt";
+
+/// How a run was set up and what it rolled.
+pub struct Run<'a> {
+    /// Seed the random number generator started from, if it was seeded.
+    pub seed: Option<u64>,
+    /// Where the rolls came from.
+    pub source: &'a str,
+    /// Relative chance of each face, if the die was loaded.
+    pub weights: Option<&'a [f64]>,
+    /// Faces, numbered from `first_face`, that were never rolled.
+    pub excluded: Vec<usize>,
+    pub stickiness: f64,
+    pub labels: &'a [String],
+    /// Number the first face goes by.
+    pub first_face: usize,
+    /// Rolls of each face since the last reset.
+    pub counts: &'a [u64],
+    /// Hash of every roll since the last reset, in order.
+    pub checksum: RollHash,
+    /// When the report was made, in seconds since the Unix epoch.
+    pub unix_seconds: u64,
+}
+
+/// Write the report on `run` to `path`.
+pub fn save(path: &Path, run: &Run) -> std::io::Result<()> {
+    std::fs::write(path, render(run))
+}
+
+pub fn render(run: &Run) -> String {
+    let metrics = Metrics::compute(run.counts);
+    let sides = run.counts.len();
+    let join = |values: Vec<String>| values.join(", ");
+    let mut lines = vec![
+        format!(
+            "{} {} reproducibility report",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        ),
+        format!("Generated {} UTC", history::format_time(run.unix_seconds)),
+        String::new(),
+        "Setup".to_string(),
+        match run.seed {
+            Some(seed) => format!("  Seed: {seed}"),
+            None => "  Seed: none; seeded from entropy, so the rolls can't be repeated".to_string(),
+        },
+        format!("  Source: {}", run.source),
+        format!("  Sides: {sides}"),
+        match run.weights {
+            Some(weights) => format!(
+                "  Weights: {}",
+                join(weights.iter().map(f64::to_string).collect())
+            ),
+            None => "  Weights: fair".to_string(),
+        },
+        if run.excluded.is_empty() {
+            "  Excluded faces: none".to_string()
+        } else {
+            format!(
+                "  Excluded faces: {}",
+                join(run.excluded.iter().map(usize::to_string).collect())
+            )
+        },
+        format!("  Stickiness: {}", run.stickiness),
+        String::new(),
+        "Results".to_string(),
+        format!("  Total rolls: {}", metrics.total),
+        format!(
+            "  Roll checksum: {} (FNV-1a over each zero-based face)",
+            run.checksum
+        ),
+    ];
+    for (roll_key, &count) in run.counts.iter().enumerate() {
+        lines.push(format!(
+            "  {:>2} ({}): {count} ({:.3}%)",
+            roll_key + run.first_face,
+            run.labels[roll_key],
+            metrics.shares[roll_key] * 100.
+        ));
+    }
+    if metrics.total > 0 {
+        lines.push(format!(
+            "  Chi-square: {:.3} with {} degrees of freedom",
+            metrics.chi_square(),
+            sides.saturating_sub(1)
+        ));
+        lines.push(format!("  p-value: {:.6}", metrics.p_value()));
+        lines.push(format!("  Verdict: {}", summary::verdict(&metrics)));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
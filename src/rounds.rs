@@ -0,0 +1,60 @@
+//! Many short experiments run back to back, charting how their chi-square statistics are
+//! distributed. For a fair die, that distribution should itself follow the chi-square
+//! distribution.
+
+use crate::metrics;
+
+/// Width of each histogram bucket, in units of the chi-square statistic.
+const BUCKET_WIDTH: f64 = 1.;
+
+pub struct Rounds {
+    /// Rolls made in each round.
+    pub rolls: u64,
+    /// Degrees of freedom of each round's chi-square statistic.
+    pub degrees_of_freedom: usize,
+    /// Rounds whose statistic fell in each bucket; the last also takes everything beyond it.
+    pub buckets: Vec<u64>,
+    pub completed: u64,
+}
+
+impl Rounds {
+    pub fn new(rolls: u64, sides: usize) -> Self {
+        let degrees_of_freedom = sides.saturating_sub(1).max(1);
+        // Three times the mean leaves only a sliver of the distribution in the last bucket.
+        let buckets = (3. * degrees_of_freedom as f64 / BUCKET_WIDTH).ceil() as usize;
+        Self {
+            rolls,
+            degrees_of_freedom,
+            buckets: vec![0; buckets.max(1)],
+            completed: 0,
+        }
+    }
+
+    pub fn record(&mut self, chi_square: f64) {
+        let bucket = ((chi_square / BUCKET_WIDTH) as usize).min(self.buckets.len() - 1);
+        self.buckets[bucket] += 1;
+        self.completed += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.fill(0);
+        self.completed = 0;
+    }
+
+    /// The largest statistic the histogram covers before its last bucket.
+    pub fn range(&self) -> f64 {
+        self.buckets.len() as f64 * BUCKET_WIDTH
+    }
+
+    /// How many of the completed rounds the chi-square distribution puts in `bucket`.
+    pub fn expected(&self, bucket: usize) -> f64 {
+        let low = bucket as f64 * BUCKET_WIDTH;
+        let upper_tail = |x| metrics::chi_square_p_value(x, self.degrees_of_freedom);
+        let share = if bucket + 1 == self.buckets.len() {
+            upper_tail(low)
+        } else {
+            upper_tail(low) - upper_tail(low + BUCKET_WIDTH)
+        };
+        share * self.completed as f64
+    }
+}
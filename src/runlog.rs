@@ -0,0 +1,183 @@
+//! A plain-text summary of each run, written however it ends, so there's a record of what
+//! happened even when nothing else was saved. The last few runs are kept by rotating older
+//! logs to numbered names, such as `d20-run.log.1` for the one before.
+
+use crate::metrics::{self, Metrics};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Where the latest run is logged, relative to the working directory.
+pub const PATH: &str = "d20-run.log";
+
+/// How many runs' logs to keep, counting the latest.
+pub const KEEP: usize = 10;
+
+pub struct Run<'a> {
+    /// What ended the run, such as "window closed".
+    pub ending: &'a str,
+    pub duration: Duration,
+    pub labels: &'a [String],
+    pub counts: &'a [u64],
+    /// Number the first face is counted as in the mean.
+    pub first_face: usize,
+    /// Anything that makes the counts less trustworthy, one line each.
+    pub warnings: Vec<String>,
+    pub unix_seconds: u64,
+}
+
+/// Move older logs out of the way, then write `run` to `path`.
+pub fn save(path: &Path, run: &Run) -> std::io::Result<()> {
+    rotate(path, KEEP)?;
+    std::fs::write(path, render(run))
+}
+
+pub fn render(run: &Run) -> String {
+    let metrics = Metrics::compute(run.counts);
+    let mut log = String::new();
+    let _ = writeln!(log, "Ended: {} at {}", run.ending, run.unix_seconds);
+    let _ = writeln!(log, "Duration: {:.1}s", run.duration.as_secs_f64());
+    let _ = writeln!(log, "Total rolls: {}", metrics.total);
+    for (roll_key, count) in run.counts.iter().enumerate() {
+        let label = run.labels.get(roll_key).map_or("", String::as_str);
+        let _ = writeln!(log, "  {label:>4}: {count}");
+    }
+    if metrics.total > 0 {
+        let _ = writeln!(log, "Mean roll: {:.3}", metrics.mean_face(run.first_face));
+        let _ = writeln!(
+            log,
+            "Chi-square: {:.2} (5% at {:.2})",
+            metrics.chi_square(),
+            metrics::chi_square_critical_value(run.counts.len())
+        );
+        let _ = writeln!(log, "p-value: {:.4}", metrics.p_value());
+    }
+    if run.warnings.is_empty() {
+        log.push_str("Warnings: none\n");
+    } else {
+        log.push_str("Warnings:\n");
+        for warning in &run.warnings {
+            let _ = writeln!(log, "  {warning}");
+        }
+    }
+    log
+}
+
+/// Shift `path` to `path.1`, `path.1` to `path.2`, and so on, dropping whatever would go
+/// past `keep` files in all. Missing files are skipped.
+pub fn rotate(path: &Path, keep: usize) -> std::io::Result<()> {
+    if keep == 0 {
+        return Ok(());
+    }
+    ignore_missing(std::fs::remove_file(numbered(path, keep - 1)))?;
+    for index in (1..keep - 1).rev() {
+        ignore_missing(std::fs::rename(
+            numbered(path, index),
+            numbered(path, index + 1),
+        ))?;
+    }
+    if keep > 1 {
+        ignore_missing(std::fs::rename(path, numbered(path, 1)))?;
+    }
+    Ok(())
+}
+
+/// `path` with `.index` added, or `path` itself for 0.
+fn numbered(path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return path.to_path_buf();
+    }
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+fn ignore_missing(result: std::io::Result<()>) -> std::io::Result<()> {
+    match result {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh, empty directory for `test` under the system's temporary one.
+    fn scratch(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("d20-runlog-{}-{test}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn run(ending: &str) -> Run<'_> {
+        Run {
+            ending,
+            duration: Duration::from_secs(1),
+            labels: &[],
+            counts: &[],
+            first_face: 1,
+            warnings: Vec::new(),
+            unix_seconds: 0,
+        }
+    }
+
+    fn ending(path: &Path) -> String {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn keeps_only_the_latest_runs() {
+        let dir = scratch("keep");
+        let path = dir.join(PATH);
+        for index in 0..KEEP + 3 {
+            save(&path, &run(&format!("run {index}"))).unwrap();
+        }
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), KEEP);
+        assert_eq!(ending(&path), format!("Ended: run {} at 0", KEEP + 2));
+        for index in 1..KEEP {
+            assert_eq!(
+                ending(&numbered(&path, index)),
+                format!("Ended: run {} at 0", KEEP + 2 - index)
+            );
+        }
+        // The oldest were dropped rather than kept under a higher number.
+        assert!(!numbered(&path, KEEP).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotates_around_gaps() {
+        let dir = scratch("gaps");
+        let path = dir.join(PATH);
+        std::fs::write(&path, "latest").unwrap();
+        std::fs::write(numbered(&path, 2), "oldest").unwrap();
+        rotate(&path, 3).unwrap();
+        assert!(!path.exists());
+        assert_eq!(
+            std::fs::read_to_string(numbered(&path, 1)).unwrap(),
+            "latest"
+        );
+        // Already the last kept, so it's removed instead of moving to `.3`.
+        assert!(!numbered(&path, 2).exists());
+        assert!(!numbered(&path, 3).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeping_one_overwrites_the_last() {
+        let dir = scratch("one");
+        let path = dir.join(PATH);
+        std::fs::write(&path, "old").unwrap();
+        rotate(&path, 1).unwrap();
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
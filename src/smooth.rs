@@ -0,0 +1,86 @@
+//! A smoothed curve over the bars, which shows the shape of a distribution with many buckets
+//! before there are enough rolls for the bars themselves to settle.
+
+/// Fewest buckets worth smoothing. With fewer, the kernel spans so much of the die that the
+/// curve flattens real differences between faces.
+pub const MIN_BUCKETS: usize = 12;
+
+/// Standard deviation of the kernel, in buckets, widening slowly as buckets are added so the
+/// curve stays about as smooth whatever their number.
+pub fn bandwidth(buckets: usize) -> f64 {
+    (buckets as f64).sqrt() / 4.
+}
+
+/// Each bucket's share of `counts` after blurring them with a Gaussian kernel of `bandwidth`
+/// buckets. Near the ends, the kernel is renormalized over the buckets that exist, so the edges
+/// aren't pulled down by buckets off the die.
+pub fn smooth(counts: &[u64], bandwidth: f64) -> Vec<f64> {
+    let total = counts.iter().sum::<u64>();
+    if total == 0 {
+        return vec![0.; counts.len()];
+    }
+    // Past three standard deviations, the kernel adds next to nothing.
+    let reach = (bandwidth * 3.).ceil() as usize;
+    (0..counts.len())
+        .map(|center| {
+            let first = center.saturating_sub(reach);
+            let last = (center + reach).min(counts.len() - 1);
+            let mut weighted = 0.;
+            let mut weight_sum = 0.;
+            for (bucket, &count) in counts.iter().enumerate().take(last + 1).skip(first) {
+                let distance = (bucket as f64 - center as f64) / bandwidth;
+                let weight = (-distance * distance / 2.).exp();
+                weighted += weight * count as f64;
+                weight_sum += weight;
+            }
+            weighted / weight_sum / total as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_single_roll_spreads_into_the_kernel() {
+        let mut counts = [0; 41];
+        counts[20] = 1;
+        let bandwidth = 2.;
+        let smoothed = smooth(&counts, bandwidth);
+        // Clear of the ends, every bucket's weights are the same, and add up to the one roll.
+        assert!((smoothed.iter().sum::<f64>() - 1.).abs() < 1e-12);
+        for (bucket, share) in smoothed.iter().enumerate() {
+            let distance = (bucket as f64 - 20.) / bandwidth;
+            if distance.abs() <= 3. {
+                let expected = smoothed[20] * (-distance * distance / 2.).exp();
+                assert!((share - expected).abs() < 1e-12, "bucket {bucket}");
+            } else {
+                assert_eq!(*share, 0., "bucket {bucket}");
+            }
+        }
+        // Symmetric about the roll, and highest at it.
+        for offset in 1..=20 {
+            assert_eq!(smoothed[20 - offset], smoothed[20 + offset]);
+            assert!(smoothed[20] > smoothed[20 + offset]);
+        }
+    }
+
+    #[test]
+    fn flat_counts_stay_flat() {
+        for buckets in [MIN_BUCKETS, 20, 100, 1_000] {
+            let counts = vec![7; buckets];
+            let smoothed = smooth(&counts, bandwidth(buckets));
+            // Even at the ends, which only have buckets on one side.
+            for share in smoothed {
+                assert!((share * buckets as f64 - 1.).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn nothing_rolled_is_nothing() {
+        assert_eq!(smooth(&[0; 20], bandwidth(20)), [0.; 20]);
+        assert_eq!(smooth(&[], 1.), [0.; 0]);
+    }
+}
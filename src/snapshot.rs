@@ -0,0 +1,80 @@
+//! Named copies of the distribution, kept on disk for later comparison.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Where snapshots are saved, relative to the working directory.
+pub const PATH: &str = "d20-snapshots.json";
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Snapshot {
+    pub name: String,
+    /// Rolls made when the snapshot was taken.
+    pub total_rolls: u64,
+    /// Count for each face, as displayed when the snapshot was taken.
+    pub counts: Vec<u64>,
+    /// Whether the counts were imported for comparison rather than saved, so they're never
+    /// written back.
+    #[serde(skip)]
+    pub imported: bool,
+}
+
+impl Snapshot {
+    /// Each face's fraction of the snapshot's counts.
+    pub fn shares(&self) -> Vec<f64> {
+        let total: u64 = self.counts.iter().sum();
+        self.counts
+            .iter()
+            .map(|&count| {
+                if total == 0 {
+                    0.
+                } else {
+                    count as f64 / total as f64
+                }
+            })
+            .collect()
+    }
+}
+
+/// Load the snapshots saved at `path`, if there are any.
+pub fn load(path: &Path) -> Result<Vec<Snapshot>, Error> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(Error::Io(err)),
+    };
+    serde_json::from_str(&contents).map_err(Error::Json)
+}
+
+pub fn save(path: &Path, snapshots: &[Snapshot]) -> Result<(), Error> {
+    let saved: Vec<&Snapshot> = snapshots
+        .iter()
+        .filter(|snapshot| !snapshot.imported)
+        .collect();
+    let contents = serde_json::to_string_pretty(&saved).map_err(Error::Json)?;
+    std::fs::write(path, contents).map_err(Error::Io)
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "could not access snapshots"),
+            Self::Json(_) => write!(f, "could not parse snapshots"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Json(err) => Some(err),
+        }
+    }
+}
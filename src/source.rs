@@ -0,0 +1,231 @@
+//! Rolls that come from somewhere other than the built-in random number generator.
+
+use log::{info, warn};
+use rand::{Rng as _, RngCore};
+use std::io::BufRead as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Where rolls can be taken from while running, in the order they're cycled through.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// The built-in random number generator.
+    Rng,
+    Stdin,
+}
+
+impl Kind {
+    pub const ALL: [Self; 2] = [Self::Rng, Self::Stdin];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rng => "rng",
+            Self::Stdin => "stdin",
+        }
+    }
+
+    /// The kind after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&kind| kind == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Start taking rolls of a die with `sides` faces from this kind of source, or `None` for
+    /// the built-in generator, which isn't a `RollSource`.
+    pub fn spawn(self, sides: usize) -> Option<Box<dyn RollSource>> {
+        match self {
+            Self::Rng => None,
+            Self::Stdin => Some(Box::new(StdinSource::spawn(sides))),
+        }
+    }
+}
+
+pub struct Roll {
+    /// Zero-based face index.
+    pub roll_key: usize,
+    /// When the roll reached us.
+    pub arrived: Instant,
+}
+
+pub trait RollSource {
+    fn name(&self) -> &'static str;
+
+    /// Whether rolls arrive from outside in real time, making their timing worth tracking,
+    /// rather than being generated or replayed on demand.
+    fn is_external(&self) -> bool;
+
+    /// Append every roll that's available right now to `rolls`, without blocking.
+    fn poll(&mut self, rolls: &mut Vec<Roll>);
+
+    /// How many values that weren't faces have been received since this was last called.
+    fn take_invalid(&mut self) -> u64 {
+        0
+    }
+
+    /// Stop any background thread, waiting up to `timeout` for it to finish. Rolls it sent
+    /// before stopping can still be polled; any it reads afterward are thrown away.
+    fn stop(&mut self, _timeout: Duration) {}
+}
+
+/// Wait up to `timeout` for `thread` to finish, returning whether it did. A thread that's still
+/// running is left to finish on its own.
+fn join_within(thread: JoinHandle<()>, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while !thread.is_finished() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    if thread.join().is_err() {
+        warn!("roll source thread panicked");
+    }
+    true
+}
+
+/// What the reading thread found in standard input.
+enum Reading {
+    Roll(Roll),
+    /// A value that isn't a face, which has already been reported.
+    Invalid,
+}
+
+/// Rolls typed or piped into standard input as face numbers, any number per line.
+pub struct StdinSource {
+    receiver: Receiver<Reading>,
+    finished: bool,
+    /// Values received since the last `take_invalid` that weren't faces.
+    invalid: u64,
+    /// Tells the reading thread to stop sending what it reads.
+    stopping: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl StdinSource {
+    /// Start reading standard input on a background thread.
+    pub fn spawn(sides: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let stopping = Arc::new(AtomicBool::new(false));
+        let thread_stopping = Arc::clone(&stopping);
+        let thread = std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                // Reading blocks, so a line may arrive after being asked to stop.
+                if thread_stopping.load(Ordering::Relaxed) {
+                    return;
+                }
+                let line = match line {
+                    Ok(line) => line,
+                    Err(err) => {
+                        warn!("stopped reading rolls from stdin: {err}");
+                        return;
+                    }
+                };
+                for value in line.split_whitespace() {
+                    let reading = match value.parse::<usize>() {
+                        Ok(face @ 1..) if face <= sides => Reading::Roll(Roll {
+                            roll_key: face - 1,
+                            arrived: Instant::now(),
+                        }),
+                        _ => {
+                            warn!(
+                                "ignoring {value:?} from stdin; expected a face from 1 to {sides}"
+                            );
+                            Reading::Invalid
+                        }
+                    };
+                    if sender.send(reading).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Self {
+            receiver,
+            finished: false,
+            invalid: 0,
+            stopping,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl RollSource for StdinSource {
+    fn name(&self) -> &'static str {
+        "stdin"
+    }
+
+    fn is_external(&self) -> bool {
+        true
+    }
+
+    fn poll(&mut self, rolls: &mut Vec<Roll>) {
+        while !self.finished {
+            match self.receiver.try_recv() {
+                Ok(Reading::Roll(roll)) => rolls.push(roll),
+                Ok(Reading::Invalid) => self.invalid += 1,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    warn!("stdin closed; no more rolls will arrive");
+                    self.finished = true;
+                }
+            }
+        }
+    }
+
+    fn take_invalid(&mut self) -> u64 {
+        std::mem::take(&mut self.invalid)
+    }
+
+    fn stop(&mut self, timeout: Duration) {
+        self.stopping.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            if !join_within(thread, timeout) {
+                info!("stdin is still being read; the next line will be ignored");
+            }
+        }
+    }
+}
+
+/// Rolls from any random number generator, made as fast as they're asked for, such as to compare
+/// generators.
+pub struct GeneratedSource<R> {
+    name: &'static str,
+    rng: R,
+    sides: usize,
+    /// Rolls made on each poll.
+    batch: usize,
+}
+
+impl<R: RngCore> GeneratedSource<R> {
+    pub fn new(name: &'static str, rng: R, sides: usize, batch: usize) -> Self {
+        Self {
+            name,
+            rng,
+            sides,
+            batch,
+        }
+    }
+}
+
+impl<R: RngCore> RollSource for GeneratedSource<R> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn is_external(&self) -> bool {
+        false
+    }
+
+    fn poll(&mut self, rolls: &mut Vec<Roll>) {
+        let arrived = Instant::now();
+        for _ in 0..self.batch {
+            rolls.push(Roll {
+                roll_key: self.rng.gen_range(0..self.sides),
+                arrived,
+            });
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Detection of faces that stop coming up, as a stuck random number generator would cause.
+
+/// Whether a face has gone unrolled for a whole trailing window of rolls.
+///
+/// Once stalled, a face has to keep turning up for half a window before the alarm clears, so a
+/// face hovering right at the edge of the window doesn't flap in and out of the alarm.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Stall {
+    #[default]
+    Clear,
+    Stalled,
+    /// Rolled again after stalling, at roll number `since`.
+    Recovering {
+        since: u64,
+    },
+}
+
+/// What changed in the last `Stall::update`.
+#[derive(PartialEq, Eq)]
+pub enum Change {
+    None,
+    Stalled,
+    Recovered,
+}
+
+impl Stall {
+    /// Whether the face should be shown as alarming.
+    pub fn is_alarming(self) -> bool {
+        self != Self::Clear
+    }
+
+    /// Advance the state given how many rolls it's been since the face came up, out of
+    /// `total_rolls`, with a trailing window of `window` rolls.
+    pub fn update(&mut self, rolls_since_seen: u64, total_rolls: u64, window: u64) -> Change {
+        let stalled = window > 0 && rolls_since_seen >= window;
+        match *self {
+            Self::Clear if stalled => {
+                *self = Self::Stalled;
+                Change::Stalled
+            }
+            Self::Clear => Change::None,
+            Self::Stalled if !stalled => {
+                *self = Self::Recovering {
+                    since: total_rolls - rolls_since_seen,
+                };
+                Change::None
+            }
+            Self::Stalled => Change::None,
+            Self::Recovering { .. } if stalled => {
+                *self = Self::Stalled;
+                Change::None
+            }
+            Self::Recovering { since } if total_rolls - since >= window / 2 => {
+                *self = Self::Clear;
+                Change::Recovered
+            }
+            Self::Recovering { .. } => Change::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stalls_exactly_at_the_window() {
+        let mut stall = Stall::default();
+        assert!(stall.update(199, 1_000, 200) == Change::None);
+        assert!(!stall.is_alarming());
+        assert!(stall.update(200, 1_001, 200) == Change::Stalled);
+        assert!(stall.is_alarming());
+    }
+
+    #[test]
+    fn zero_window_never_stalls() {
+        let mut stall = Stall::default();
+        assert!(stall.update(u64::MAX, u64::MAX, 0) == Change::None);
+        assert!(!stall.is_alarming());
+    }
+
+    #[test]
+    fn clears_after_half_a_window() {
+        let mut stall = Stall::Stalled;
+        // Rolled again at roll 1,000.
+        assert!(stall.update(0, 1_000, 200) == Change::None);
+        assert!(stall == Stall::Recovering { since: 1_000 });
+        // Still within half a window, and still alarming.
+        assert!(stall.update(10, 1_099, 200) == Change::None);
+        assert!(stall.is_alarming());
+        assert!(stall.update(10, 1_100, 200) == Change::Recovered);
+        assert!(!stall.is_alarming());
+    }
+
+    #[test]
+    fn stalling_again_while_recovering_stays_quiet() {
+        let mut stall = Stall::Recovering { since: 1_000 };
+        assert!(stall.update(200, 1_050, 200) == Change::None);
+        assert!(stall == Stall::Stalled);
+    }
+}
@@ -0,0 +1,75 @@
+//! A self-describing image of the final distribution, saved on exit.
+
+use crate::metrics::{self, Metrics};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Where the summary is saved when `--summary` is given without a path.
+pub const DEFAULT_PATH: &str = "d20-summary.png";
+
+/// The statistics printed on the summary, with faces numbered from `first_face`.
+pub fn lines(metrics: &Metrics, first_face: usize) -> Vec<String> {
+    let sides = metrics.shares.len();
+    let mut lines = vec![
+        format!("Sides: {sides}"),
+        format!("Total rolls: {}", metrics.total),
+    ];
+    if metrics.total == 0 {
+        lines.push("No rolls yet".to_string());
+        return lines;
+    }
+    let chi_square = metrics.chi_square();
+    let critical_value = metrics::chi_square_critical_value(sides);
+    lines.push(format!("Mean roll: {:.3}", metrics.mean_face(first_face)));
+    lines.push(format!(
+        "Chi-square: {chi_square:.2} (5% at {critical_value:.2})"
+    ));
+    lines.push(format!("Verdict: {}", verdict(metrics)));
+    lines
+}
+
+/// Whether the chi-square test at the 5% level suggests the die is fair.
+pub fn verdict(metrics: &Metrics) -> &'static str {
+    let critical_value = metrics::chi_square_critical_value(metrics.shares.len());
+    if metrics.chi_square() > critical_value {
+        "unlikely to be fair"
+    } else {
+        "consistent with a fair die"
+    }
+}
+
+/// Encode an RGBA `frame` of `width` by `height` pixels as a PNG at `path`.
+pub fn save(path: &Path, width: u32, height: u32, frame: &[u8]) -> Result<(), Error> {
+    let file = File::create(path).map_err(Error::Io)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(Error::Png)?;
+    writer.write_image_data(frame).map_err(Error::Png)?;
+    writer.finish().map_err(Error::Png)
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Png(png::EncodingError),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "could not create the summary"),
+            Self::Png(_) => write!(f, "could not encode the summary"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Png(err) => Some(err),
+        }
+    }
+}
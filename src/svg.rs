@@ -0,0 +1,165 @@
+//! A vector bar chart of the counts, for documents that need one that scales.
+
+use crate::metrics::Metrics;
+use crate::summary;
+use crate::theme::{format_hex_color, Theme};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Where charts are written when no path is given.
+pub const DEFAULT_PATH: &str = "d20-chart.svg";
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 500;
+const MARGIN: u32 = 20;
+/// Room left of the plot for the count axis's numbers and title.
+const AXIS_WIDTH: u32 = 80;
+/// Room below the plot for the face labels and the axis title.
+const AXIS_HEIGHT: u32 = 50;
+const FONT_SIZE: u32 = 14;
+const LINE_HEIGHT: u32 = 18;
+/// Marks along the count axis, not counting zero.
+const TICKS: u64 = 4;
+
+/// Write a chart of `counts`, one bar per face with its label, to `path`. The mean counts faces
+/// from `first_face`.
+pub fn save(
+    path: &Path,
+    labels: &[String],
+    counts: &[u64],
+    first_face: usize,
+    theme: &Theme,
+) -> std::io::Result<()> {
+    std::fs::write(path, render(labels, counts, first_face, theme))
+}
+
+pub fn render(labels: &[String], counts: &[u64], first_face: usize, theme: &Theme) -> String {
+    let metrics = Metrics::compute(counts);
+    let lines = summary::lines(&metrics, first_face);
+    let text = format_hex_color(theme.text);
+    let plot_left = MARGIN + AXIS_WIDTH;
+    let plot_top = MARGIN + LINE_HEIGHT * lines.len() as u32 + MARGIN;
+    let plot_width = WIDTH - plot_left - MARGIN;
+    let plot_height = HEIGHT - plot_top - AXIS_HEIGHT - MARGIN;
+    let plot_bottom = plot_top + plot_height;
+    // Leave headroom so the tallest bar doesn't touch the top, and avoid dividing by zero.
+    let scale_max = (metrics.max + metrics.max / 10).max(TICKS);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="{FONT_SIZE}">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{WIDTH}" height="{HEIGHT}" fill="{}"/>"#,
+        format_hex_color(theme.background)
+    );
+    for (index, line) in lines.iter().enumerate() {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{MARGIN}" y="{}" fill="{text}">{}</text>"#,
+            MARGIN + LINE_HEIGHT * (index as u32 + 1),
+            escape(line)
+        );
+    }
+
+    // Count axis, with evenly spaced marks.
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{plot_left}" y1="{plot_top}" x2="{plot_left}" y2="{plot_bottom}" stroke="{text}"/>"#
+    );
+    for tick in 0..=TICKS {
+        let count = scale_max * tick / TICKS;
+        let y = plot_bottom as f64 - plot_height as f64 * tick as f64 / TICKS as f64;
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{y:.1}" fill="{text}" text-anchor="end" dominant-baseline="middle">{count}</text>"#,
+            plot_left - 6
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"<text transform="translate({}, {}) rotate(-90)" fill="{text}" text-anchor="middle">Rolls</text>"#,
+        MARGIN + FONT_SIZE / 2,
+        plot_top + plot_height / 2
+    );
+
+    // Face axis, with a bar and label per face.
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{plot_left}" y1="{plot_bottom}" x2="{}" y2="{plot_bottom}" stroke="{text}"/>"#,
+        plot_left + plot_width
+    );
+    let slot = plot_width as f64 / counts.len().max(1) as f64;
+    for (roll_key, &count) in counts.iter().enumerate() {
+        let height = plot_height as f64 * count as f64 / scale_max as f64;
+        let x = plot_left as f64 + slot * roll_key as f64;
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{height:.1}" fill="{}"/>"#,
+            x + slot * 0.1,
+            plot_bottom as f64 - height,
+            slot * 0.8,
+            format_hex_color(theme.bars[roll_key % theme.bars.len()])
+        );
+        let label = labels.get(roll_key).map_or("", String::as_str);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{}" fill="{text}" text-anchor="middle">{}</text>"#,
+            x + slot / 2.,
+            plot_bottom + LINE_HEIGHT,
+            escape(label)
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" fill="{text}" text-anchor="middle">Face</text>"#,
+        plot_left + plot_width / 2,
+        plot_bottom + LINE_HEIGHT * 2 + 4
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Make `text` safe to put between tags.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(sides: usize) -> Vec<String> {
+        (1..=sides).map(|face| face.to_string()).collect()
+    }
+
+    #[test]
+    fn one_rect_per_face_plus_the_background() {
+        for sides in [2, 6, 20] {
+            let counts: Vec<u64> = (0..sides as u64).map(|roll_key| roll_key * 3).collect();
+            let svg = render(&labels(sides), &counts, 1, &Theme::default());
+            assert_eq!(svg.matches("<rect").count(), sides + 1, "d{sides}");
+        }
+    }
+
+    #[test]
+    fn renders_with_no_rolls() {
+        let svg = render(&labels(20), &[0; 20], 1, &Theme::default());
+        assert_eq!(svg.matches("<rect").count(), 21);
+        assert!(!svg.contains("NaN"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn escapes_labels() {
+        let labels = vec!["<crit>".to_string(), "a&b".to_string()];
+        let svg = render(&labels, &[1, 2], 1, &Theme::default());
+        assert!(svg.contains("&lt;crit&gt;"));
+        assert!(svg.contains("a&amp;b"));
+        assert!(!svg.contains("<crit>"));
+    }
+}
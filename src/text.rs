@@ -0,0 +1,319 @@
+//! Minimal bitmap text rendering straight into the RGBA frame buffer.
+//!
+//! Glyphs are 5x7 pixels, stored one row per byte with the leftmost pixel in bit 4, and are
+//! drawn at an integer scale with one (scaled) pixel of spacing between characters.
+
+pub const GLYPH_WIDTH: u32 = 5;
+pub const GLYPH_HEIGHT: u32 = 7;
+
+/// Glyphs for printable ASCII (`' '..='~'`). Other than `…`, anything else is drawn as `?`.
+const GLYPHS: [[u8; 7]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04], // !
+    [0x0a, 0x0a, 0x0a, 0x00, 0x00, 0x00, 0x00], // "
+    [0x0a, 0x0a, 0x1f, 0x0a, 0x1f, 0x0a, 0x0a], // #
+    [0x04, 0x0f, 0x14, 0x0e, 0x05, 0x1e, 0x04], // $
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03], // %
+    [0x0c, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0d], // &
+    [0x0c, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00], // '
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02], // (
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08], // )
+    [0x00, 0x04, 0x15, 0x0e, 0x15, 0x04, 0x00], // *
+    [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00], // +
+    [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08], // ,
+    [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00], // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c], // .
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00], // /
+    [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e], // 0
+    [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e], // 1
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f], // 2
+    [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e], // 3
+    [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02], // 4
+    [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e], // 5
+    [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e], // 6
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // 7
+    [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e], // 8
+    [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c], // 9
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00], // :
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x04, 0x08], // ;
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02], // <
+    [0x00, 0x00, 0x1f, 0x00, 0x1f, 0x00, 0x00], // =
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08], // >
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // ?
+    [0x0e, 0x11, 0x01, 0x0d, 0x15, 0x15, 0x0e], // @
+    [0x0e, 0x11, 0x11, 0x11, 0x1f, 0x11, 0x11], // A
+    [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e], // B
+    [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e], // C
+    [0x1c, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1c], // D
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f], // E
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10], // F
+    [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f], // G
+    [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // H
+    [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // I
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c], // J
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // K
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f], // L
+    [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11], // M
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // N
+    [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // O
+    [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10], // P
+    [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d], // Q
+    [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11], // R
+    [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e], // S
+    [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // T
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // U
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04], // V
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a], // W
+    [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11], // X
+    [0x11, 0x11, 0x11, 0x0a, 0x04, 0x04, 0x04], // Y
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f], // Z
+    [0x0e, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0e], // [
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00], // \
+    [0x0e, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0e], // ]
+    [0x04, 0x0a, 0x11, 0x00, 0x00, 0x00, 0x00], // ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1f], // _
+    [0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00], // `
+    [0x00, 0x00, 0x0e, 0x01, 0x0f, 0x11, 0x0f], // a
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1e], // b
+    [0x00, 0x00, 0x0e, 0x10, 0x10, 0x11, 0x0e], // c
+    [0x01, 0x01, 0x0d, 0x13, 0x11, 0x11, 0x0f], // d
+    [0x00, 0x00, 0x0e, 0x11, 0x1f, 0x10, 0x0e], // e
+    [0x06, 0x09, 0x08, 0x1c, 0x08, 0x08, 0x08], // f
+    [0x00, 0x0f, 0x11, 0x11, 0x0f, 0x01, 0x0e], // g
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11], // h
+    [0x04, 0x00, 0x0c, 0x04, 0x04, 0x04, 0x0e], // i
+    [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0c], // j
+    [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12], // k
+    [0x0c, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // l
+    [0x00, 0x00, 0x1a, 0x15, 0x15, 0x11, 0x11], // m
+    [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11], // n
+    [0x00, 0x00, 0x0e, 0x11, 0x11, 0x11, 0x0e], // o
+    [0x00, 0x00, 0x1e, 0x11, 0x1e, 0x10, 0x10], // p
+    [0x00, 0x00, 0x0d, 0x13, 0x0f, 0x01, 0x01], // q
+    [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10], // r
+    [0x00, 0x00, 0x0e, 0x10, 0x0e, 0x01, 0x1e], // s
+    [0x08, 0x08, 0x1c, 0x08, 0x08, 0x09, 0x06], // t
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0d], // u
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x0a, 0x04], // v
+    [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0a], // w
+    [0x00, 0x00, 0x11, 0x0a, 0x04, 0x0a, 0x11], // x
+    [0x00, 0x00, 0x11, 0x11, 0x0f, 0x01, 0x0e], // y
+    [0x00, 0x00, 0x1f, 0x02, 0x04, 0x08, 0x1f], // z
+    [0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02], // {
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // |
+    [0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08], // }
+    [0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00], // ~
+];
+
+const ELLIPSIS: [u8; 7] = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x15];
+
+const TIMES: [u8; 7] = [0x00, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x00];
+
+fn glyph(c: char) -> &'static [u8; 7] {
+    let index = match c {
+        '…' => return &ELLIPSIS,
+        '×' => return &TIMES,
+        ' '..='~' => c as usize - ' ' as usize,
+        _ => '?' as usize - ' ' as usize,
+    };
+    &GLYPHS[index]
+}
+
+/// Width in pixels of `text` when drawn at `scale`.
+pub fn text_width(text: &str, scale: u32) -> u32 {
+    let chars = text.chars().count() as u32;
+    if chars == 0 {
+        0
+    } else {
+        (chars * (GLYPH_WIDTH + 1) - 1) * scale
+    }
+}
+
+/// `text`, cut short with an ellipsis if needed so it fits within `max_width` pixels.
+pub fn truncate_to_width(text: &str, scale: u32, max_width: u32) -> std::borrow::Cow<'_, str> {
+    if text_width(text, scale) <= max_width {
+        return text.into();
+    }
+    let mut truncated = text.to_string();
+    while truncated.pop().is_some() {
+        truncated.push('…');
+        if text_width(&truncated, scale) <= max_width {
+            return truncated.into();
+        }
+        truncated.pop();
+    }
+    truncated.into()
+}
+
+/// Height in pixels of a line of text drawn at `scale`.
+pub fn text_height(scale: u32) -> u32 {
+    GLYPH_HEIGHT * scale
+}
+
+/// Draw `text` with its top-left corner at (`x`, `y`), clipping anything outside the frame.
+pub fn draw_text(
+    frame: &mut [u8],
+    frame_width: u32,
+    x: i32,
+    y: i32,
+    scale: u32,
+    color: [u8; 4],
+    text: &str,
+) {
+    let frame_height = (frame.len() / 4) as i32 / frame_width.max(1) as i32;
+    let scale = scale as i32;
+    for (pixel_x, pixel_y) in lit_pixels(text, x, y, scale) {
+        fill_rect_clipped(
+            frame,
+            frame_width,
+            frame_height,
+            pixel_x,
+            pixel_y,
+            scale,
+            scale,
+            color,
+        );
+    }
+}
+
+/// Draw `text` like `draw_text`, but only over pixels still set to `background`, so it shows
+/// through behind anything already drawn.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_text_behind(
+    frame: &mut [u8],
+    frame_width: u32,
+    x: i32,
+    y: i32,
+    scale: u32,
+    color: [u8; 4],
+    background: [u8; 4],
+    text: &str,
+) {
+    let frame_height = (frame.len() / 4) as i32 / frame_width.max(1) as i32;
+    let scale = scale as i32;
+    for (block_x, block_y) in lit_pixels(text, x, y, scale) {
+        for pixel_y in block_y.max(0)..(block_y + scale).min(frame_height) {
+            for pixel_x in block_x.max(0)..(block_x + scale).min(frame_width as i32) {
+                let index = (pixel_y as usize * frame_width as usize + pixel_x as usize) * 4;
+                let pixel = &mut frame[index..index + 4];
+                if *pixel == background {
+                    pixel.copy_from_slice(&color);
+                }
+            }
+        }
+    }
+}
+
+/// Top-left corner of every lit glyph pixel, scaled, in `text` drawn from (`x`, `y`).
+fn lit_pixels(text: &str, x: i32, y: i32, scale: i32) -> impl Iterator<Item = (i32, i32)> + '_ {
+    text.chars().enumerate().flat_map(move |(char_index, c)| {
+        let glyph_x = x + char_index as i32 * (GLYPH_WIDTH as i32 + 1) * scale;
+        glyph(c).iter().enumerate().flat_map(move |(row, &bits)| {
+            (0..GLYPH_WIDTH as i32)
+                .filter(move |column| bits & (0x10 >> column) != 0)
+                .map(move |column| (glyph_x + column * scale, y + row as i32 * scale))
+        })
+    })
+}
+
+/// Draw `lines` on a padded, filled panel with its top-left corner at (`x`, `y`).
+///
+/// Use `text_box_size` to position the panel before drawing it.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_text_box(
+    frame: &mut [u8],
+    frame_width: u32,
+    x: i32,
+    y: i32,
+    scale: u32,
+    color: [u8; 4],
+    background: [u8; 4],
+    lines: &[&str],
+) {
+    let frame_height = (frame.len() / 4) as i32 / frame_width.max(1) as i32;
+    let (width, height) = text_box_size(scale, lines);
+    fill_rect_clipped(
+        frame,
+        frame_width,
+        frame_height,
+        x,
+        y,
+        width as i32,
+        height as i32,
+        background,
+    );
+    let padding = (2 * scale) as i32;
+    let line_height = (text_height(scale) + 2 * scale) as i32;
+    for (line_index, line) in lines.iter().enumerate() {
+        draw_text(
+            frame,
+            frame_width,
+            x + padding,
+            y + padding + line_index as i32 * line_height,
+            scale,
+            color,
+            line,
+        );
+    }
+}
+
+/// Size in pixels of the panel `draw_text_box` would draw for `lines`.
+pub fn text_box_size(scale: u32, lines: &[&str]) -> (u32, u32) {
+    let padding = 2 * scale;
+    let line_height = text_height(scale) + 2 * scale;
+    let widest = lines
+        .iter()
+        .map(|line| text_width(line, scale))
+        .max()
+        .unwrap_or(0);
+    (
+        widest + 2 * padding,
+        lines.len() as u32 * line_height - 2 * scale + 2 * padding,
+    )
+}
+
+/// Fill a rectangle with its top-left corner at (`x`, `y`), clipping anything outside the frame.
+pub fn fill_rect(
+    frame: &mut [u8],
+    frame_width: u32,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    color: [u8; 4],
+) {
+    let frame_height = (frame.len() / 4) as i32 / frame_width.max(1) as i32;
+    fill_rect_clipped(
+        frame,
+        frame_width,
+        frame_height,
+        x,
+        y,
+        width as i32,
+        height as i32,
+        color,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fill_rect_clipped(
+    frame: &mut [u8],
+    frame_width: u32,
+    frame_height: i32,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    color: [u8; 4],
+) {
+    let x_start = x.max(0);
+    let x_end = (x + width).min(frame_width as i32);
+    let y_start = y.max(0);
+    let y_end = (y + height).min(frame_height);
+    for pixel_y in y_start..y_end {
+        for pixel_x in x_start..x_end {
+            let index = (pixel_y as usize * frame_width as usize + pixel_x as usize) * 4;
+            frame[index..index + 4].copy_from_slice(&color);
+        }
+    }
+}
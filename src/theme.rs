@@ -0,0 +1,439 @@
+/// The brightest bar in the default theme, and the accent used when the desktop has none.
+pub const DEFAULT_BAR: [u8; 4] = [0x00, 0xb4, 0xb4, 0xff];
+
+/// Colors used when drawing, all as RGBA.
+#[derive(Clone, PartialEq)]
+pub struct Theme {
+    pub background: [u8; 4],
+    pub winner: [u8; 4],
+    pub loser: [u8; 4],
+    pub overdue: [u8; 4],
+    /// The expected count for a fair die, drawn beside each observed bar.
+    pub expected: [u8; 4],
+    /// Saved snapshots drawn behind the bars for comparison.
+    pub ghost: [u8; 4],
+    /// Outlines faces that have stopped coming up.
+    pub alarm: [u8; 4],
+    /// Outlines the fair simulation run alongside a physical die.
+    pub simulated: [u8; 4],
+    pub text: [u8; 4],
+    /// Backing for tooltips and other floating text.
+    pub panel: [u8; 4],
+    /// One color per face, ramping up to the configured bar color.
+    pub bars: [[u8; 4]; 20],
+    /// How bright bars outside the spotlight are drawn, from 0 (invisible) to 1 (unchanged).
+    pub dim: f64,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: [0x33, 0x33, 0x33, 0xff],
+            winner: [0x33, 0xcc, 0x33, 0xff],
+            loser: [0xcc, 0x33, 0x33, 0xff],
+            overdue: [0xcc, 0x99, 0x33, 0xff],
+            expected: [0x77, 0x77, 0x77, 0xff],
+            ghost: [0xdd, 0xdd, 0xff, 0xff],
+            alarm: [0xff, 0x22, 0x22, 0xff],
+            simulated: [0xff, 0xff, 0xff, 0xff],
+            text: [0xcc, 0xcc, 0xcc, 0xff],
+            panel: [0x11, 0x11, 0x11, 0xff],
+            bars: bar_ramp(DEFAULT_BAR),
+            dim: 0.3,
+        }
+    }
+}
+
+/// Winner and loser colors that stay apart for the common kinds of color blindness, to try in
+/// place of the theme's own.
+pub struct HighlightPair {
+    pub name: &'static str,
+    pub winner: [u8; 4],
+    pub loser: [u8; 4],
+}
+
+/// Pairs from the Okabe–Ito palette.
+pub const HIGHLIGHT_PAIRS: [HighlightPair; 4] = [
+    HighlightPair {
+        name: "blue and orange",
+        winner: [0x00, 0x72, 0xb2, 0xff],
+        loser: [0xe6, 0x9f, 0x00, 0xff],
+    },
+    HighlightPair {
+        name: "bluish green and vermilion",
+        winner: [0x00, 0x9e, 0x73, 0xff],
+        loser: [0xd5, 0x5e, 0x00, 0xff],
+    },
+    HighlightPair {
+        name: "sky blue and reddish purple",
+        winner: [0x56, 0xb4, 0xe9, 0xff],
+        loser: [0xcc, 0x79, 0xa7, 0xff],
+    },
+    HighlightPair {
+        name: "yellow and blue",
+        winner: [0xf0, 0xe4, 0x42, 0xff],
+        loser: [0x00, 0x72, 0xb2, 0xff],
+    },
+];
+
+/// Evenly spaced steps from near-black up to `brightest`, one per face.
+pub fn bar_ramp(brightest: [u8; 4]) -> [[u8; 4]; 20] {
+    let mut bars = [[0; 4]; 20];
+    for (roll_key, bar) in bars.iter_mut().enumerate() {
+        for channel in 0..3 {
+            bar[channel] = (brightest[channel] as usize * (roll_key + 1) / 20) as u8;
+        }
+        bar[3] = brightest[3];
+    }
+    bars
+}
+
+/// One color per face in `hue`, in evenly spaced steps of perceived lightness: from dark up to
+/// light on a `dark` background, and from light down to dark otherwise.
+///
+/// Steps are taken in Oklab, which tracks perceived lightness closely enough to stand in for
+/// HSLuv. Each step gets as much of a fixed chroma as fits in sRGB, so none is clipped.
+pub fn accent_ramp(hue: f64, dark: bool) -> [[u8; 4]; 20] {
+    const DARKEST: f64 = 0.3;
+    const LIGHTEST: f64 = 0.85;
+    const CHROMA: f64 = 0.15;
+    let mut bars = [[0; 4]; 20];
+    for (roll_key, bar) in bars.iter_mut().enumerate() {
+        let step = roll_key as f64 / 19.;
+        let lightness = if dark {
+            DARKEST + (LIGHTEST - DARKEST) * step
+        } else {
+            LIGHTEST - (LIGHTEST - DARKEST) * step
+        };
+        // Gray always fits, so shrinking the chroma ends.
+        let mut chroma = CHROMA;
+        let rgb = loop {
+            let rgb = oklab_to_linear_srgb(lightness, chroma * hue.cos(), chroma * hue.sin());
+            if chroma < 0.001 || rgb.iter().all(|channel| (0. ..=1.).contains(channel)) {
+                break rgb;
+            }
+            chroma *= 0.9;
+        };
+        for (channel, linear) in rgb.into_iter().enumerate() {
+            bar[channel] = (encode_srgb(linear.clamp(0., 1.)) * 255.).round() as u8;
+        }
+        bar[3] = 0xff;
+    }
+    bars
+}
+
+/// A hue in degrees picked from `seed`, so runs with different seeds get different colors
+/// while the same seed always gets the same one. The seed is mixed first, since nearby seeds
+/// are common and would otherwise get nearly the same hue.
+pub fn seed_hue(seed: u64) -> f64 {
+    // The finalizer from SplitMix64.
+    let mut mixed = seed;
+    mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    mixed ^= mixed >> 31;
+    (mixed % 360) as f64
+}
+
+/// Hue of `color` in Oklab, in radians.
+pub fn hue(color: [u8; 4]) -> f64 {
+    let [red, green, blue] = [color[0], color[1], color[2]].map(decode_srgb);
+    let l = (0.412_221_470_8 * red + 0.536_352_132_5 * green + 0.051_445_944_1 * blue).cbrt();
+    let m = (0.211_903_498_2 * red + 0.680_699_545_1 * green + 0.107_396_956_6 * blue).cbrt();
+    let s = (0.088_302_461_9 * red + 0.281_718_837_6 * green + 0.629_978_700_5 * blue).cbrt();
+    let a = 1.977_998_495_1 * l - 2.428_592_205 * m + 0.450_593_709_9 * s;
+    let b = 0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766 * s;
+    b.atan2(a)
+}
+
+fn oklab_to_linear_srgb(lightness: f64, a: f64, b: f64) -> [f64; 3] {
+    let l = (lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+    let m = (lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+    let s = (lightness - 0.089_484_177_5 * a - 1.291_485_548 * b).powi(3);
+    [
+        4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s,
+        -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s,
+        -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701 * s,
+    ]
+}
+
+/// Kinds of color blindness whose view of a palette can be previewed.
+#[derive(Clone, Copy)]
+pub enum Deficiency {
+    /// No red cones.
+    Protanopia,
+    /// No green cones.
+    Deuteranopia,
+    /// No blue cones.
+    Tritanopia,
+}
+
+impl Deficiency {
+    pub const ALL: [Self; 3] = [Self::Protanopia, Self::Deuteranopia, Self::Tritanopia];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Protanopia => "Protanopia",
+            Self::Deuteranopia => "Deuteranopia",
+            Self::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// Machado, Oliveira, and Fernandes's matrix for the deficiency at full severity, in linear
+    /// sRGB.
+    fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            Self::Protanopia => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            Self::Deuteranopia => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            Self::Tritanopia => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+        }
+    }
+}
+
+/// How `color` looks to someone with `deficiency`, keeping its alpha.
+pub fn simulate(color: [u8; 4], deficiency: Deficiency) -> [u8; 4] {
+    let linear = [color[0], color[1], color[2]].map(decode_srgb);
+    let mut simulated = [0, 0, 0, color[3]];
+    for (channel, row) in deficiency.matrix().iter().enumerate() {
+        let value = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+        simulated[channel] = (encode_srgb(value.clamp(0., 1.)) * 255.).round() as u8;
+    }
+    simulated
+}
+
+fn decode_srgb(channel: u8) -> f64 {
+    let encoded = channel as f64 / 255.;
+    if encoded <= 0.040_45 {
+        encoded / 12.92
+    } else {
+        ((encoded + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn encode_srgb(linear: f64) -> f64 {
+    if linear <= 0.003_130_8 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1. / 2.4) - 0.055
+    }
+}
+
+/// Mix `amount` of `to` into `from`.
+pub fn blend(from: [u8; 4], to: [u8; 4], amount: f64) -> [u8; 4] {
+    let mut color = [0; 4];
+    for channel in 0..4 {
+        color[channel] =
+            (from[channel] as f64 + (to[channel] as f64 - from[channel] as f64) * amount) as u8;
+    }
+    color
+}
+
+/// Move each channel of `from` toward `to` by at most `step`, stopping once it gets there.
+pub fn fade(from: [u8; 4], to: [u8; 4], step: u8) -> [u8; 4] {
+    let mut color = [0; 4];
+    for channel in 0..4 {
+        color[channel] = if from[channel] > to[channel] {
+            from[channel].saturating_sub(step).max(to[channel])
+        } else {
+            from[channel].saturating_add(step).min(to[channel])
+        };
+    }
+    color
+}
+
+/// Format `color` as `#rrggbb`, or `#rrggbbaa` if it isn't opaque.
+pub fn format_hex_color(color: [u8; 4]) -> String {
+    let [red, green, blue, alpha] = color;
+    if alpha == 0xff {
+        format!("#{red:02x}{green:02x}{blue:02x}")
+    } else {
+        format!("#{red:02x}{green:02x}{blue:02x}{alpha:02x}")
+    }
+}
+
+/// Parse `#rrggbb` or `#rrggbbaa` (the `#` is optional).
+pub fn parse_hex_color(hex: &str) -> Result<[u8; 4], InvalidColor> {
+    let invalid = || InvalidColor(hex.to_string());
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    if !(digits.len() == 6 || digits.len() == 8) || !digits.is_ascii() {
+        return Err(invalid());
+    }
+    let mut color = [0xff; 4];
+    for (channel, value) in color.iter_mut().enumerate().take(digits.len() / 2) {
+        *value =
+            u8::from_str_radix(&digits[channel * 2..channel * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(color)
+}
+
+#[derive(Debug)]
+pub struct InvalidColor(pub String);
+
+impl std::fmt::Display for InvalidColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid color {:?}; expected #rrggbb or #rrggbbaa",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidColor {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Perceived lightness of `color` in Oklab, from 0 for black to 1 for white.
+    fn lightness(color: [u8; 4]) -> f64 {
+        let [red, green, blue] = [color[0], color[1], color[2]].map(decode_srgb);
+        let l = (0.412_221_470_8 * red + 0.536_352_132_5 * green + 0.051_445_944_1 * blue).cbrt();
+        let m = (0.211_903_498_2 * red + 0.680_699_545_1 * green + 0.107_396_956_6 * blue).cbrt();
+        let s = (0.088_302_461_9 * red + 0.281_718_837_6 * green + 0.629_978_700_5 * blue).cbrt();
+        0.210_454_255_3 * l + 0.793_617_785 * m - 0.004_072_046_8 * s
+    }
+
+    #[test]
+    fn accent_ramp_steps_evenly_in_lightness() {
+        for degrees in (0..360).step_by(15) {
+            let hue = f64::from(degrees).to_radians();
+            for dark in [true, false] {
+                let bars = accent_ramp(hue, dark);
+                let lightnesses = bars.map(lightness);
+                for pair in lightnesses.windows(2) {
+                    let step = pair[1] - pair[0];
+                    let step = if dark { step } else { -step };
+                    assert!(
+                        (0.02..0.04).contains(&step),
+                        "uneven step of {step} at {degrees} degrees"
+                    );
+                }
+                let (first, last) = if dark { (0.3, 0.85) } else { (0.85, 0.3) };
+                assert!((lightnesses[0] - first).abs() < 0.01);
+                assert!((lightnesses[19] - last).abs() < 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn accent_ramp_stays_in_gamut() {
+        for degrees in (0..360).step_by(15) {
+            let hue = f64::from(degrees).to_radians();
+            for bar in accent_ramp(hue, true) {
+                assert_eq!(bar[3], 0xff);
+                // Fitting the chroma rather than clipping it keeps the hue where it was asked.
+                // Grays have no hue to check.
+                let spread = bar[..3].iter().max().unwrap() - bar[..3].iter().min().unwrap();
+                if spread > 8 {
+                    let off = (super::hue(bar) - hue).rem_euclid(std::f64::consts::TAU);
+                    let off = off.min(std::f64::consts::TAU - off);
+                    assert!(off < 0.1, "hue off by {off} at {degrees} degrees");
+                }
+            }
+        }
+    }
+
+    fn assert_close(actual: [u8; 4], expected: [u8; 4]) {
+        assert!(
+            actual
+                .iter()
+                .zip(expected)
+                .all(|(&actual, expected)| actual.abs_diff(expected) <= 2),
+            "{actual:?} isn't close to {expected:?}"
+        );
+    }
+
+    #[test]
+    fn simulates_the_primaries() {
+        const RED: [u8; 4] = [0xff, 0x00, 0x00, 0xff];
+        const GREEN: [u8; 4] = [0x00, 0xff, 0x00, 0xff];
+        const BLUE: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+        // As Machado, Oliveira, and Fernandes's matrices give them, rounded to 8 bits.
+        let table = [
+            (
+                Deficiency::Protanopia,
+                [[109, 95, 0], [255, 229, 0], [0, 89, 255]],
+            ),
+            (
+                Deficiency::Deuteranopia,
+                [[163, 144, 0], [239, 214, 58], [0, 61, 251]],
+            ),
+            (
+                Deficiency::Tritanopia,
+                [[255, 0, 15], [0, 247, 217], [0, 107, 150]],
+            ),
+        ];
+        for (deficiency, [red, green, blue]) in table {
+            assert_close(simulate(RED, deficiency), [red[0], red[1], red[2], 0xff]);
+            assert_close(
+                simulate(GREEN, deficiency),
+                [green[0], green[1], green[2], 0xff],
+            );
+            assert_close(
+                simulate(BLUE, deficiency),
+                [blue[0], blue[1], blue[2], 0xff],
+            );
+        }
+    }
+
+    #[test]
+    fn simulation_keeps_grays_and_alpha() {
+        for deficiency in Deficiency::ALL {
+            for gray in [0x00, 0x40, 0x80, 0xff] {
+                assert_close(
+                    simulate([gray, gray, gray, 0x80], deficiency),
+                    [gray, gray, gray, 0x80],
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn the_same_seed_gets_the_same_palette() {
+        for seed in [0, 1, 42, u64::MAX] {
+            assert_eq!(seed_hue(seed), seed_hue(seed));
+            let hue = seed_hue(seed).to_radians();
+            assert_eq!(accent_ramp(hue, true), accent_ramp(hue, true));
+        }
+    }
+
+    #[test]
+    fn nearby_seeds_get_different_hues() {
+        let hues: Vec<f64> = (0..1_000).map(seed_hue).collect();
+        assert!(hues.iter().all(|hue| (0. ..360.).contains(hue)));
+        // Spread around the whole wheel.
+        let mut buckets = [0; 36];
+        for hue in &hues {
+            buckets[(hue / 10.) as usize] += 1;
+        }
+        assert!(buckets.iter().all(|&count| count > 10), "{buckets:?}");
+        // And far apart for consecutive seeds, not only for distant ones.
+        let close = hues
+            .windows(2)
+            .filter(|pair| {
+                let apart = (pair[0] - pair[1]).abs();
+                apart.min(360. - apart) < 10.
+            })
+            .count();
+        assert!(
+            close < 100,
+            "{close} consecutive seeds got nearly the same hue"
+        );
+        assert_ne!(
+            accent_ramp(hues[0].to_radians(), true),
+            accent_ramp(hues[1].to_radians(), true)
+        );
+    }
+}
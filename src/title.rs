@@ -0,0 +1,253 @@
+//! The window title, composed from segments kept up to date by different parts of the app.
+
+use std::time::{Duration, Instant};
+
+/// Used when the config doesn't set a title.
+pub const DEFAULT_TEMPLATE: &str = "{app}";
+
+/// Shortest time between composing the title, so frequent changes don't make it flicker.
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A named part of the title, written into the template as `{name}`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Segment {
+    App,
+    Total,
+    Mode,
+    Hover,
+    Alert,
+}
+
+impl Segment {
+    const ALL: [Self; 5] = [Self::App, Self::Total, Self::Mode, Self::Hover, Self::Alert];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::App => "app",
+            Self::Total => "total",
+            Self::Mode => "mode",
+            Self::Hover => "hover",
+            Self::Alert => "alert",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum Piece {
+    Literal(String),
+    Segment(Segment),
+}
+
+/// A title layout such as `"{app} — {total} rolls — {mode}"`.
+#[derive(Clone, PartialEq)]
+pub struct Template {
+    /// The template as written.
+    source: String,
+    pieces: Vec<Piece>,
+}
+
+impl Template {
+    /// Parse `template`, rejecting any placeholder that doesn't name a segment. A `{` without a
+    /// matching `}` is kept as written.
+    pub fn parse(template: &str) -> Result<Self, UnknownPlaceholder> {
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            literal.push_str(&rest[..open]);
+            let Some(close) = rest[open..].find('}') else {
+                rest = &rest[open..];
+                break;
+            };
+            let name = &rest[open + 1..open + close];
+            let segment = Segment::ALL
+                .into_iter()
+                .find(|segment| segment.name() == name)
+                .ok_or_else(|| UnknownPlaceholder(name.to_string()))?;
+            if !literal.is_empty() {
+                pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+            }
+            pieces.push(Piece::Segment(segment));
+            rest = &rest[open + close + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(literal));
+        }
+        Ok(Self {
+            source: template.to_string(),
+            pieces,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Fill in each placeholder with `segment`'s value for it.
+    fn expand<'a>(&self, segment: impl Fn(Segment) -> &'a str) -> String {
+        let mut title = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Literal(text) => title.push_str(text),
+                Piece::Segment(name) => title.push_str(segment(*name)),
+            }
+        }
+        title
+    }
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Self::parse(DEFAULT_TEMPLATE).expect("default title template is valid")
+    }
+}
+
+/// Collects title segments and composes them into the title at most a few times per second.
+pub struct TitleBar {
+    template: Template,
+    segments: [String; Segment::ALL.len()],
+    /// The most recently composed title.
+    title: String,
+    /// When the title was last composed, or `None` if it needs composing right away.
+    composed_at: Option<Instant>,
+}
+
+impl TitleBar {
+    pub fn new(template: Template) -> Self {
+        Self {
+            template,
+            segments: Default::default(),
+            title: String::new(),
+            composed_at: None,
+        }
+    }
+
+    pub fn template(&self) -> &Template {
+        &self.template
+    }
+
+    pub fn set_template(&mut self, template: Template) {
+        self.template = template;
+        self.composed_at = None;
+    }
+
+    pub fn set(&mut self, segment: Segment, value: impl Into<String>) {
+        self.segments[segment as usize] = value.into();
+    }
+
+    /// The title as last composed.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Compose the title if it's been long enough since last time, returning it only if it
+    /// changed.
+    pub fn poll(&mut self, now: Instant) -> Option<&str> {
+        if let Some(composed_at) = self.composed_at {
+            if now.saturating_duration_since(composed_at) < MIN_INTERVAL {
+                return None;
+            }
+        }
+        self.composed_at = Some(now);
+        let title = self
+            .template
+            .expand(|segment| self.segments[segment as usize].as_str());
+        if title == self.title {
+            return None;
+        }
+        self.title = title;
+        Some(&self.title)
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownPlaceholder(pub String);
+
+impl std::fmt::Display for UnknownPlaceholder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<String> = Segment::ALL
+            .iter()
+            .map(|segment| format!("{{{}}}", segment.name()))
+            .collect();
+        write!(
+            f,
+            "unknown placeholder {{{}}}; expected one of {}",
+            self.0,
+            names.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownPlaceholder {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_segments(template: &str) -> TitleBar {
+        let mut title_bar = TitleBar::new(Template::parse(template).unwrap());
+        title_bar.set(Segment::App, "d20");
+        title_bar.set(Segment::Total, "1,234");
+        title_bar.set(Segment::Mode, "Paused");
+        title_bar
+    }
+
+    #[test]
+    fn fills_in_every_placeholder() {
+        let now = Instant::now();
+        let mut title_bar = with_segments("{app} — {total} rolls — {mode}{hover}");
+        assert_eq!(title_bar.poll(now), Some("d20 — 1,234 rolls — Paused"));
+        // Literal text around and between placeholders, and an unclosed brace, are kept.
+        let mut title_bar = with_segments("[{app}] {alert}{total}{ {mode");
+        assert_eq!(title_bar.poll(now), Some("[d20] 1,234{ {mode"));
+        assert_eq!(
+            title_bar.template().as_str(),
+            "[{app}] {alert}{total}{ {mode"
+        );
+    }
+
+    #[test]
+    fn reports_unknown_placeholders() {
+        let Err(err) = Template::parse("{app} {rolls} {mode}") else {
+            panic!("{{rolls}} isn't a segment");
+        };
+        assert_eq!(err.0, "rolls");
+        assert_eq!(
+            err.to_string(),
+            "unknown placeholder {rolls}; expected one of {app}, {total}, {mode}, {hover}, {alert}"
+        );
+        assert_eq!(Template::parse("{}").err().unwrap().0, "");
+        assert_eq!(Template::parse("{App}").err().unwrap().0, "App");
+    }
+
+    #[test]
+    fn only_reports_changes() {
+        let start = Instant::now();
+        let mut title_bar = with_segments("{app} — {total}");
+        assert_eq!(title_bar.poll(start), Some("d20 — 1,234"));
+        // Composed again, but the same as what the window already shows.
+        assert_eq!(title_bar.poll(start + MIN_INTERVAL), None);
+        title_bar.set(Segment::Total, "1,234");
+        assert_eq!(title_bar.poll(start + MIN_INTERVAL * 2), None);
+        title_bar.set(Segment::Total, "1,235");
+        assert_eq!(
+            title_bar.poll(start + MIN_INTERVAL * 3),
+            Some("d20 — 1,235")
+        );
+        assert_eq!(title_bar.title(), "d20 — 1,235");
+    }
+
+    #[test]
+    fn composes_at_most_every_interval() {
+        let start = Instant::now();
+        let mut title_bar = with_segments("{total}");
+        assert_eq!(title_bar.poll(start), Some("1,234"));
+        title_bar.set(Segment::Total, "1,235");
+        assert_eq!(title_bar.poll(start + MIN_INTERVAL / 2), None);
+        assert_eq!(title_bar.poll(start + MIN_INTERVAL), Some("1,235"));
+        // A new template is composed straight away.
+        title_bar.set_template(Template::parse("{app}").unwrap());
+        assert_eq!(title_bar.poll(start + MIN_INTERVAL), Some("d20"));
+    }
+}
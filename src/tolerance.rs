@@ -0,0 +1,103 @@
+//! Checking each face's share of the rolls against a target share and how far either way it's
+//! allowed to stray, as when tuning a loot table's drop rates.
+
+/// How far outside its tolerance a face can stray, as a multiple of the tolerance, and still
+/// count as near rather than outside.
+const NEAR: f64 = 1.5;
+
+/// How much further inside a boundary a face has to come back before it's counted as having
+/// crossed back, as a fraction of the boundary's distance. Keeps a face sitting right at the
+/// edge from flickering between states with every roll.
+const HYSTERESIS: f64 = 0.1;
+
+/// Where a face's share lies relative to its tolerance band, from best to worst.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Fit {
+    #[default]
+    Inside,
+    /// Outside the band, but within half the tolerance again of it.
+    Near,
+    Outside,
+}
+
+impl Fit {
+    /// The fit for a face whose share is `distance` from its target, given `tolerance` either
+    /// way. Getting worse happens as soon as a boundary is crossed, but getting better takes
+    /// coming back past it by a margin.
+    pub fn update(self, distance: f64, tolerance: f64) -> Self {
+        let worse = Self::classify(distance, tolerance);
+        if worse >= self {
+            return worse;
+        }
+        Self::classify(distance, tolerance * (1. - HYSTERESIS)).min(self)
+    }
+
+    fn classify(distance: f64, tolerance: f64) -> Self {
+        if distance <= tolerance {
+            Self::Inside
+        } else if distance <= tolerance * NEAR {
+            Self::Near
+        } else {
+            Self::Outside
+        }
+    }
+}
+
+/// The shares of the rolls, from 0 to 1, at the edges of the band around `expected` allowed
+/// `tolerance` either way.
+pub fn band(expected: f64, tolerance: f64) -> (f64, f64) {
+    (
+        (expected - tolerance).max(0.),
+        (expected + tolerance).min(1.),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f64 = 0.003;
+
+    #[test]
+    fn classifies_from_inside_to_outside() {
+        assert!(Fit::Inside.update(0., TOLERANCE) == Fit::Inside);
+        assert!(Fit::Inside.update(0.003, TOLERANCE) == Fit::Inside);
+        assert!(Fit::Inside.update(0.004, TOLERANCE) == Fit::Near);
+        assert!(Fit::Inside.update(0.0045, TOLERANCE) == Fit::Near);
+        assert!(Fit::Inside.update(0.0046, TOLERANCE) == Fit::Outside);
+    }
+
+    #[test]
+    fn gets_worse_at_once_but_better_only_past_a_margin() {
+        let mut fit = Fit::Inside.update(0.0031, TOLERANCE);
+        assert!(fit == Fit::Near);
+        // Back inside, but not by the hysteresis margin.
+        fit = fit.update(0.0028, TOLERANCE);
+        assert!(fit == Fit::Near);
+        fit = fit.update(0.0026, TOLERANCE);
+        assert!(fit == Fit::Inside);
+    }
+
+    #[test]
+    fn hovering_at_the_edge_does_not_flicker() {
+        let mut fit = Fit::Outside;
+        for distance in [0.0044, 0.0046, 0.0044, 0.0046, 0.0044] {
+            fit = fit.update(distance, TOLERANCE);
+            assert!(fit == Fit::Outside, "{distance}");
+        }
+        fit = fit.update(0.0040, TOLERANCE);
+        assert!(fit == Fit::Near);
+        for distance in [0.0029, 0.0031, 0.0029, 0.0031] {
+            fit = fit.update(distance, TOLERANCE);
+            assert!(fit == Fit::Near, "{distance}");
+        }
+    }
+
+    #[test]
+    fn band_stays_within_shares() {
+        let (low, high) = band(0.05, TOLERANCE);
+        assert!((low - 0.047).abs() < 1e-12 && (high - 0.053).abs() < 1e-12);
+        assert_eq!(band(0.001, TOLERANCE), (0., 0.004));
+        assert_eq!(band(0.999, TOLERANCE).1, 1.);
+    }
+}
@@ -0,0 +1,342 @@
+//! Rolling the same number of dice with every built-in random number generator and ranking
+//! them on the same tests, to see which can be trusted.
+
+use crate::metrics::{self, Metrics};
+use crate::source::{GeneratedSource, Roll, RollSource};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use std::path::Path;
+
+/// Rolls asked of a source at a time.
+const BATCH: usize = 4096;
+
+/// Level at which a test counts as failed.
+const SIGNIFICANCE: f64 = 0.05;
+
+/// How many standard errors from zero the serial correlation can be before it counts as failed.
+const CORRELATION_LIMIT: f64 = 3.;
+
+/// How much longer than usual the longest streak can be before it counts as failed.
+const STREAK_LIMIT: f64 = 3.;
+
+#[derive(Clone, Copy)]
+pub enum Backend {
+    /// ChaCha12, the generator the window rolls with.
+    Std,
+    SplitMix64,
+    Xorshift64,
+    /// A 32-bit linear congruential generator, the kind behind many C library `rand`s.
+    Lcg32,
+}
+
+pub const BACKENDS: [Backend; 4] = [
+    Backend::Std,
+    Backend::SplitMix64,
+    Backend::Xorshift64,
+    Backend::Lcg32,
+];
+
+impl Backend {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Std => "chacha12",
+            Self::SplitMix64 => "splitmix64",
+            Self::Xorshift64 => "xorshift64",
+            Self::Lcg32 => "lcg32",
+        }
+    }
+
+    fn source(self, seed: u64, sides: usize) -> Box<dyn RollSource> {
+        let name = self.name();
+        match self {
+            Self::Std => Box::new(GeneratedSource::new(
+                name,
+                StdRng::seed_from_u64(seed),
+                sides,
+                BATCH,
+            )),
+            Self::SplitMix64 => {
+                Box::new(GeneratedSource::new(name, SplitMix64(seed), sides, BATCH))
+            }
+            // Xorshift gets stuck on a zero state.
+            Self::Xorshift64 => Box::new(GeneratedSource::new(
+                name,
+                Xorshift64(seed.max(1)),
+                sides,
+                BATCH,
+            )),
+            Self::Lcg32 => Box::new(GeneratedSource::new(
+                name,
+                Lcg32(seed as u32 ^ (seed >> 32) as u32),
+                sides,
+                BATCH,
+            )),
+        }
+    }
+}
+
+/// How one generator did.
+pub struct Entry {
+    pub name: &'static str,
+    pub counts: Vec<u64>,
+    /// Chance of a fair die's counts straying at least this far from even.
+    pub p_value: f64,
+    /// Shannon entropy of the faces, in bits.
+    pub entropy: f64,
+    /// Correlation between each roll and the next, which is near 0 if rolls are independent.
+    pub serial_correlation: f64,
+    /// Most rolls in a row that came up the same face.
+    pub longest_streak: u64,
+    /// Names of the tests failed.
+    pub failures: Vec<&'static str>,
+}
+
+/// Roll `rolls` dice with `sides` faces using every backend seeded from `seed`, and rank them
+/// best first: fewest failed tests, then highest p-value.
+pub fn run(rolls: u64, sides: usize, seed: u64) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = BACKENDS
+        .iter()
+        .map(|backend| play(backend.source(seed, sides), rolls, sides))
+        .collect();
+    entries.sort_by(|a, b| {
+        a.failures
+            .len()
+            .cmp(&b.failures.len())
+            .then(b.p_value.total_cmp(&a.p_value))
+    });
+    entries
+}
+
+/// Take `rolls` rolls from `source` and test them.
+fn play(mut source: Box<dyn RollSource>, rolls: u64, sides: usize) -> Entry {
+    let mut counts = vec![0; sides];
+    let mut correlation = SerialCorrelation::default();
+    let mut streak = 0;
+    let mut longest_streak = 0;
+    let mut previous = None;
+    let mut taken = 0;
+    let mut batch = Vec::with_capacity(BATCH);
+    while taken < rolls {
+        batch.clear();
+        source.poll(&mut batch);
+        let wanted = (rolls - taken).min(batch.len() as u64) as usize;
+        for &Roll { roll_key, .. } in &batch[..wanted] {
+            counts[roll_key] += 1;
+            correlation.add(roll_key as f64);
+            streak = if previous == Some(roll_key) {
+                streak + 1
+            } else {
+                1
+            };
+            longest_streak = longest_streak.max(streak);
+            previous = Some(roll_key);
+        }
+        taken += wanted as u64;
+    }
+
+    let metrics = Metrics::compute(&counts);
+    let p_value = metrics.p_value();
+    let serial_correlation = correlation.value();
+    let mut failures = Vec::new();
+    if rolls > 0 && p_value < SIGNIFICANCE {
+        failures.push("chi-square");
+    }
+    if rolls > 1 && serial_correlation.abs() > CORRELATION_LIMIT / (rolls as f64).sqrt() {
+        failures.push("serial correlation");
+    }
+    if longest_streak as f64 > usual_longest_streak(rolls, sides) + STREAK_LIMIT {
+        failures.push("longest streak");
+    }
+    Entry {
+        name: source.name(),
+        counts,
+        p_value,
+        entropy: entropy(&metrics.shares),
+        serial_correlation,
+        longest_streak,
+        failures,
+    }
+}
+
+/// About how long the longest streak of one face is in `rolls` fair rolls.
+fn usual_longest_streak(rolls: u64, sides: usize) -> f64 {
+    let chance = 1. / sides as f64;
+    ((rolls as f64 * (1. - chance)).max(1.)).ln() / (sides as f64).ln()
+}
+
+fn entropy(shares: &[f64]) -> f64 {
+    metrics::compensated_sum(
+        shares
+            .iter()
+            .filter(|&&share| share > 0.)
+            .map(|share| -share * share.log2()),
+    )
+}
+
+/// Lag-one correlation of a sequence, built up one value at a time.
+#[derive(Default)]
+struct SerialCorrelation {
+    count: u64,
+    sum: f64,
+    sum_of_squares: f64,
+    sum_of_products: f64,
+    previous: Option<f64>,
+}
+
+impl SerialCorrelation {
+    fn add(&mut self, value: f64) {
+        if let Some(previous) = self.previous {
+            self.sum_of_products += previous * value;
+        }
+        self.count += 1;
+        self.sum += value;
+        self.sum_of_squares += value * value;
+        self.previous = Some(value);
+    }
+
+    fn value(&self) -> f64 {
+        if self.count < 2 {
+            return 0.;
+        }
+        let count = self.count as f64;
+        let mean = self.sum / count;
+        let variance = self.sum_of_squares / count - mean * mean;
+        if variance <= 0. {
+            return 0.;
+        }
+        let covariance = self.sum_of_products / (count - 1.) - mean * mean;
+        covariance / variance
+    }
+}
+
+/// Print the ranking as a table.
+pub fn print(entries: &[Entry], rolls: u64, seed: u64) {
+    println!("seed {seed}, {rolls} rolls each");
+    println!(
+        "{:<4} {:<12} {:>10} {:>9} {:>12} {:>7}  failed",
+        "rank", "generator", "p-value", "entropy", "serial corr", "streak"
+    );
+    for (rank, entry) in entries.iter().enumerate() {
+        let failed = if entry.failures.is_empty() {
+            "none".to_string()
+        } else {
+            entry.failures.join(", ")
+        };
+        println!(
+            "{:<4} {:<12} {:>10.4} {:>9.5} {:>12.6} {:>7}  {failed}",
+            rank + 1,
+            entry.name,
+            entry.p_value,
+            entry.entropy,
+            entry.serial_correlation,
+            entry.longest_streak
+        );
+    }
+}
+
+/// Write the ranking as JSON, with what's needed to run it again.
+pub fn save(path: &Path, entries: &[Entry], rolls: u64, seed: u64) -> std::io::Result<()> {
+    let entries: Vec<_> = entries
+        .iter()
+        .enumerate()
+        .map(|(rank, entry)| {
+            serde_json::json!({
+                "rank": rank + 1,
+                "generator": entry.name,
+                "counts": entry.counts,
+                "p_value": entry.p_value,
+                "entropy": entry.entropy,
+                "serial_correlation": entry.serial_correlation,
+                "longest_streak": entry.longest_streak,
+                "failures": entry.failures,
+            })
+        })
+        .collect();
+    let json = serde_json::json!({
+        "seed": seed,
+        "rolls": rolls,
+        "generators": entries,
+    });
+    std::fs::write(path, format!("{json:#}\n"))
+}
+
+/// Sebastiano Vigna's SplitMix64.
+struct SplitMix64(u64);
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        fill_bytes(self, dest);
+        Ok(())
+    }
+}
+
+/// George Marsaglia's xorshift64, with shifts 13, 7, and 17.
+struct Xorshift64(u64);
+
+impl RngCore for Xorshift64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        fill_bytes(self, dest);
+        Ok(())
+    }
+}
+
+/// The linear congruential generator from Numerical Recipes, handing out its whole state.
+struct Lcg32(u32);
+
+impl RngCore for Lcg32 {
+    fn next_u32(&mut self) -> u32 {
+        self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        self.0
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (u64::from(self.next_u32()) << 32) | u64::from(self.next_u32())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_bytes(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        fill_bytes(self, dest);
+        Ok(())
+    }
+}
+
+fn fill_bytes(rng: &mut impl RngCore, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(8) {
+        let bytes = rng.next_u64().to_le_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
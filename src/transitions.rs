@@ -0,0 +1,116 @@
+//! How often each face follows each other face, for spotting rolls that depend on the one
+//! before, as a sticky or patterned die's do.
+
+pub struct Transitions {
+    sides: usize,
+    /// Times each face came up straight after each other, by the earlier face, then the later.
+    counts: Vec<u64>,
+    /// Times each face was followed by another, and followed another.
+    from_totals: Vec<u64>,
+    to_totals: Vec<u64>,
+    total: u64,
+}
+
+impl Transitions {
+    pub fn new(sides: usize) -> Self {
+        Self {
+            sides,
+            counts: vec![0; sides * sides],
+            from_totals: vec![0; sides],
+            to_totals: vec![0; sides],
+            total: 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new(self.sides);
+    }
+
+    /// Count `to` coming up straight after `from`.
+    pub fn record(&mut self, from: usize, to: usize) {
+        self.counts[from * self.sides + to] += 1;
+        self.from_totals[from] += 1;
+        self.to_totals[to] += 1;
+        self.total += 1;
+    }
+
+    pub fn sides(&self) -> usize {
+        self.sides
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// How many times `to` followed `from`, over how many it would have if each roll ignored
+    /// the one before, or `None` until that's known. Sticking near 1 everywhere is what a
+    /// memoryless die does, however loaded; this comparison doesn't assume a fair one.
+    pub fn ratio(&self, from: usize, to: usize) -> Option<f64> {
+        let expected =
+            self.from_totals[from] as f64 * self.to_totals[to] as f64 / self.total as f64;
+        (expected > 0.).then(|| self.counts[from * self.sides + to] as f64 / expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng as _, SeedableRng as _};
+
+    const SIDES: usize = 20;
+
+    /// The transitions between `rolls` rolls, each drawn by `next` from the one before.
+    fn roll(rolls: usize, mut next: impl FnMut(usize) -> usize) -> Transitions {
+        let mut transitions = Transitions::new(SIDES);
+        let mut previous = next(0);
+        for _ in 1..rolls {
+            let roll_key = next(previous);
+            transitions.record(previous, roll_key);
+            previous = roll_key;
+        }
+        transitions
+    }
+
+    #[test]
+    fn fair_die_is_roughly_uniform() {
+        let mut rng = StdRng::seed_from_u64(7);
+        // About a thousand of each pair, which chance keeps within a few percent.
+        let transitions = roll(400_000, |_| rng.gen_range(0..SIDES));
+        assert_eq!(transitions.total(), 399_999);
+        for from in 0..SIDES {
+            for to in 0..SIDES {
+                let ratio = transitions.ratio(from, to).unwrap();
+                assert!((0.85..1.15).contains(&ratio), "{from} → {to}: {ratio}");
+            }
+        }
+    }
+
+    #[test]
+    fn sticky_die_shows_on_the_diagonal() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let transitions = roll(100_000, |previous| {
+            if rng.gen_bool(0.5) {
+                previous
+            } else {
+                rng.gen_range(0..SIDES)
+            }
+        });
+        for face in 0..SIDES {
+            assert!(transitions.ratio(face, face).unwrap() > 5.);
+            assert!(transitions.ratio(face, (face + 1) % SIDES).unwrap() < 1.);
+        }
+    }
+
+    #[test]
+    fn unknown_until_rolled() {
+        let mut transitions = Transitions::new(SIDES);
+        assert_eq!(transitions.ratio(0, 1), None);
+        transitions.record(0, 1);
+        assert_eq!(transitions.ratio(0, 1), Some(1.));
+        assert_eq!(transitions.ratio(1, 0), None);
+        transitions.clear();
+        assert_eq!(transitions.total(), 0);
+        assert_eq!(transitions.ratio(0, 1), None);
+    }
+}